@@ -0,0 +1,255 @@
+//! Generator of typed Rust bindings for the contract ABI.
+//!
+//! For the contract `Name` the generator emits:
+//! - `<Function>Input` and `<Function>Output` structs for each ABI function;
+//! - `<Event>Event` struct for each ABI event and the `NameEvent` enum of all events;
+//! - structs for the ABI tuples;
+//! - `Name` client calling `ton_client::abi::encode_message`, `ton_client::processing::process_message`
+//!   and `ton_client::tvm::run_tvm` with the typed inputs and outputs.
+//!
+//! ABI types are mapped to Rust types as follows:
+//! - `uintN`, `intN`, `varuint16`, `varint16` - the smallest Rust integer containing `N` bits,
+//!   `String` if `N` is greater than 128;
+//! - `bool` - `bool`;
+//! - `address`, `cell`, `bytes`, `fixedbytesN`, `string` - `String`;
+//! - `T[]` - `Vec<T>`;
+//! - `map(K,V)` - `BTreeMap<String, V>`;
+//! - `optional(T)` - `Option<T>`;
+//! - `tuple` - generated struct.
+//!
+//! Integers nested in the maps, nested arrays and options are represented as `String`.
+//!
+//! Generated code refers to `ton_client` and `serde` crates, so they must be
+//! the dependencies of the crate using the bindings. The bindings are generated either
+//! with `ton_client::abi::bindings::abi_bindings!` macro or in the build script
+//! with `generate_source` function.
+
+mod types;
+
+#[cfg(test)]
+mod tests;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use types::{camel_case, ident, snake_case, Contract, Function, TypeMapper};
+
+/// Generates bindings for the contract with the specified name and ABI JSON.
+pub fn generate(contract_name: &str, abi_json: &str) -> Result<TokenStream, String> {
+    let contract = Contract::parse(abi_json)?;
+    let mut mapper = TypeMapper::new();
+    let mut items = Vec::new();
+    let mut methods = Vec::new();
+
+    for function in &contract.functions {
+        let name = camel_case(&function.name);
+        items.push(mapper.structure(&format!("{}Input", name), &function.inputs)?);
+        items.push(mapper.structure(&format!("{}Output", name), &function.outputs)?);
+        methods.push(function_methods(function));
+    }
+
+    let event_enum = ident(&format!("{}Event", contract_name));
+    let mut variants = Vec::new();
+    let mut decoders = Vec::new();
+    for event in &contract.events {
+        let struct_name = format!("{}Event", camel_case(&event.name));
+        items.push(mapper.structure(&struct_name, &event.inputs)?);
+        let variant = ident(&camel_case(&event.name));
+        let struct_ident = ident(&struct_name);
+        let abi_name = &event.name;
+        variants.push(quote!(#variant(#struct_ident),));
+        decoders.push(quote! {
+            #abi_name => Ok(#event_enum::#variant(
+                ::ton_client::abi::bindings::decode_output(decoded.value)?
+            )),
+        });
+    }
+    if !variants.is_empty() {
+        let doc = format!("Events of the `{}` contract.", contract_name);
+        items.push(quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, PartialEq)]
+            pub enum #event_enum {
+                #(#variants)*
+            }
+        });
+        methods.push(quote! {
+            /// Decodes the body of the event message emitted by the contract.
+            pub fn decode_event(&self, body: &str) -> ::ton_client::error::ClientResult<#event_enum> {
+                let decoded = ::ton_client::abi::decode_message_body(
+                    self.context.clone(),
+                    ::ton_client::abi::ParamsOfDecodeMessageBody {
+                        abi: self.abi.clone(),
+                        body: body.to_string(),
+                        is_internal: false,
+                        allow_partial: false,
+                        function_name: None,
+                        data_layout: None,
+                    },
+                )?;
+                match decoded.name.as_str() {
+                    #(#decoders)*
+                    name => Err(::ton_client::abi::Error::invalid_message_for_decode(
+                        format!("unknown event {}", name),
+                    )),
+                }
+            }
+        });
+    }
+
+    let structs = &mapper.structs;
+    let client = ident(contract_name);
+    let client_doc = format!("Client of the `{}` contract.", contract_name);
+    Ok(quote! {
+        #(#items)*
+        #(#structs)*
+
+        #[doc = #client_doc]
+        ///
+        /// ABI is registered in the client context while the client exists.
+        pub struct #client {
+            context: ::std::sync::Arc<::ton_client::ClientContext>,
+            abi: ::ton_client::abi::Abi,
+            address: String,
+        }
+
+        impl #client {
+            /// Contract ABI JSON.
+            pub const ABI: &'static str = #abi_json;
+
+            /// Creates the client of the contract deployed at the `address`.
+            pub fn new(
+                context: ::std::sync::Arc<::ton_client::ClientContext>,
+                address: impl Into<String>,
+            ) -> ::ton_client::error::ClientResult<Self> {
+                let registered = ::ton_client::abi::register_abi(
+                    context.clone(),
+                    ::ton_client::abi::ParamsOfRegisterAbi {
+                        abi: ::ton_client::abi::Abi::Json(Self::ABI.to_string()),
+                    },
+                )?;
+                Ok(Self {
+                    context,
+                    abi: ::ton_client::abi::Abi::Handle(registered.handle),
+                    address: address.into(),
+                })
+            }
+
+            #(#methods)*
+        }
+
+        impl Drop for #client {
+            fn drop(&mut self) {
+                if let ::ton_client::abi::Abi::Handle(handle) = &self.abi {
+                    let _ = ::ton_client::abi::remove_abi(
+                        self.context.clone(),
+                        ::ton_client::abi::ParamsOfRemoveAbi { handle: *handle },
+                    );
+                }
+            }
+        }
+    })
+}
+
+/// Generates bindings source code. Intended to be used in the build scripts.
+pub fn generate_source(contract_name: &str, abi_json: &str) -> Result<String, String> {
+    Ok(generate(contract_name, abi_json)?.to_string())
+}
+
+fn function_methods(function: &Function) -> TokenStream {
+    let name = snake_case(&function.name);
+    let camel = camel_case(&function.name);
+    let abi_name = &function.name;
+    let input = ident(&format!("{}Input", camel));
+    let output = ident(&format!("{}Output", camel));
+    let params_fn = ident(&format!("{}_params", name));
+    let encode_fn = ident(&format!("encode_{}", name));
+    let call_fn = ident(&format!("call_{}", name));
+    let run_fn = ident(&format!("run_{}", name));
+    let params_doc = format!("Returns parameters of `encode_message` for the `{}` function.", abi_name);
+    let encode_doc = format!("Encodes the message calling the `{}` function.", abi_name);
+    let call_doc = format!(
+        "Sends the message calling the `{}` function and waits for its transaction.",
+        abi_name
+    );
+    let run_doc = format!(
+        "Runs the `{}` function on the local account state with `run_tvm`.",
+        abi_name
+    );
+    quote! {
+        #[doc = #params_doc]
+        pub fn #params_fn(
+            &self,
+            input: &#input,
+            signer: ::ton_client::abi::Signer,
+        ) -> ::ton_client::error::ClientResult<::ton_client::abi::ParamsOfEncodeMessage> {
+            Ok(::ton_client::abi::ParamsOfEncodeMessage {
+                abi: self.abi.clone(),
+                address: Some(self.address.clone()),
+                call_set: ::ton_client::abi::CallSet::some_with_function_and_input(
+                    #abi_name,
+                    ::ton_client::abi::bindings::encode_input(input)?,
+                ),
+                signer,
+                ..Default::default()
+            })
+        }
+
+        #[doc = #encode_doc]
+        pub async fn #encode_fn(
+            &self,
+            input: &#input,
+            signer: ::ton_client::abi::Signer,
+        ) -> ::ton_client::error::ClientResult<::ton_client::abi::ResultOfEncodeMessage> {
+            ::ton_client::abi::encode_message(self.context.clone(), self.#params_fn(input, signer)?)
+                .await
+        }
+
+        #[doc = #call_doc]
+        pub async fn #call_fn(
+            &self,
+            input: &#input,
+            signer: ::ton_client::abi::Signer,
+        ) -> ::ton_client::error::ClientResult<::ton_client::abi::bindings::CallResult<#output>> {
+            let result = ::ton_client::processing::process_message(
+                self.context.clone(),
+                ::ton_client::processing::ParamsOfProcessMessage {
+                    message_encode_params: self.#params_fn(input, signer)?,
+                    ..Default::default()
+                },
+                |_| async {},
+            )
+            .await?;
+            Ok(::ton_client::abi::bindings::CallResult {
+                output: ::ton_client::abi::bindings::decode_output(
+                    result.decoded.as_ref().and_then(|decoded| decoded.output.clone()),
+                )?,
+                result,
+            })
+        }
+
+        #[doc = #run_doc]
+        pub async fn #run_fn(
+            &self,
+            account: &str,
+            input: &#input,
+        ) -> ::ton_client::error::ClientResult<#output> {
+            let message = self
+                .#encode_fn(input, ::ton_client::abi::Signer::None)
+                .await?
+                .message;
+            let result = ::ton_client::tvm::run_tvm(
+                self.context.clone(),
+                ::ton_client::tvm::ParamsOfRunTvm {
+                    message,
+                    account: account.to_string(),
+                    abi: Some(self.abi.clone()),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            ::ton_client::abi::bindings::decode_output(
+                result.decoded.and_then(|decoded| decoded.output),
+            )
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::generate_source;
+use crate::types::{camel_case, snake_case};
+
+const ABI: &str = r#"{
+    "ABI version": 2,
+    "version": "2.2",
+    "header": ["time", "expire"],
+    "functions": [
+        {
+            "name": "sendTransaction",
+            "inputs": [
+                {"name": "dest", "type": "address"},
+                {"name": "value", "type": "uint128"},
+                {"name": "bounce", "type": "bool"},
+                {"name": "flags", "type": "optional(uint8)"},
+                {"name": "ids", "type": "uint32[]"},
+                {"name": "balances", "type": "map(address,uint256)"},
+                {"name": "info", "type": "tuple", "components": [
+                    {"name": "type", "type": "uint16"},
+                    {"name": "comment", "type": "bytes"}
+                ]}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "getCounter",
+            "inputs": [],
+            "outputs": [{"name": "value0", "type": "int64"}]
+        }
+    ],
+    "events": [
+        {
+            "name": "CounterChanged",
+            "inputs": [{"name": "counter", "type": "int64"}]
+        }
+    ],
+    "data": []
+}"#;
+
+fn squash(source: &str) -> String {
+    source.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+#[test]
+fn test_names() {
+    assert_eq!(snake_case("sendTransaction"), "send_transaction");
+    assert_eq!(snake_case("value0"), "value0");
+    assert_eq!(snake_case("getID"), "get_id");
+    assert_eq!(camel_case("sendTransaction"), "SendTransaction");
+    assert_eq!(camel_case("counter_changed"), "CounterChanged");
+}
+
+#[test]
+fn test_generate() {
+    let source = squash(&generate_source("Wallet", ABI).unwrap());
+
+    assert!(source.contains("pubstructSendTransactionInput{"));
+    assert!(source.contains("pubdest:String,"));
+    assert!(source.contains(
+        "#[serde(with=\"::ton_client::abi::bindings::number\")]pubvalue:u128,"
+    ));
+    assert!(source.contains("pubbounce:bool,"));
+    assert!(source.contains(
+        "#[serde(with=\"::ton_client::abi::bindings::number_option\")]pubflags:Option<u8>,"
+    ));
+    assert!(source.contains(
+        "#[serde(with=\"::ton_client::abi::bindings::number_vec\")]pubids:Vec<u32>,"
+    ));
+    assert!(source.contains("pubbalances:::std::collections::BTreeMap<String,String>,"));
+    assert!(source.contains("pubinfo:SendTransactionInputInfo,"));
+    assert!(source.contains("pubstructSendTransactionInputInfo{"));
+    assert!(source.contains("pubr#type:u16,"));
+
+    assert!(source.contains("pubstructGetCounterOutput{"));
+    assert!(source.contains("pubvalue0:i64,"));
+
+    assert!(source.contains("pubstructCounterChangedEvent{"));
+    assert!(source.contains("pubenumWalletEvent{CounterChanged(CounterChangedEvent),}"));
+
+    assert!(source.contains("pubstructWallet{"));
+    assert!(source.contains("pubfnsend_transaction_params("));
+    assert!(source.contains("pubasyncfnencode_send_transaction("));
+    assert!(source.contains("pubasyncfncall_send_transaction("));
+    assert!(source.contains("pubasyncfnrun_get_counter("));
+    assert!(source.contains("pubfndecode_event("));
+}
+
+#[test]
+fn test_invalid_abi() {
+    assert!(generate_source("Wallet", "{").is_err());
+    assert!(generate_source("Wallet", r#"{"functions": [{"inputs": []}]}"#).is_err());
+}
@@ -0,0 +1,313 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use serde_json::Value;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+/// Keywords which can't be used as raw identifiers.
+const RESERVED: &[&str] = &["crate", "self", "Self", "super"];
+
+pub(crate) struct Param {
+    pub name: String,
+    pub kind: String,
+    pub components: Vec<Param>,
+}
+
+pub(crate) struct Function {
+    pub name: String,
+    pub inputs: Vec<Param>,
+    pub outputs: Vec<Param>,
+}
+
+pub(crate) struct Event {
+    pub name: String,
+    pub inputs: Vec<Param>,
+}
+
+pub(crate) struct Contract {
+    pub functions: Vec<Function>,
+    pub events: Vec<Event>,
+}
+
+fn str_field(value: &Value, field: &str) -> Result<String, String> {
+    value[field]
+        .as_str()
+        .map(|x| x.to_string())
+        .ok_or_else(|| format!("ABI item has no `{}` field: {}", field, value))
+}
+
+fn parse_params(value: &Value) -> Result<Vec<Param>, String> {
+    let mut params = Vec::new();
+    for param in value.as_array().map(|x| x.as_slice()).unwrap_or_default() {
+        params.push(Param {
+            name: str_field(param, "name")?,
+            kind: str_field(param, "type")?,
+            components: parse_params(&param["components"])?,
+        });
+    }
+    Ok(params)
+}
+
+impl Contract {
+    pub fn parse(abi_json: &str) -> Result<Self, String> {
+        let abi: Value =
+            serde_json::from_str(abi_json).map_err(|err| format!("Invalid ABI JSON: {}", err))?;
+        let mut functions = Vec::new();
+        for function in abi["functions"].as_array().map(|x| x.as_slice()).unwrap_or_default() {
+            functions.push(Function {
+                name: str_field(function, "name")?,
+                inputs: parse_params(&function["inputs"])?,
+                outputs: parse_params(&function["outputs"])?,
+            });
+        }
+        let mut events = Vec::new();
+        for event in abi["events"].as_array().map(|x| x.as_slice()).unwrap_or_default() {
+            events.push(Event {
+                name: str_field(event, "name")?,
+                inputs: parse_params(&event["inputs"])?,
+            });
+        }
+        Ok(Self { functions, events })
+    }
+}
+
+pub(crate) fn snake_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if prev_lower {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+            prev_lower = false;
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            result.push(c);
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        } else {
+            result.push('_');
+            prev_lower = false;
+        }
+    }
+    if result.is_empty() || result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, '_');
+    }
+    result
+}
+
+pub(crate) fn camel_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut upper = true;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            upper = true;
+        } else if upper {
+            result.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    if result.is_empty() || result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, 'T');
+    }
+    result
+}
+
+pub(crate) fn ident(name: &str) -> Ident {
+    if RESERVED.contains(&name) {
+        Ident::new(&format!("{}_", name), Span::call_site())
+    } else if KEYWORDS.contains(&name) {
+        Ident::new_raw(name, Span::call_site())
+    } else {
+        Ident::new(name, Span::call_site())
+    }
+}
+
+fn split_map_args(args: &str) -> Result<(&str, &str), String> {
+    let mut depth = 0;
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => return Ok((args[..i].trim(), args[i + 1..].trim())),
+            _ => {}
+        }
+    }
+    Err(format!("Invalid map type: map({})", args))
+}
+
+fn integer_type(kind: &str) -> Option<TokenStream> {
+    let (signed, bits) = if let Some(bits) = kind.strip_prefix("uint") {
+        (false, bits.parse::<u32>().ok()?)
+    } else if let Some(bits) = kind.strip_prefix("int") {
+        (true, bits.parse::<u32>().ok()?)
+    } else {
+        // Variable length integers store up to `8 * (N - 1)` bits
+        match kind {
+            "varuint16" | "gram" | "token" => (false, 120),
+            "varint16" => (true, 120),
+            _ => return None,
+        }
+    };
+    Some(match (signed, bits) {
+        (false, 0..=8) => quote!(u8),
+        (false, 9..=16) => quote!(u16),
+        (false, 17..=32) => quote!(u32),
+        (false, 33..=64) => quote!(u64),
+        (false, 65..=128) => quote!(u128),
+        (true, 0..=8) => quote!(i8),
+        (true, 9..=16) => quote!(i16),
+        (true, 17..=32) => quote!(i32),
+        (true, 33..=64) => quote!(i64),
+        (true, 65..=128) => quote!(i128),
+        _ => return None,
+    })
+}
+
+fn is_big_integer(kind: &str) -> bool {
+    kind.starts_with("uint")
+        || kind.starts_with("int")
+        || kind.starts_with("varuint")
+        || kind.starts_with("varint")
+}
+
+fn strip_wrapper<'a>(kind: &'a str, wrapper: &str) -> Option<&'a str> {
+    kind.strip_prefix(wrapper)?
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Rust type of the ABI parameter with the serde helper module used to (de)serialize it.
+pub(crate) struct MappedType {
+    pub tokens: TokenStream,
+    pub serde_with: Option<&'static str>,
+}
+
+/// Maps ABI types to Rust types and collects the structs generated for tuples.
+pub(crate) struct TypeMapper {
+    pub structs: Vec<TokenStream>,
+}
+
+impl TypeMapper {
+    pub fn new() -> Self {
+        Self {
+            structs: Vec::new(),
+        }
+    }
+
+    /// Maps the type of the struct field.
+    ///
+    /// Integers of the fields, optional fields and arrays are represented with Rust integers
+    /// (de)serialized from the ABI JSON strings. Integers nested deeper are represented
+    /// as strings because serde helper modules can't be composed.
+    pub fn field_type(&mut self, param: &Param, owner: &str) -> Result<MappedType, String> {
+        let kind = param.kind.as_str();
+        let kind = strip_wrapper(kind, "ref").unwrap_or(kind);
+        if let Some(int) = integer_type(kind) {
+            return Ok(MappedType {
+                tokens: int,
+                serde_with: Some("number"),
+            });
+        }
+        if let Some(inner) = strip_wrapper(kind, "optional") {
+            if let Some(int) = integer_type(inner) {
+                return Ok(MappedType {
+                    tokens: quote!(Option<#int>),
+                    serde_with: Some("number_option"),
+                });
+            }
+        }
+        if let Some(inner) = kind.strip_suffix("[]") {
+            if let Some(int) = integer_type(inner) {
+                return Ok(MappedType {
+                    tokens: quote!(Vec<#int>),
+                    serde_with: Some("number_vec"),
+                });
+            }
+        }
+        let struct_name = format!("{}{}", owner, camel_case(&param.name));
+        Ok(MappedType {
+            tokens: self.nested_type(kind, &param.components, &struct_name)?,
+            serde_with: None,
+        })
+    }
+
+    fn nested_type(
+        &mut self,
+        kind: &str,
+        components: &[Param],
+        struct_name: &str,
+    ) -> Result<TokenStream, String> {
+        if let Some(inner) = strip_wrapper(kind, "ref") {
+            return self.nested_type(inner, components, struct_name);
+        }
+        if let Some(inner) = strip_wrapper(kind, "optional") {
+            let inner = self.nested_type(inner, components, struct_name)?;
+            return Ok(quote!(Option<#inner>));
+        }
+        if let Some(args) = strip_wrapper(kind, "map") {
+            let (_, value) = split_map_args(args)?;
+            let value = self.nested_type(value, components, struct_name)?;
+            // Map keys are represented as strings in the ABI JSON
+            return Ok(quote!(::std::collections::BTreeMap<String, #value>));
+        }
+        if kind.ends_with(']') {
+            if let Some(pos) = kind.rfind('[') {
+                let inner = self.nested_type(&kind[..pos], components, struct_name)?;
+                return Ok(quote!(Vec<#inner>));
+            }
+        }
+        if kind == "tuple" {
+            let name = ident(struct_name);
+            let tuple = self.structure(struct_name, components)?;
+            self.structs.push(tuple);
+            return Ok(quote!(#name));
+        }
+        Ok(match kind {
+            "bool" => quote!(bool),
+            "address" | "address_std" | "cell" | "bytes" | "string" => quote!(String),
+            _ if kind.starts_with("fixedbytes") || is_big_integer(kind) => quote!(String),
+            _ => quote!(::serde_json::Value),
+        })
+    }
+
+    /// Generates struct with the fields for the ABI parameters.
+    pub fn structure(&mut self, name: &str, params: &[Param]) -> Result<TokenStream, String> {
+        let mut fields = Vec::new();
+        for param in params {
+            let field_name = snake_case(&param.name);
+            let field = ident(&field_name);
+            let mapped = self.field_type(param, name)?;
+            let ty = mapped.tokens;
+            let mut attrs = Vec::new();
+            if field_name != param.name {
+                let rename = &param.name;
+                attrs.push(quote!(#[serde(rename = #rename)]));
+            }
+            if let Some(helper) = mapped.serde_with {
+                let path = format!("::ton_client::abi::bindings::{}", helper);
+                attrs.push(quote!(#[serde(with = #path)]));
+            }
+            let doc = format!("`{}`: `{}`", param.name, param.kind);
+            fields.push(quote! {
+                #[doc = #doc]
+                #(#attrs)*
+                pub #field: #ty,
+            });
+        }
+        let name = ident(name);
+        Ok(quote! {
+            #[derive(::serde::Serialize, ::serde::Deserialize, Debug, Clone, PartialEq, Default)]
+            pub struct #name {
+                #(#fields)*
+            }
+        })
+    }
+}
@@ -43,3 +43,37 @@ pub fn include_build_info(_input: TokenStream) -> TokenStream {
     };
     return quote!(#content).into();
 }
+
+/// Generates typed contract bindings: `abi_bindings!(Name, "path/to/Contract.abi.json")`.
+///
+/// ABI path is relative to the `CARGO_MANIFEST_DIR` of the crate using the macro.
+#[proc_macro]
+pub fn abi_bindings(input: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(input as AbiBindingsArgs);
+    let path = std::path::Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default())
+        .join(args.path.value());
+    let result = std::fs::read_to_string(&path)
+        .map_err(|err| format!("Can not read ABI file {}: {}", path.display(), err))
+        .and_then(|abi| api_bindgen::generate(&args.name.to_string(), &abi));
+    match result {
+        Ok(tokens) => tokens.into(),
+        Err(err) => syn::Error::new(args.path.span(), err)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+struct AbiBindingsArgs {
+    name: syn::Ident,
+    path: syn::LitStr,
+}
+
+impl syn::parse::Parse for AbiBindingsArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        let path = input.parse()?;
+        let _ = input.parse::<Option<syn::Token![,]>>()?;
+        Ok(Self { name, path })
+    }
+}
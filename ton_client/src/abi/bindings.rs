@@ -0,0 +1,167 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Runtime support of the typed contract bindings.
+//!
+//! Bindings are generated from the contract ABI JSON with the `abi_bindings!` macro:
+//!
+//! ```ignore
+//! ton_client::abi::bindings::abi_bindings!(Wallet, "contracts/Wallet.abi.json");
+//! ```
+//!
+//! The ABI path is relative to the crate manifest directory. Build scripts can
+//! generate the same code with `api_bindgen::generate_source`.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::abi::Error;
+use crate::error::ClientResult;
+use crate::processing::ResultOfProcessMessage;
+
+pub use api_derive::abi_bindings;
+
+/// Result of the contract function call made with the generated client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallResult<T> {
+    /// Decoded function output.
+    pub output: T,
+
+    /// Result of the message processing.
+    pub result: ResultOfProcessMessage,
+}
+
+/// Converts typed function input to the ABI JSON.
+pub fn encode_input<T: Serialize>(input: &T) -> ClientResult<Value> {
+    serde_json::to_value(input).map_err(|err| Error::invalid_json(err))
+}
+
+/// Converts decoded ABI JSON to typed function output.
+///
+/// Missing output is treated as the output without parameters.
+pub fn decode_output<T: DeserializeOwned>(output: Option<Value>) -> ClientResult<T> {
+    serde_json::from_value(output.unwrap_or_else(|| json!({})))
+        .map_err(|err| Error::invalid_message_for_decode(err))
+}
+
+/// Integer type of the ABI parameter.
+pub trait AbiNumber: Sized + ToString {
+    fn from_str_radix(value: &str, radix: u32) -> Result<Self, std::num::ParseIntError>;
+}
+
+macro_rules! abi_number {
+    ($($t:ty),*) => {
+        $(impl AbiNumber for $t {
+            fn from_str_radix(value: &str, radix: u32) -> Result<Self, std::num::ParseIntError> {
+                <$t>::from_str_radix(value, radix)
+            }
+        })*
+    };
+}
+
+abi_number!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+fn parse_number<T: AbiNumber>(value: &Value) -> Result<T, String> {
+    let string = match value {
+        Value::String(string) => string.clone(),
+        Value::Number(number) => number.to_string(),
+        _ => return Err(format!("number expected, got {}", value)),
+    };
+    let (negative, digits) = match string.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, string.as_str()),
+    };
+    let (digits, radix) = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => (hex, 16),
+        None => (digits, 10),
+    };
+    let digits = if negative {
+        format!("-{}", digits)
+    } else {
+        digits.to_string()
+    };
+    T::from_str_radix(&digits, radix).map_err(|err| format!("invalid number {}: {}", string, err))
+}
+
+/// Serde helper for the integer parameters.
+///
+/// ABI JSON represents integers as decimal or `0x`-prefixed hex strings.
+pub mod number {
+    use super::{parse_number, AbiNumber};
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    pub fn serialize<T: AbiNumber, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, T: AbiNumber, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        parse_number(&Value::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serde helper for the optional integer parameters.
+pub mod number_option {
+    use super::{parse_number, AbiNumber};
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    pub fn serialize<T: AbiNumber, S: Serializer>(
+        value: &Option<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_some(&value.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T: AbiNumber, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<T>, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::Null => Ok(None),
+            value => parse_number(&value)
+                .map(Some)
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Serde helper for the integer array parameters.
+pub mod number_vec {
+    use super::{parse_number, AbiNumber};
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    pub fn serialize<T: AbiNumber, S: Serializer>(
+        value: &Vec<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(value.iter().map(|x| x.to_string()))
+    }
+
+    pub fn deserialize<'de, T: AbiNumber, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<T>, D::Error> {
+        Vec::<Value>::deserialize(deserializer)?
+            .iter()
+            .map(parse_number)
+            .collect::<Result<_, _>>()
+            .map_err(serde::de::Error::custom)
+    }
+}
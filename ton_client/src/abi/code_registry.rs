@@ -0,0 +1,446 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+
+use serde_json::Value;
+
+use crate::abi::registry::{register_abi, register_parsed_abi, ParamsOfRegisterAbi};
+use crate::abi::{
+    decode_message, decode_message_body, Abi, AbiHandle, DataLayout, DecodedMessageBody, Error,
+    ParamsOfDecodeMessage, ParamsOfDecodeMessageBody,
+};
+use crate::boc::internal::deserialize_object_from_boc;
+use crate::client::storage::KeyValueStorage;
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use crate::net::{ParamsOfQueryCollection, ACCOUNTS_COLLECTION};
+
+const STORAGE_NAME: &str = "code_abis";
+
+pub(crate) fn code_hash_key(code_hash: &str) -> String {
+    format!("code_{}", code_hash.to_lowercase())
+}
+
+/// Storage key of the account address. Local storage keys can contain only letters,
+/// digits, `_` and `.`, so the negative workchain sign is encoded with `n`.
+pub(crate) fn address_key(address: &str) -> ClientResult<String> {
+    let address = crate::encoding::account_decode(address)?;
+    let workchain_id = address.get_workchain_id();
+    Ok(format!(
+        "address_{}{}_{}",
+        if workchain_id < 0 { "n" } else { "" },
+        workchain_id.abs(),
+        format!("{:x}", address.get_address()),
+    ))
+}
+
+/// Durable registry of the contract ABIs identified by the code hash or the account address.
+///
+/// ABIs are parsed once on the first use and kept in the context ABI registry.
+#[derive(Default)]
+pub(crate) struct CodeAbiRegistry {
+    storage: RwLock<Option<Arc<dyn KeyValueStorage>>>,
+    handles: RwLock<HashMap<String, AbiHandle>>,
+    /// Set when a code ABI is registered or loaded in this context.
+    used: AtomicBool,
+}
+
+impl CodeAbiRegistry {
+    /// Returns `true` if a code ABI is registered or loaded in this context,
+    /// so decoding of the arbitrary messages doesn't touch the storage and the network otherwise.
+    pub fn is_used(&self) -> bool {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    async fn storage(&self, context: &ClientContext) -> ClientResult<Arc<dyn KeyValueStorage>> {
+        if let Some(storage) = self.storage.read().await.as_ref() {
+            return Ok(storage.clone());
+        }
+        let mut write_guard = self.storage.write().await;
+        if let Some(storage) = write_guard.as_ref() {
+            return Ok(storage.clone());
+        }
+        let storage = Arc::new(
+            crate::client::LocalStorage::new(
+                context.config.local_storage_path.clone(),
+                STORAGE_NAME.to_string(),
+            )
+            .await?,
+        ) as Arc<dyn KeyValueStorage>;
+        *write_guard = Some(storage.clone());
+        Ok(storage)
+    }
+
+    fn register(context: &Arc<ClientContext>, abi: String) -> ClientResult<AbiHandle> {
+        Ok(register_abi(
+            context.clone(),
+            ParamsOfRegisterAbi {
+                abi: Abi::Json(abi),
+            },
+        )?
+        .handle)
+    }
+
+    async fn put(&self, context: &Arc<ClientContext>, key: String, abi: &str) -> ClientResult<()> {
+        // ABI is validated before storing but is registered only when it is stored,
+        // so a failed write doesn't leave the registered ABI behind
+        let contract =
+            ton_abi::Contract::load(abi.as_bytes()).map_err(|err| Error::invalid_json(err))?;
+        self.storage(context).await?.put_str(&key, abi).await?;
+        let handle = register_parsed_abi(context, contract, abi.to_string());
+        self.used.store(true, Ordering::Relaxed);
+        if let Some(previous) = self.handles.write().await.insert(key, handle) {
+            context.abis.remove(&previous.0);
+        }
+        Ok(())
+    }
+
+    async fn remove(&self, context: &Arc<ClientContext>, key: &str) -> ClientResult<()> {
+        self.storage(context).await?.remove(key).await?;
+        if let Some(handle) = self.handles.write().await.remove(key) {
+            context.abis.remove(&handle.0);
+        }
+        Ok(())
+    }
+
+    async fn find(&self, context: &Arc<ClientContext>, key: &str) -> ClientResult<Option<AbiHandle>> {
+        if let Some(handle) = self.handles.read().await.get(key) {
+            return Ok(Some(*handle));
+        }
+        let abi = match self.storage(context).await?.get_str(key).await? {
+            Some(abi) => abi,
+            None => return Ok(None),
+        };
+        let handle = Self::register(context, abi)?;
+        self.used.store(true, Ordering::Relaxed);
+        let mut handles = self.handles.write().await;
+        // ABI can be loaded by the concurrent call
+        if let Some(existing) = handles.get(key) {
+            context.abis.remove(&handle.0);
+            return Ok(Some(*existing));
+        }
+        handles.insert(key.to_string(), handle);
+        Ok(Some(handle))
+    }
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfRegisterCodeAbi {
+    /// Contract ABI.
+    pub abi: Abi,
+
+    /// Hash of the contract code. Encoded as hex.
+    ///
+    /// The ABI is used for all accounts with this code hash.
+    pub code_hash: Option<String>,
+
+    /// Account address.
+    ///
+    /// The ABI is used for this account regardless of its code hash.
+    /// Takes precedence over the ABI registered for the code hash.
+    pub address: Option<String>,
+}
+
+/// Registers the contract ABI for the code hash and (or) the account address.
+///
+/// Registered ABIs are used by `decode_message_auto` and to decode the message bodies
+/// in `net.query_transaction_tree` and `net.create_account_transactions_iterator` which
+/// are not decoded with the `abi_registry` parameter. ABIs are persisted in the local storage,
+/// so they are available after the client restart. Transaction trees and iterators use
+/// the registry only after a code ABI is registered or used by `decode_message_auto`
+/// in the client context.
+#[api_function]
+pub async fn register_code_abi(
+    context: Arc<ClientContext>,
+    params: ParamsOfRegisterCodeAbi,
+) -> ClientResult<()> {
+    if params.code_hash.is_none() && params.address.is_none() {
+        return Err(Error::invalid_abi(
+            "`code_hash` or `address` must be specified to register ABI",
+        ));
+    }
+    let abi = params.abi.resolve_json_string(&context)?;
+    if let Some(code_hash) = &params.code_hash {
+        context
+            .code_abis
+            .put(&context, code_hash_key(code_hash), &abi)
+            .await?;
+    }
+    if let Some(address) = &params.address {
+        context
+            .code_abis
+            .put(&context, address_key(address)?, &abi)
+            .await?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfRemoveCodeAbi {
+    /// Hash of the contract code. Encoded as hex.
+    pub code_hash: Option<String>,
+
+    /// Account address.
+    pub address: Option<String>,
+}
+
+/// Removes the ABI registered with `register_code_abi`.
+#[api_function]
+pub async fn remove_code_abi(
+    context: Arc<ClientContext>,
+    params: ParamsOfRemoveCodeAbi,
+) -> ClientResult<()> {
+    if let Some(code_hash) = &params.code_hash {
+        context
+            .code_abis
+            .remove(&context, &code_hash_key(code_hash))
+            .await?;
+    }
+    if let Some(address) = &params.address {
+        context
+            .code_abis
+            .remove(&context, &address_key(address)?)
+            .await?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default)]
+pub struct ParamsOfDecodeMessageAuto {
+    /// Message BOC.
+    pub message: String,
+
+    /// Flag allowing partial BOC decoding when ABI doesn't describe the full body BOC.
+    /// Default is `false`.
+    #[serde(default)]
+    pub allow_partial: bool,
+
+    /// Data layout of the internal message body. See `decode_message`.
+    pub data_layout: Option<DataLayout>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Debug, PartialEq, Clone)]
+pub struct ResultOfDecodeMessageAuto {
+    /// Decoded message body.
+    pub decoded: DecodedMessageBody,
+
+    /// Address of the account which ABI is used to decode the message.
+    pub address: String,
+
+    /// Code hash of the account. Missing if the ABI is registered for the address.
+    pub code_hash: Option<String>,
+}
+
+async fn query_code_hash(context: &Arc<ClientContext>, address: &str) -> ClientResult<Option<String>> {
+    let accounts = crate::net::fetch_collection(
+        context.clone(),
+        ParamsOfQueryCollection {
+            collection: ACCOUNTS_COLLECTION.to_string(),
+            filter: Some(json!({ "id": { "eq": address } })),
+            result: "code_hash".to_string(),
+            limit: Some(1),
+            ..Default::default()
+        },
+    )
+    .await?
+    .result;
+    Ok(accounts
+        .first()
+        .and_then(|account| account["code_hash"].as_str())
+        .map(|code_hash| code_hash.to_string()))
+}
+
+/// Finds the ABI registered for the account address or, if there is no such ABI,
+/// for the account code hash queried from the network.
+/// Returns the found ABI handle and the queried code hash.
+async fn find_account_abi(
+    context: &Arc<ClientContext>,
+    address: &str,
+) -> ClientResult<(Option<AbiHandle>, Option<String>)> {
+    let handle = context
+        .code_abis
+        .find(context, &address_key(address)?)
+        .await?;
+    if handle.is_some() {
+        return Ok((handle, None));
+    }
+    let code_hash = query_code_hash(context, address).await?;
+    let handle = match &code_hash {
+        Some(code_hash) => {
+            context
+                .code_abis
+                .find(context, &code_hash_key(code_hash))
+                .await?
+        }
+        None => None,
+    };
+    Ok((handle, code_hash))
+}
+
+/// Returns the address of the account the message belongs to: the source account
+/// for the external outbound message (event) and the destination account otherwise.
+fn message_account(message: &Value) -> Option<&str> {
+    let address = if message["msg_type"].as_u64() == Some(2) {
+        message["src"].as_str()?
+    } else {
+        message["dst"].as_str()?
+    };
+    Some(address).filter(|address| !address.is_empty())
+}
+
+/// Decodes message bodies with the ABIs registered by `register_code_abi`.
+///
+/// ABI of each account is resolved once per resolver, so the resolver is created
+/// for a batch of messages, e.g. a transaction tree or an iterator page.
+#[derive(Default)]
+pub(crate) struct CodeAbiResolver {
+    accounts: HashMap<String, Option<AbiHandle>>,
+}
+
+impl CodeAbiResolver {
+    /// Resolves ABIs of the message accounts which are not resolved yet.
+    ///
+    /// Does nothing until a code ABI is used in the context. Code hashes of the accounts
+    /// without the ABI registered for the address are queried with one request.
+    pub async fn resolve(&mut self, context: &Arc<ClientContext>, messages: &[&Value]) {
+        if !context.code_abis.is_used() {
+            return;
+        }
+        let mut unresolved = Vec::new();
+        for address in messages.iter().filter_map(|message| message_account(message)) {
+            if self.accounts.contains_key(address) || unresolved.contains(&address) {
+                continue;
+            }
+            let handle = match address_key(address) {
+                Ok(key) => context.code_abis.find(context, &key).await,
+                Err(err) => Err(err),
+            };
+            match handle {
+                Ok(Some(handle)) => {
+                    self.accounts.insert(address.to_string(), Some(handle));
+                }
+                Ok(None) => unresolved.push(address),
+                Err(err) => {
+                    log::debug!("Can not find registered ABI of {}: {}", address, err);
+                    self.accounts.insert(address.to_string(), None);
+                }
+            }
+        }
+        if unresolved.is_empty() {
+            return;
+        }
+        let accounts = crate::net::fetch_collection(
+            context.clone(),
+            ParamsOfQueryCollection {
+                collection: ACCOUNTS_COLLECTION.to_string(),
+                filter: Some(json!({ "id": { "in": unresolved } })),
+                result: "id code_hash".to_string(),
+                limit: Some(unresolved.len() as u32),
+                ..Default::default()
+            },
+        )
+        .await
+        .map(|result| result.result)
+        .unwrap_or_else(|err| {
+            log::debug!("Can not query code hashes of the accounts: {}", err);
+            Vec::new()
+        });
+        for address in unresolved {
+            let code_hash = accounts
+                .iter()
+                .find(|account| account["id"] == address)
+                .and_then(|account| account["code_hash"].as_str());
+            let handle = match code_hash {
+                Some(code_hash) => context
+                    .code_abis
+                    .find(context, &code_hash_key(code_hash))
+                    .await
+                    .unwrap_or_else(|err| {
+                        log::debug!("Can not find registered ABI of {}: {}", address, err);
+                        None
+                    }),
+                None => None,
+            };
+            self.accounts.insert(address.to_string(), handle);
+        }
+    }
+
+    /// Decodes the body of the message having `src`, `dst`, `msg_type` and `body` fields
+    /// with the ABI resolved by `resolve`.
+    pub fn decode_body(
+        &self,
+        context: &Arc<ClientContext>,
+        message: &Value,
+    ) -> Option<DecodedMessageBody> {
+        let body = message["body"].as_str()?;
+        let handle = (*self.accounts.get(message_account(message)?)?)?;
+        decode_message_body(
+            context.clone(),
+            ParamsOfDecodeMessageBody {
+                body: body.to_string(),
+                abi: Abi::Handle(handle),
+                is_internal: message["msg_type"].as_u64().unwrap_or(0) == 0,
+                ..Default::default()
+            },
+        )
+        .ok()
+    }
+}
+
+/// Decodes message body without specifying the ABI.
+///
+/// The ABI is taken from the registry filled by `register_code_abi`. The message belongs to
+/// its source account if the message is an external outbound one (event) and to its destination
+/// account otherwise. The ABI registered for the account address is used first. Otherwise
+/// the code hash of the account is queried from the network and the ABI registered
+/// for this code hash is used.
+#[api_function]
+pub async fn decode_message_auto(
+    context: Arc<ClientContext>,
+    params: ParamsOfDecodeMessageAuto,
+) -> ClientResult<ResultOfDecodeMessageAuto> {
+    let message =
+        deserialize_object_from_boc::<ton_block::Message>(&context, &params.message, "message")?
+            .object;
+    let address = match message.header() {
+        ton_block::CommonMsgInfo::ExtOutMsgInfo(_) => message.src(),
+        _ => message.dst(),
+    }
+    .ok_or_else(|| Error::invalid_message_for_decode("message has no account address"))?
+    .to_string();
+
+    let (handle, code_hash) = find_account_abi(&context, &address).await?;
+    let handle =
+        handle.ok_or_else(|| Error::code_abi_not_found(&address, code_hash.as_deref()))?;
+
+    let decoded = decode_message(
+        context.clone(),
+        ParamsOfDecodeMessage {
+            abi: Abi::Handle(handle),
+            message: params.message,
+            allow_partial: params.allow_partial,
+            function_name: None,
+            data_layout: params.data_layout,
+        },
+    )?;
+    Ok(ResultOfDecodeMessageAuto {
+        decoded,
+        address,
+        code_hash,
+    })
+}
@@ -0,0 +1,239 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::sync::Arc;
+
+use rand::RngCore;
+use ton_types::{BuilderData, Cell, IBitstring};
+
+use crate::abi::Error;
+use crate::boc::internal::{deserialize_cell_from_boc, serialize_cell_to_base64};
+use crate::client::ClientContext;
+use crate::crypto::{
+    nacl_box, nacl_box_keypair_from_secret_key, nacl_box_open, ParamsOfNaclBox,
+    ParamsOfNaclBoxKeyPairFromSecret, ParamsOfNaclBoxOpen,
+};
+use crate::encoding::{base64_decode, slice_from_cell};
+use crate::error::ClientResult;
+
+/// Function id of the plain text comment.
+pub const COMMENT_FUNCTION_ID: u32 = 0;
+
+/// Function id of the encrypted comment.
+pub const ENCRYPTED_COMMENT_FUNCTION_ID: u32 = 0x656e6363;
+
+const NONCE_LENGTH: usize = 24;
+const PUBLIC_KEY_LENGTH: usize = 32;
+const MAX_CELL_BYTES: usize = 127;
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug)]
+pub struct CommentEncryption {
+    /// Recipient's public key of the nacl box key pair - unprefixed 0-padded
+    /// to 64 symbols hex string.
+    pub their_public: String,
+
+    /// Sender's secret key of the nacl box key pair - unprefixed 0-padded
+    /// to 64 symbols hex string.
+    pub secret: String,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug)]
+pub struct ParamsOfEncodeCommentBody {
+    /// Comment text.
+    pub comment: String,
+
+    /// Encryption parameters. The comment is not encrypted if missing.
+    pub encryption: Option<CommentEncryption>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfEncodeCommentBody {
+    /// Message body BOC encoded with `base64`.
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfDecodeCommentBody {
+    /// Message body BOC encoded with `base64`.
+    pub body: String,
+
+    /// Recipient's secret key of the nacl box key pair - unprefixed 0-padded
+    /// to 64 symbols hex string. Required to decode the encrypted comment.
+    pub secret: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfDecodeCommentBody {
+    /// Comment text.
+    pub comment: String,
+
+    /// Sender's public key of the nacl box key pair. Present if the comment is encrypted.
+    pub sender_public: Option<String>,
+}
+
+/// Builds the body of the function id followed by the data split into the cell chain.
+fn build_snake_body(function_id: u32, data: &[u8]) -> ClientResult<Cell> {
+    let root_bytes = (MAX_CELL_BYTES - 4).min(data.len());
+    let (root_data, tail) = data.split_at(root_bytes);
+    let mut next: Option<Cell> = None;
+    for chunk in tail.chunks(MAX_CELL_BYTES).rev() {
+        let mut builder = BuilderData::new();
+        builder
+            .append_raw(chunk, chunk.len() * 8)
+            .map_err(|err| Error::invalid_comment(err))?;
+        if let Some(next) = next.take() {
+            builder
+                .checked_append_reference(next)
+                .map_err(|err| Error::invalid_comment(err))?;
+        }
+        next = Some(builder.into_cell().map_err(|err| Error::invalid_comment(err))?);
+    }
+    let mut builder = BuilderData::new();
+    builder
+        .append_u32(function_id)
+        .and_then(|builder| builder.append_raw(root_data, root_data.len() * 8))
+        .map_err(|err| Error::invalid_comment(err))?;
+    if let Some(next) = next {
+        builder
+            .checked_append_reference(next)
+            .map_err(|err| Error::invalid_comment(err))?;
+    }
+    builder.into_cell().map_err(|err| Error::invalid_comment(err))
+}
+
+/// Reads the function id and the data split into the cell chain.
+/// Returns `None` if the body has other layout.
+fn read_snake_body(body: &Cell) -> Option<(u32, Vec<u8>)> {
+    let mut slice = slice_from_cell(body.clone()).ok()?;
+    let function_id = slice.get_next_u32().ok()?;
+    let mut data = Vec::new();
+    loop {
+        if slice.remaining_bits() % 8 != 0 || slice.remaining_references() > 1 {
+            return None;
+        }
+        data.extend(slice.get_next_bytes(slice.remaining_bits() / 8).ok()?);
+        if slice.remaining_references() == 0 {
+            break;
+        }
+        slice = slice_from_cell(slice.reference(0).ok()?).ok()?;
+    }
+    Some((function_id, data))
+}
+
+/// Reads the text of the plain comment body.
+pub(crate) fn read_comment(body: &Cell) -> Option<String> {
+    match read_snake_body(body)? {
+        (COMMENT_FUNCTION_ID, data) => String::from_utf8(data).ok(),
+        _ => None,
+    }
+}
+
+pub(crate) fn build_comment_body(
+    context: &Arc<ClientContext>,
+    params: ParamsOfEncodeCommentBody,
+) -> ClientResult<Cell> {
+    let encryption = match params.encryption {
+        None => return build_snake_body(COMMENT_FUNCTION_ID, params.comment.as_bytes()),
+        Some(encryption) => encryption,
+    };
+    let mut nonce = [0u8; NONCE_LENGTH];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let keys = nacl_box_keypair_from_secret_key(
+        context.clone(),
+        ParamsOfNaclBoxKeyPairFromSecret {
+            secret: encryption.secret.clone(),
+        },
+    )?;
+    let encrypted = nacl_box(
+        context.clone(),
+        ParamsOfNaclBox {
+            decrypted: base64::encode(params.comment.as_bytes()),
+            nonce: hex::encode(nonce),
+            their_public: encryption.their_public.clone(),
+            secret: encryption.secret.clone(),
+        },
+    )?;
+    let mut data = nonce.to_vec();
+    data.extend(hex::decode(&keys.public).map_err(|err| Error::invalid_comment(err))?);
+    data.extend(base64_decode(&encrypted.encrypted)?);
+    build_snake_body(ENCRYPTED_COMMENT_FUNCTION_ID, &data)
+}
+
+/// Encodes the body of the transfer with a text comment.
+///
+/// The plain comment body is the zero function id followed by the UTF-8 text.
+/// The text that doesn't fit into the root cell continues in the chain of cells,
+/// each cell referring to the next one with its first reference.
+///
+/// The encrypted comment body has the same layout, but starts with the `0x656e6363`
+/// function id followed by the nonce (24 bytes), the sender's public key (32 bytes)
+/// and the text encrypted with `crypto.nacl_box`.
+///
+/// The comment can also be passed to `encode_internal_message` to encode the whole
+/// transfer message.
+#[api_function]
+pub fn encode_comment_body(
+    context: Arc<ClientContext>,
+    params: ParamsOfEncodeCommentBody,
+) -> ClientResult<ResultOfEncodeCommentBody> {
+    let body = build_comment_body(&context, params)?;
+    Ok(ResultOfEncodeCommentBody {
+        body: serialize_cell_to_base64(&body, "message body")?,
+    })
+}
+
+/// Decodes the body encoded with `encode_comment_body`.
+#[api_function]
+pub fn decode_comment_body(
+    context: Arc<ClientContext>,
+    params: ParamsOfDecodeCommentBody,
+) -> ClientResult<ResultOfDecodeCommentBody> {
+    let (_, body) = deserialize_cell_from_boc(&context, &params.body, "message body")?;
+    let (function_id, data) =
+        read_snake_body(&body).ok_or_else(|| Error::invalid_comment("body is not a comment"))?;
+    match function_id {
+        COMMENT_FUNCTION_ID => Ok(ResultOfDecodeCommentBody {
+            comment: String::from_utf8(data).map_err(|err| Error::invalid_comment(err))?,
+            sender_public: None,
+        }),
+        ENCRYPTED_COMMENT_FUNCTION_ID => {
+            let secret = params.secret.ok_or_else(|| {
+                Error::invalid_comment("comment is encrypted, `secret` is required")
+            })?;
+            if data.len() < NONCE_LENGTH + PUBLIC_KEY_LENGTH {
+                return Err(Error::invalid_comment("encrypted comment is too short"));
+            }
+            let (nonce, data) = data.split_at(NONCE_LENGTH);
+            let (sender_public, encrypted) = data.split_at(PUBLIC_KEY_LENGTH);
+            let sender_public = hex::encode(sender_public);
+            let decrypted = nacl_box_open(
+                context.clone(),
+                ParamsOfNaclBoxOpen {
+                    encrypted: base64::encode(encrypted),
+                    nonce: hex::encode(nonce),
+                    their_public: sender_public.clone(),
+                    secret,
+                },
+            )?;
+            Ok(ResultOfDecodeCommentBody {
+                comment: String::from_utf8(base64_decode(&decrypted.decrypted)?)
+                    .map_err(|err| Error::invalid_comment(err))?,
+                sender_public: Some(sender_public),
+            })
+        }
+        _ => Err(Error::invalid_comment(format!(
+            "unknown comment function id 0x{:08x}",
+            function_id
+        ))),
+    }
+}
@@ -0,0 +1,390 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::sync::Arc;
+
+use crate::abi::{Abi, AbiContract, AbiParam, Error};
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+
+#[derive(Serialize, Deserialize, ApiType, Debug, PartialEq, Clone)]
+pub enum AbiChangeKind {
+    /// Clients using the old ABI can't encode or decode messages or data
+    /// of the contract with the new ABI.
+    Breaking,
+    /// Clients using the old ABI keep working with the contract with the new ABI.
+    Compatible,
+}
+
+impl Default for AbiChangeKind {
+    fn default() -> Self {
+        AbiChangeKind::Breaking
+    }
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct AbiChange {
+    /// Change kind.
+    pub kind: AbiChangeKind,
+
+    /// Location of the changed item within the new ABI JSON (within the old ABI JSON
+    /// for the removed items), e.g. `functions[1].inputs[0]`.
+    pub path: String,
+
+    /// Change description.
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfCheckAbiCompatibility {
+    /// ABI of the current contract version.
+    pub old_abi: Abi,
+
+    /// ABI of the new contract version.
+    pub new_abi: Abi,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfCheckAbiCompatibility {
+    /// `true` if there are no breaking changes.
+    pub is_compatible: bool,
+
+    /// Found changes.
+    pub changes: Vec<AbiChange>,
+}
+
+pub(crate) fn param_signature(param: &AbiParam) -> String {
+    if param.components.is_empty() {
+        return param.param_type.clone();
+    }
+    let components = param
+        .components
+        .iter()
+        .map(param_signature)
+        .collect::<Vec<_>>()
+        .join(",");
+    // Tuple components are placed into the type: `tuple[]` becomes `(uint8,bool)[]`
+    param.param_type.replacen("tuple", &format!("({})", components), 1)
+}
+
+fn params_signature(params: &[AbiParam]) -> String {
+    params.iter().map(param_signature).collect::<Vec<_>>().join(",")
+}
+
+fn version_of(abi: &AbiContract) -> String {
+    abi.version
+        .clone()
+        .unwrap_or_else(|| format!("{}.0", abi.obsolete_abi_version))
+}
+
+struct Checker<'a> {
+    old: &'a AbiContract,
+    new: &'a AbiContract,
+    old_contract: Arc<ton_abi::Contract>,
+    new_contract: Arc<ton_abi::Contract>,
+    changes: Vec<AbiChange>,
+}
+
+impl<'a> Checker<'a> {
+    fn breaking(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.changes.push(AbiChange {
+            kind: AbiChangeKind::Breaking,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    fn compatible(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.changes.push(AbiChange {
+            kind: AbiChangeKind::Compatible,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    fn check_version(&mut self) {
+        let (old, new) = (version_of(self.old), version_of(self.new));
+        if old == new {
+            return;
+        }
+        let message = format!("ABI version changed from {} to {}", old, new);
+        if old.split('.').next() != new.split('.').next() {
+            self.breaking("version", message);
+        } else {
+            self.compatible("version", message);
+        }
+    }
+
+    fn check_header(&mut self) {
+        let (old, new) = (self.old, self.new);
+        if old.header != new.header {
+            self.breaking(
+                "header",
+                format!(
+                    "header changed from [{}] to [{}], external messages are encoded differently",
+                    old.header.join(", "),
+                    new.header.join(", ")
+                ),
+            );
+        }
+    }
+
+    /// Compares parameter names of the parameters with the same types.
+    fn check_param_names(&mut self, path: &str, old: &[AbiParam], new: &[AbiParam]) {
+        for (i, (old, new)) in old.iter().zip(new).enumerate() {
+            if old.name != new.name {
+                self.compatible(
+                    format!("{}[{}]", path, i),
+                    format!("parameter `{}` renamed to `{}`", old.name, new.name),
+                );
+            }
+            self.check_param_names(
+                &format!("{}[{}].components", path, i),
+                &old.components,
+                &new.components,
+            );
+        }
+    }
+
+    fn check_params(&mut self, path: &str, old: &[AbiParam], new: &[AbiParam]) {
+        let (old_signature, new_signature) = (params_signature(old), params_signature(new));
+        if old_signature != new_signature {
+            self.breaking(
+                path,
+                format!(
+                    "parameters changed from ({}) to ({})",
+                    old_signature, new_signature
+                ),
+            );
+        } else {
+            self.check_param_names(path, old, new);
+        }
+    }
+
+    fn check_functions(&mut self) {
+        let (old_abi, new_abi) = (self.old, self.new);
+        for (i, function) in old_abi.functions.iter().enumerate() {
+            if !new_abi.functions.iter().any(|x| x.name == function.name) {
+                self.breaking(
+                    format!("functions[{}]", i),
+                    format!("function `{}` removed", function.name),
+                );
+            }
+        }
+        for (i, function) in new_abi.functions.iter().enumerate() {
+            let path = format!("functions[{}]", i);
+            let old = match old_abi.functions.iter().find(|x| x.name == function.name) {
+                Some(old) => old,
+                None => {
+                    self.compatible(path, format!("function `{}` added", function.name));
+                    continue;
+                }
+            };
+            let ids = match (
+                self.old_contract.function(&function.name),
+                self.new_contract.function(&function.name),
+            ) {
+                (Ok(old), Ok(new)) => Some((old.get_input_id(), new.get_input_id())),
+                _ => None,
+            };
+            if let Some((old_id, new_id)) = ids {
+                if old_id != new_id {
+                    self.breaking(
+                        path.clone(),
+                        format!(
+                            "function `{}` id changed from 0x{:08x} to 0x{:08x}",
+                            function.name, old_id, new_id
+                        ),
+                    );
+                }
+            }
+            self.check_params(&format!("{}.inputs", path), &old.inputs, &function.inputs);
+            self.check_params(&format!("{}.outputs", path), &old.outputs, &function.outputs);
+        }
+    }
+
+    fn check_events(&mut self) {
+        let (old_abi, new_abi) = (self.old, self.new);
+        for (i, event) in old_abi.events.iter().enumerate() {
+            if !new_abi.events.iter().any(|x| x.name == event.name) {
+                self.breaking(
+                    format!("events[{}]", i),
+                    format!("event `{}` removed", event.name),
+                );
+            }
+        }
+        for (i, event) in new_abi.events.iter().enumerate() {
+            let path = format!("events[{}]", i);
+            let old = match old_abi.events.iter().find(|x| x.name == event.name) {
+                Some(old) => old,
+                None => {
+                    self.compatible(path, format!("event `{}` added", event.name));
+                    continue;
+                }
+            };
+            let ids = match (
+                self.old_contract.events().get(&event.name),
+                self.new_contract.events().get(&event.name),
+            ) {
+                (Some(old), Some(new)) => Some((old.get_function_id(), new.get_function_id())),
+                _ => None,
+            };
+            if let Some((old_id, new_id)) = ids {
+                if old_id != new_id {
+                    self.breaking(
+                        path.clone(),
+                        format!(
+                            "event `{}` id changed from 0x{:08x} to 0x{:08x}",
+                            event.name, old_id, new_id
+                        ),
+                    );
+                }
+            }
+            self.check_params(&format!("{}.inputs", path), &old.inputs, &event.inputs);
+        }
+    }
+
+    /// Fields are decoded by `decode_account_data` in the order of their declaration,
+    /// so any change of the existing fields layout is breaking.
+    fn check_fields(&mut self) {
+        let (old_abi, new_abi) = (self.old, self.new);
+        let (old, new) = (&old_abi.fields, &new_abi.fields);
+        for (i, field) in old.iter().enumerate() {
+            let path = format!("fields[{}]", i);
+            let moved_to = new
+                .iter()
+                .position(|x| x.name == field.name)
+                .filter(|position| *position != i);
+            match new.get(i) {
+                Some(new_field) if param_signature(new_field) == param_signature(field) => {
+                    if new_field.name != field.name {
+                        self.compatible(
+                            path,
+                            format!("field `{}` renamed to `{}`", field.name, new_field.name),
+                        );
+                    }
+                }
+                _ if moved_to.is_some() => self.breaking(
+                    path,
+                    format!(
+                        "field `{}` moved from position {} to {}",
+                        field.name,
+                        i,
+                        moved_to.unwrap_or_default()
+                    ),
+                ),
+                Some(new_field) => self.breaking(
+                    path,
+                    format!(
+                        "field `{}` of type `{}` replaced by `{}` of type `{}`",
+                        field.name,
+                        param_signature(field),
+                        new_field.name,
+                        param_signature(new_field)
+                    ),
+                ),
+                None => self.breaking(path, format!("field `{}` removed", field.name)),
+            }
+        }
+        // Old ABI doesn't describe the data of the added field, so `decode_account_data`
+        // with the old ABI fails unless `allow_partial` is set
+        for (i, field) in new.iter().enumerate().skip(old.len()) {
+            self.breaking(
+                format!("fields[{}]", i),
+                format!(
+                    "field `{}` added, the old ABI decodes account data only with `allow_partial`",
+                    field.name
+                ),
+            );
+        }
+    }
+
+    /// Data items are addressed by their keys, so only changes of the existing keys are breaking.
+    fn check_data(&mut self) {
+        let (old_abi, new_abi) = (self.old, self.new);
+        for (i, data) in old_abi.data.iter().enumerate() {
+            match new_abi.data.iter().find(|x| x.name == data.name) {
+                None => self.breaking(
+                    format!("data[{}]", i),
+                    format!("data `{}` removed", data.name),
+                ),
+                Some(new) if new.key != data.key => self.breaking(
+                    format!("data[{}]", i),
+                    format!(
+                        "data `{}` key changed from {} to {}",
+                        data.name, data.key, new.key
+                    ),
+                ),
+                Some(new) if new.param_type != data.param_type => self.breaking(
+                    format!("data[{}]", i),
+                    format!(
+                        "data `{}` type changed from `{}` to `{}`",
+                        data.name, data.param_type, new.param_type
+                    ),
+                ),
+                Some(_) => {}
+            }
+        }
+        for (i, data) in new_abi.data.iter().enumerate() {
+            if !old_abi.data.iter().any(|x| x.name == data.name) {
+                self.compatible(format!("data[{}]", i), format!("data `{}` added", data.name));
+            }
+        }
+    }
+}
+
+fn resolve_contract(context: &ClientContext, abi: &Abi) -> ClientResult<AbiContract> {
+    match abi {
+        Abi::Contract(abi) | Abi::Serialized(abi) => Ok(abi.clone()),
+        _ => serde_json::from_str(&abi.resolve_json_string(context)?)
+            .map_err(|err| Error::invalid_json(err)),
+    }
+}
+
+/// Compares two versions of the contract ABI.
+///
+/// Reports the changes breaking the clients using the old ABI: removed functions and events,
+/// changed function and event signatures and ids, header changes, changed layout of the `fields`
+/// decoded by `decode_account_data` (including the added fields) and changed data keys.
+/// Other added items and renamed parameters are reported as compatible changes.
+///
+/// Intended to gate contract upgrades in CI: the upgrade is safe for the existing clients
+/// if `is_compatible` is `true`.
+#[api_function]
+pub fn check_compatibility(
+    context: Arc<ClientContext>,
+    params: ParamsOfCheckAbiCompatibility,
+) -> ClientResult<ResultOfCheckAbiCompatibility> {
+    let old = resolve_contract(&context, &params.old_abi)?;
+    let new = resolve_contract(&context, &params.new_abi)?;
+    let mut checker = Checker {
+        old: &old,
+        new: &new,
+        old_contract: params.old_abi.resolve_abi(&context)?,
+        new_contract: params.new_abi.resolve_abi(&context)?,
+        changes: Vec::new(),
+    };
+    checker.check_version();
+    checker.check_header();
+    checker.check_functions();
+    checker.check_events();
+    checker.check_fields();
+    checker.check_data();
+    Ok(ResultOfCheckAbiCompatibility {
+        is_compatible: !checker
+            .changes
+            .iter()
+            .any(|x| x.kind == AbiChangeKind::Breaking),
+        changes: checker.changes,
+    })
+}
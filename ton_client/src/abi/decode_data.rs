@@ -1,13 +1,20 @@
-use crate::abi::types::Abi;
+use crate::abi::types::{Abi, AbiParam};
 use crate::abi::Error;
 use crate::client::ClientContext;
 use crate::boc::internal::deserialize_cell_from_boc;
-use crate::encoding::slice_from_cell;
-use crate::error::ClientResult;
+use crate::encoding::{account_decode, account_encode, slice_from_cell};
+use crate::error::{ClientError, ClientResult};
+use num_bigint::{BigInt, Sign};
+use num_traits::One;
 use serde_json;
 use serde_json::Value;
+use std::convert::TryInto;
 use std::sync::Arc;
-use ton_abi::token::Detokenizer;
+use ton_abi::contract::MAX_SUPPORTED_VERSION;
+use ton_abi::token::{Detokenizer, Tokenizer};
+use ton_abi::{Token, TokenValue};
+use ton_block::{Deserializable, MsgAddressInt, Serializable};
+use ton_types::{BuilderData, Cell, HashmapE, HashmapType, SliceData};
 
 #[derive(Serialize, Deserialize, ApiType, Default)]
 pub struct ParamsOfDecodeAccountData {
@@ -40,7 +47,7 @@ pub fn decode_account_data(
     params: ParamsOfDecodeAccountData,
 ) -> ClientResult<ResultOfDecodeAccountData> {
     let (_, data) = deserialize_cell_from_boc(&context, &params.data, "contract data")?;
-    let abi = params.abi.abi()?;
+    let abi = params.abi.resolve_abi(&context)?;
 
     let tokens = abi.decode_storage_fields(slice_from_cell(data)?, params.allow_partial)
         .map_err(|e| Error::invalid_data_for_decode(e))?;
@@ -49,3 +56,532 @@ pub fn decode_account_data(
         .map_err(|e| Error::invalid_data_for_decode(e))?;
     Ok(ResultOfDecodeAccountData { data })
 }
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct AccountDataMap {
+    /// Name of the map field.
+    pub name: String,
+
+    /// `true` if the map has no entries.
+    pub is_empty: bool,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default)]
+pub struct ParamsOfDecodeAccountDataLazy {
+    /// Contract ABI
+    pub abi: Abi,
+
+    /// Data BOC or BOC handle
+    pub data: String,
+
+    /// Flag allowing partial BOC decoding when ABI doesn't describe the full body BOC.
+    /// Controls decoder behaviour when after decoding all described in ABI params there are some data left in BOC:
+    /// `true` - return decoded values
+    /// `false` - return error of incomplete BOC deserialization (default)
+    #[serde(default)]
+    pub allow_partial: bool,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default)]
+pub struct ResultOfDecodeAccountDataLazy {
+    /// Decoded data as a JSON structure. Values of the `map` fields are `null`.
+    pub data: Value,
+
+    /// Top level `map` fields of the data. Use `decode_account_data_map` to read their entries.
+    pub maps: Vec<AccountDataMap>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default)]
+pub struct ParamsOfDecodeAccountDataMap {
+    /// Contract ABI
+    pub abi: Abi,
+
+    /// Data BOC or BOC handle
+    pub data: String,
+
+    /// Name of the top level `map` field.
+    pub field: String,
+
+    /// The first key of the page (inclusive). The page starts from the lowest key if missing.
+    ///
+    /// Integer keys are decimal or `0x` prefixed hex strings, address keys are raw addresses.
+    /// Pass `next_key` of the previous page to get the next page.
+    pub start_key: Option<String>,
+
+    /// The key the page ends at (exclusive). The page ends at the highest key if missing.
+    pub end_key: Option<String>,
+
+    /// Maximum number of the page entries. Default is 100.
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default)]
+pub struct ResultOfDecodeAccountDataMap {
+    /// Page entries as a JSON object in the `decode_account_data` format.
+    pub entries: Value,
+
+    /// Key of the first entry of the next page. Missing if there are no more entries
+    /// in the requested key range.
+    pub next_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, ApiType, PartialEq)]
+#[serde(tag = "type")]
+pub enum DataMapKey {
+    /// Integer key as a decimal or `0x` prefixed hex string, or address key as a raw address.
+    Value { value: String },
+    /// Struct key. The compiler packs the struct fields into the bits of the integer key,
+    /// so the fields are encoded with `params` like in `encode_boc` and must fill the key
+    /// exactly.
+    Struct { params: Vec<AbiParam>, value: Value },
+}
+
+impl Default for DataMapKey {
+    fn default() -> Self {
+        DataMapKey::Value { value: String::new() }
+    }
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default)]
+pub struct ParamsOfGetDataMapValue {
+    /// Contract ABI
+    pub abi: Abi,
+
+    /// Data BOC or BOC handle
+    pub data: String,
+
+    /// Name of the top level `map` field.
+    pub field: String,
+
+    /// Key path: the key of the field map followed by the keys of the nested maps.
+    pub keys: Vec<DataMapKey>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default)]
+pub struct ResultOfGetDataMapValue {
+    /// Decoded value in the `decode_account_data` format. Missing if there is no entry
+    /// with the key.
+    pub value: Option<Value>,
+}
+
+const DEFAULT_MAP_PAGE_LIMIT: u32 = 100;
+const ADDRESS_KEY_BITS: usize = 267;
+
+#[derive(Clone, Copy, PartialEq)]
+enum MapKeyKind {
+    Uint,
+    Int,
+    Address,
+}
+
+struct MapKey {
+    kind: MapKeyKind,
+    bits: usize,
+}
+
+/// Splits the `map(K,V)` ABI type into the key and value types.
+fn split_map_type(param_type: &str) -> Option<(&str, &str)> {
+    let inner = param_type.strip_prefix("map(")?.strip_suffix(')')?;
+    let mut depth = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => return Some((inner[..i].trim(), inner[i + 1..].trim())),
+            _ => {}
+        }
+    }
+    None
+}
+
+impl MapKey {
+    /// Parses key type of the `map(K,V)` ABI type.
+    fn parse(param_type: &str) -> Option<Self> {
+        let (key, _) = split_map_type(param_type)?;
+        if key == "address" {
+            return Some(Self { kind: MapKeyKind::Address, bits: ADDRESS_KEY_BITS });
+        }
+        let (kind, bits) = if let Some(bits) = key.strip_prefix("uint") {
+            (MapKeyKind::Uint, bits)
+        } else if let Some(bits) = key.strip_prefix("int") {
+            (MapKeyKind::Int, bits)
+        } else {
+            return None;
+        };
+        let bits = bits.parse::<usize>().ok().filter(|bits| *bits > 0)?;
+        Some(Self { kind, bits })
+    }
+
+    fn padding(&self) -> usize {
+        (8 - self.bits % 8) % 8
+    }
+
+    /// Converts the dictionary key to the bytes with the ordering of the key values.
+    /// Sign bit of the signed keys is inverted.
+    fn normalize(&self, key: &SliceData) -> ton_types::Result<Vec<u8>> {
+        let mut bytes = key.clone().get_next_bits(self.bits)?;
+        if self.kind == MapKeyKind::Int {
+            bytes[0] ^= 0x80;
+        }
+        Ok(bytes)
+    }
+
+    /// Converts the key string to the normalized key bytes.
+    fn normalize_str(&self, key: &str) -> ClientResult<Vec<u8>> {
+        let invalid_key = || Error::invalid_data_for_decode(format!("invalid map key `{}`", key));
+        if self.kind == MapKeyKind::Address {
+            let address = account_decode(key)?;
+            let cell = address
+                .serialize()
+                .map_err(|err| Error::invalid_data_for_decode(err))?;
+            if cell.bit_length() != self.bits {
+                return Err(invalid_key());
+            }
+            return self
+                .normalize(&slice_from_cell(cell)?)
+                .map_err(|err| Error::invalid_data_for_decode(err));
+        }
+        let value = if let Some(hex) = key.strip_prefix("-0x") {
+            -BigInt::parse_bytes(hex.as_bytes(), 16).ok_or_else(invalid_key)?
+        } else if let Some(hex) = key.strip_prefix("0x") {
+            BigInt::parse_bytes(hex.as_bytes(), 16).ok_or_else(invalid_key)?
+        } else {
+            BigInt::parse_bytes(key.as_bytes(), 10).ok_or_else(invalid_key)?
+        };
+        let modulus = BigInt::one() << self.bits;
+        let value = match self.kind {
+            MapKeyKind::Int => {
+                let half = BigInt::one() << (self.bits - 1);
+                if value < -half.clone() || value >= half {
+                    return Err(invalid_key());
+                }
+                // Two's complement with the inverted sign bit is the value shifted by 2^(bits-1)
+                value + half
+            }
+            _ => {
+                if value.sign() == Sign::Minus || value >= modulus {
+                    return Err(invalid_key());
+                }
+                value
+            }
+        };
+        let (_, bytes) = (value << self.padding()).to_bytes_be();
+        let len = (self.bits + 7) / 8;
+        let mut normalized = vec![0u8; len.saturating_sub(bytes.len())];
+        normalized.extend(bytes);
+        Ok(normalized)
+    }
+
+    /// Builds the dictionary key.
+    fn key_slice(&self, key: &DataMapKey) -> ClientResult<SliceData> {
+        match key {
+            DataMapKey::Value { value } => {
+                let mut bytes = self.normalize_str(value)?;
+                if self.kind == MapKeyKind::Int {
+                    bytes[0] ^= 0x80;
+                }
+                Ok(SliceData::from_raw(bytes, self.bits))
+            }
+            DataMapKey::Struct { params, value } => {
+                let mut abi_params = Vec::with_capacity(params.len());
+                for param in params {
+                    abi_params.push(param.clone().try_into()?);
+                }
+                let tokens = Tokenizer::tokenize_all_params(&abi_params, value)
+                    .map_err(|err| Error::invalid_data_for_decode(err))?;
+                let cell =
+                    TokenValue::pack_values_into_chain(&tokens, Vec::new(), &MAX_SUPPORTED_VERSION)
+                        .and_then(|builder| builder.into_cell())
+                        .map_err(|err| Error::invalid_data_for_decode(err))?;
+                if cell.bit_length() != self.bits || cell.references_count() != 0 {
+                    return Err(Error::invalid_data_for_decode(format!(
+                        "struct key is encoded into {} bits, map key has {} bits",
+                        cell.bit_length(),
+                        self.bits
+                    )));
+                }
+                slice_from_cell(cell)
+            }
+        }
+    }
+
+    /// Formats the dictionary key as the string accepted by `normalize_str`.
+    fn format(&self, key: &SliceData) -> ton_types::Result<String> {
+        if self.kind == MapKeyKind::Address {
+            let address = MsgAddressInt::construct_from(&mut key.clone())?;
+            return Ok(account_encode(&address));
+        }
+        let value = BigInt::from_bytes_be(Sign::Plus, &self.normalize(key)?) >> self.padding();
+        Ok(match self.kind {
+            MapKeyKind::Int => value - (BigInt::one() << (self.bits - 1)),
+            _ => value,
+        }
+        .to_string())
+    }
+}
+
+/// Replaces the top level `map` fields with `optional(cell)` ones having the same layout,
+/// so the maps are decoded as the dictionary root cells. Returns the modified ABI and
+/// the original map fields.
+fn lazy_abi(abi_json: &str) -> ClientResult<(Value, Vec<Value>)> {
+    let mut abi: Value = serde_json::from_str(abi_json).map_err(|err| Error::invalid_json(err))?;
+    let mut maps = Vec::new();
+    if let Some(fields) = abi.get_mut("fields").and_then(|fields| fields.as_array_mut()) {
+        for field in fields {
+            let is_map = field["type"]
+                .as_str()
+                .map(|param_type| param_type.starts_with("map("))
+                .unwrap_or(false);
+            if is_map {
+                maps.push(field.clone());
+                field["type"] = "optional(cell)".into();
+                if let Some(field) = field.as_object_mut() {
+                    field.remove("components");
+                }
+            }
+        }
+    }
+    Ok((abi, maps))
+}
+
+fn find_map_field(map_fields: Vec<Value>, name: &str) -> ClientResult<Value> {
+    map_fields
+        .into_iter()
+        .find(|field| field["name"] == name)
+        .ok_or_else(|| Error::invalid_abi(format!("`{}` is not a top level map field", name)))
+}
+
+fn unsupported_key(field: &str) -> ClientError {
+    Error::invalid_abi(format!("map key type of `{}` is not supported", field))
+}
+
+fn decode_tokens(abi: &Value, data: Cell, allow_partial: bool) -> ClientResult<Vec<Token>> {
+    let abi = ton_abi::Contract::load(abi.to_string().as_bytes())
+        .map_err(|err| Error::invalid_json(err))?;
+    abi.decode_storage_fields(slice_from_cell(data)?, allow_partial)
+        .map_err(|e| Error::invalid_data_for_decode(e))
+}
+
+fn decode_fields(abi: &Value, data: Cell, allow_partial: bool) -> ClientResult<Value> {
+    let tokens = decode_tokens(abi, data, allow_partial)?;
+    Detokenizer::detokenize_to_json_value(&tokens).map_err(|e| Error::invalid_data_for_decode(e))
+}
+
+/// Takes the dictionary root cell of the map field out of the tokens decoded with
+/// the lazy ABI. The root is the `optional(cell)` token value, so the dictionary itself
+/// is neither read nor serialized.
+fn take_map_root(tokens: &mut Vec<Token>, field: &str) -> ClientResult<Option<Cell>> {
+    let pos = tokens
+        .iter()
+        .position(|token| token.name == field)
+        .ok_or_else(|| Error::invalid_data_for_decode(format!("`{}` is not decoded", field)))?;
+    match tokens.remove(pos).value {
+        TokenValue::Optional(_, None) => Ok(None),
+        TokenValue::Optional(_, Some(value)) => match *value {
+            TokenValue::Cell(root) => Ok(Some(root)),
+            _ => Err(Error::invalid_data_for_decode(format!("`{}` is not a map", field))),
+        },
+        _ => Err(Error::invalid_data_for_decode(format!("`{}` is not a map", field))),
+    }
+}
+
+/// Decodes account data like `decode_account_data` but skips the entries of the top level
+/// `map` fields, so the data of the contracts with the large maps can be decoded with
+/// the limited memory. The map entries are read by pages with `decode_account_data_map`.
+///
+/// Maps nested into tuples and arrays are decoded as a whole.
+///
+/// Note: this feature requires ABI 2.1 or higher.
+#[api_function]
+pub fn decode_account_data_lazy(
+    context: Arc<ClientContext>,
+    params: ParamsOfDecodeAccountDataLazy,
+) -> ClientResult<ResultOfDecodeAccountDataLazy> {
+    let (_, data) = deserialize_cell_from_boc(&context, &params.data, "contract data")?;
+    let (abi, map_fields) = lazy_abi(&params.abi.resolve_json_string(&context)?)?;
+    let mut tokens = decode_tokens(&abi, data, params.allow_partial)?;
+
+    // Map fields are taken out before the detokenization, so the dictionaries are not
+    // converted into the BOCs
+    let mut maps = Vec::new();
+    for field in map_fields {
+        let name = field["name"].as_str().unwrap_or_default().to_string();
+        if tokens.iter().any(|token| token.name == name) {
+            maps.push(AccountDataMap {
+                is_empty: take_map_root(&mut tokens, &name)?.is_none(),
+                name,
+            });
+        }
+    }
+    let mut data = Detokenizer::detokenize_to_json_value(&tokens)
+        .map_err(|e| Error::invalid_data_for_decode(e))?;
+    if let Some(data) = data.as_object_mut() {
+        for map in &maps {
+            data.insert(map.name.clone(), Value::Null);
+        }
+    }
+    Ok(ResultOfDecodeAccountDataLazy { data, maps })
+}
+
+/// Decodes a page of the top level `map` field entries of the account data.
+///
+/// The entries are read directly from the data cell dictionary in the ascending key order.
+/// The page start is found by the key, so only the page entries are read and decoded.
+/// Integer and address keys are supported.
+///
+/// Note: this feature requires ABI 2.1 or higher.
+#[api_function]
+pub fn decode_account_data_map(
+    context: Arc<ClientContext>,
+    params: ParamsOfDecodeAccountDataMap,
+) -> ClientResult<ResultOfDecodeAccountDataMap> {
+    let (_, data) = deserialize_cell_from_boc(&context, &params.data, "contract data")?;
+    let (mut abi, map_fields) = lazy_abi(&params.abi.resolve_json_string(&context)?)?;
+    let field = find_map_field(map_fields, &params.field)?;
+    let key = MapKey::parse(field["type"].as_str().unwrap_or_default())
+        .ok_or_else(|| unsupported_key(&params.field))?;
+    let mut tokens = decode_tokens(&abi, data, true)?;
+    let map = HashmapE::with_hashmap(key.bits, take_map_root(&mut tokens, &params.field)?);
+
+    let start = params
+        .start_key
+        .as_ref()
+        .map(|value| key.key_slice(&DataMapKey::Value { value: value.clone() }))
+        .transpose()?;
+    let end = params.end_key.as_deref().map(|x| key.normalize_str(x)).transpose()?;
+    let limit = params.limit.unwrap_or(DEFAULT_MAP_PAGE_LIMIT) as usize;
+
+    // Each entry is found as the next one after the previous key, so the page is read
+    // with `limit` dictionary lookups wherever it starts. Signed keys are compared
+    // as the signed integers.
+    let signed = key.kind == MapKeyKind::Int;
+    let mut entry = match start {
+        Some(start) => map.find_leaf(start, true, true, signed, &mut 0),
+        None => map.get_min(signed, &mut 0),
+    }
+    .map_err(|err| Error::invalid_data_for_decode(err))?;
+    let mut page = HashmapE::with_bit_len(key.bits);
+    let mut page_len = 0;
+    let mut next_key = None;
+    while let Some((entry_key, value)) = entry {
+        let entry_key = entry_key
+            .into_cell()
+            .and_then(SliceData::load_cell)
+            .map_err(|err| Error::invalid_data_for_decode(err))?;
+        let normalized = key
+            .normalize(&entry_key)
+            .map_err(|err| Error::invalid_data_for_decode(err))?;
+        if end.as_ref().map(|end| normalized >= *end).unwrap_or(false) {
+            break;
+        }
+        if page_len == limit {
+            next_key = Some(
+                key.format(&entry_key)
+                    .map_err(|err| Error::invalid_data_for_decode(err))?,
+            );
+            break;
+        }
+        page.set(entry_key.clone(), &value)
+            .map_err(|err| Error::invalid_data_for_decode(err))?;
+        page_len += 1;
+        entry = map
+            .find_leaf(entry_key, true, false, signed, &mut 0)
+            .map_err(|err| Error::invalid_data_for_decode(err))?;
+    }
+
+    let mut builder = BuilderData::new();
+    page.write_hashmap_data(&mut builder)
+        .map_err(|err| Error::invalid_data_for_decode(err))?;
+    let page_data = builder
+        .into_cell()
+        .map_err(|err| Error::invalid_data_for_decode(err))?;
+    abi["fields"] = Value::Array(vec![field]);
+    let mut entries = decode_fields(&abi, page_data, false)?;
+    Ok(ResultOfDecodeAccountDataMap {
+        entries: entries[&params.field].take(),
+        next_key,
+    })
+}
+
+/// Returns the value of the top level `map` field of the account data by its key.
+///
+/// The key is looked up directly in the data cell dictionary, so only the found value is
+/// decoded. Values of the nested maps (`map(address,map(address,uint128))`) are found
+/// by the key path.
+///
+/// Note: this feature requires ABI 2.1 or higher.
+#[api_function]
+pub fn get_data_map_value(
+    context: Arc<ClientContext>,
+    params: ParamsOfGetDataMapValue,
+) -> ClientResult<ResultOfGetDataMapValue> {
+    let (_, data) = deserialize_cell_from_boc(&context, &params.data, "contract data")?;
+    let (mut abi, map_fields) = lazy_abi(&params.abi.resolve_json_string(&context)?)?;
+    let mut field = find_map_field(map_fields, &params.field)?;
+    if params.keys.is_empty() {
+        return Err(Error::invalid_data_for_decode("map key is required"));
+    }
+    let mut root = take_map_root(&mut decode_tokens(&abi, data, true)?, &params.field)?;
+    let mut map_type = field["type"].as_str().unwrap_or_default().to_string();
+
+    for (i, key) in params.keys.iter().enumerate() {
+        let key_type = MapKey::parse(&map_type).ok_or_else(|| unsupported_key(&params.field))?;
+        let key = key_type.key_slice(key)?;
+        let map = HashmapE::with_hashmap(key_type.bits, root.take());
+        let mut value = match map
+            .get(key.clone())
+            .map_err(|err| Error::invalid_data_for_decode(err))?
+        {
+            Some(value) => value,
+            None => return Ok(ResultOfGetDataMapValue { value: None }),
+        };
+
+        if i + 1 == params.keys.len() {
+            // Found entry is decoded as the single entry map of the same type
+            let mut entry = HashmapE::with_bit_len(key_type.bits);
+            entry
+                .set(key, &value)
+                .map_err(|err| Error::invalid_data_for_decode(err))?;
+            let mut builder = BuilderData::new();
+            entry
+                .write_hashmap_data(&mut builder)
+                .map_err(|err| Error::invalid_data_for_decode(err))?;
+            let entry_data = builder
+                .into_cell()
+                .map_err(|err| Error::invalid_data_for_decode(err))?;
+            field["type"] = map_type.into();
+            abi["fields"] = Value::Array(vec![field]);
+            let decoded = decode_fields(&abi, entry_data, false)?;
+            return Ok(ResultOfGetDataMapValue {
+                value: decoded[&params.field]
+                    .as_object()
+                    .and_then(|entries| entries.values().next())
+                    .cloned(),
+            });
+        }
+
+        let value_type = split_map_type(&map_type)
+            .map(|(_, value_type)| value_type.to_string())
+            .unwrap_or_default();
+        if !value_type.starts_with("map(") {
+            return Err(Error::invalid_data_for_decode(format!(
+                "`{}` has {} nested maps, {} keys are provided",
+                params.field,
+                i,
+                params.keys.len()
+            )));
+        }
+        // Nested map is stored in the value as the dictionary root presence bit and reference
+        root = if value.get_next_bit().map_err(|err| Error::invalid_data_for_decode(err))? {
+            Some(
+                value
+                    .checked_drain_reference()
+                    .map_err(|err| Error::invalid_data_for_decode(err))?,
+            )
+        } else {
+            None
+        };
+        map_type = value_type;
+    }
+    Ok(ResultOfGetDataMapValue { value: None })
+}
@@ -0,0 +1,308 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use num_bigint::BigUint;
+use ton_block::{Deserializable, MsgAddressInt};
+use ton_sdk::AbiContract;
+use ton_types::{Cell, SliceData, UInt256};
+
+use crate::abi::comment::{read_comment, ENCRYPTED_COMMENT_FUNCTION_ID};
+use crate::abi::decode_message::{decode_body, DecodedMessageBody};
+use crate::abi::known_abis::{MULTISIG_ABI, TIP3_TOKEN_WALLET_ABI};
+use crate::boc::internal::deserialize_cell_from_boc;
+use crate::client::ClientContext;
+use crate::encoding::{account_encode, slice_from_cell};
+use crate::error::ClientResult;
+
+/// Maximum number of the body cells included into the dump.
+const MAX_DUMP_CELLS: usize = 64;
+
+lazy_static! {
+    /// Built-in ABIs of the standard interfaces, parsed on the first use.
+    static ref KNOWN_ABIS: Vec<(KnownInterface, AbiContract)> = [
+        (KnownInterface::Tip3TokenWallet, TIP3_TOKEN_WALLET_ABI),
+        (KnownInterface::Multisig, MULTISIG_ABI),
+    ]
+    .iter()
+    .filter_map(|(interface, abi)| match AbiContract::load(abi.as_bytes()) {
+        Ok(abi) => Some((interface.clone(), abi)),
+        Err(err) => {
+            log::error!("Can not load built-in {:?} ABI: {}", interface, err);
+            None
+        }
+    })
+    .collect();
+}
+
+#[derive(Serialize, Deserialize, ApiType, Debug, PartialEq, Clone)]
+pub enum KnownInterface {
+    /// TIP-3.1 token wallet.
+    Tip3TokenWallet,
+    /// SafeMultisig or SetcodeMultisig wallet.
+    Multisig,
+    /// Text comment: zero function id followed by the UTF-8 text.
+    Comment,
+    /// Encrypted text comment. See `encode_comment_body`.
+    EncryptedComment,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Debug, PartialEq, Clone)]
+pub enum BodyFieldKind {
+    /// 32-bit function id at the start of the body.
+    FunctionId,
+    /// Standard internal address.
+    Address,
+    /// Unsigned integer. The field size is a guess.
+    Uint,
+}
+
+impl Default for BodyFieldKind {
+    fn default() -> Self {
+        BodyFieldKind::Uint
+    }
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct BodyField {
+    /// Offset of the field within the cell data in bits.
+    pub offset: u32,
+
+    /// Field size in bits.
+    pub bit_length: u32,
+
+    /// Plausible field kind.
+    pub kind: BodyFieldKind,
+
+    /// Field value: address in the raw form or decimal integer.
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct BodyCell {
+    /// Cell index in the `cells` list. The root cell has index 0.
+    pub index: u32,
+
+    /// Cell representation hash. Encoded as hex.
+    pub hash: String,
+
+    /// Cell data size in bits.
+    pub bit_length: u32,
+
+    /// Cell data. Encoded as hex. The last byte is padded with zero bits
+    /// if the data size isn't multiple of 8.
+    pub data: String,
+
+    /// Indexes of the referenced cells in the `cells` list.
+    pub refs: Vec<u32>,
+
+    /// Plausible fields found in the cell data.
+    pub fields: Vec<BodyField>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfDecodeMessageBodyHeuristic {
+    /// Message body BOC encoded in `base64`.
+    pub body: String,
+
+    /// True if the body belongs to the internal message.
+    pub is_internal: bool,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfDecodeMessageBodyHeuristic {
+    /// The first 32 bits of the body.
+    ///
+    /// It is the function id for the internal messages and for the function outputs and events.
+    /// The body of the external inbound message starts with the signature and the header,
+    /// so its function id can be found only with the ABI.
+    pub function_id: Option<u32>,
+
+    /// Standard interface recognized by the body.
+    pub interface: Option<KnownInterface>,
+
+    /// Body decoded with the ABI of the recognized interface.
+    pub decoded: Option<DecodedMessageBody>,
+
+    /// Text of the comment body.
+    pub comment: Option<String>,
+
+    /// Dump of the body cells for the explorer-style display. The root cell goes first.
+    /// At most 64 cells are included.
+    pub cells: Vec<BodyCell>,
+}
+
+fn read_uint(slice: &mut SliceData, bits: usize) -> Option<String> {
+    let bytes = slice.get_next_bits(bits).ok()?;
+    let value = BigUint::from_bytes_be(&bytes) >> (bytes.len() * 8 - bits);
+    Some(value.to_string())
+}
+
+fn read_address(slice: &SliceData) -> Option<(SliceData, String)> {
+    let mut probe = slice.clone();
+    match MsgAddressInt::construct_from(&mut probe).ok()? {
+        MsgAddressInt::AddrStd(address)
+            if address.anycast.is_none()
+                && (address.workchain_id == 0 || address.workchain_id == -1) =>
+        {
+            let address = MsgAddressInt::AddrStd(address);
+            Some((probe, account_encode(&address)))
+        }
+        _ => None,
+    }
+}
+
+/// Splits the cell data into the plausible fields: standard addresses and unsigned integers
+/// of the common sizes.
+fn guess_fields(cell: &Cell, with_function_id: bool) -> Vec<BodyField> {
+    let mut fields = Vec::new();
+    let mut slice = match slice_from_cell(cell.clone()) {
+        Ok(slice) => slice,
+        Err(_) => return fields,
+    };
+    let bit_length = slice.remaining_bits();
+    if with_function_id && bit_length >= 32 {
+        if let Some(value) = read_uint(&mut slice, 32) {
+            fields.push(BodyField {
+                offset: 0,
+                bit_length: 32,
+                kind: BodyFieldKind::FunctionId,
+                value,
+            });
+        }
+    }
+    while slice.remaining_bits() > 0 {
+        let offset = bit_length - slice.remaining_bits();
+        if let Some((rest, value)) = read_address(&slice) {
+            fields.push(BodyField {
+                offset: offset as u32,
+                bit_length: (slice.remaining_bits() - rest.remaining_bits()) as u32,
+                kind: BodyFieldKind::Address,
+                value,
+            });
+            slice = rest;
+            continue;
+        }
+        let bits = [128, 64, 32]
+            .iter()
+            .copied()
+            .find(|bits| *bits <= slice.remaining_bits())
+            .unwrap_or_else(|| slice.remaining_bits());
+        match read_uint(&mut slice, bits) {
+            Some(value) => fields.push(BodyField {
+                offset: offset as u32,
+                bit_length: bits as u32,
+                kind: BodyFieldKind::Uint,
+                value,
+            }),
+            None => break,
+        }
+    }
+    fields
+}
+
+fn dump_cells(root: &Cell) -> Vec<BodyCell> {
+    let mut cells = Vec::new();
+    let mut indexes = HashMap::<UInt256, u32>::new();
+    let mut queue = vec![root.clone()];
+    indexes.insert(root.repr_hash(), 0);
+    while cells.len() < queue.len() {
+        let cell = queue[cells.len()].clone();
+        let mut refs = Vec::new();
+        for i in 0..cell.references_count() {
+            let child = match cell.reference(i) {
+                Ok(child) => child,
+                Err(_) => continue,
+            };
+            if let Some(index) = indexes.get(&child.repr_hash()) {
+                refs.push(*index);
+            } else if queue.len() < MAX_DUMP_CELLS {
+                let index = queue.len() as u32;
+                indexes.insert(child.repr_hash(), index);
+                queue.push(child);
+                refs.push(index);
+            }
+        }
+        let bit_length = cell.bit_length();
+        cells.push(BodyCell {
+            index: cells.len() as u32,
+            hash: cell.repr_hash().as_hex_string(),
+            bit_length: bit_length as u32,
+            data: hex::encode(&cell.data()[..(bit_length + 7) / 8]),
+            refs,
+            fields: guess_fields(&cell, cells.is_empty()),
+        });
+    }
+    cells
+}
+
+fn decode_known(
+    body: &SliceData,
+    is_internal: bool,
+) -> Option<(KnownInterface, DecodedMessageBody)> {
+    for (interface, abi) in KNOWN_ABIS.iter() {
+        if let Ok(decoded) = decode_body(abi, body.clone(), is_internal, false, None, None) {
+            return Some((interface.clone(), decoded));
+        }
+    }
+    None
+}
+
+/// Decodes message body without ABI for the human-readable display.
+///
+/// Recognizes the bodies of the standard interfaces (TIP-3 token wallet transfers,
+/// multisig wallet transactions) with the built-in ABIs and the text comments.
+/// Otherwise only the function id and the structured dump of the body cells with
+/// the plausible fields are returned.
+///
+/// Results are heuristic: a body of an arbitrary contract can match the standard interface
+/// if it has the same function id and parameters layout.
+#[api_function]
+pub fn decode_message_body_heuristic(
+    context: Arc<ClientContext>,
+    params: ParamsOfDecodeMessageBodyHeuristic,
+) -> ClientResult<ResultOfDecodeMessageBodyHeuristic> {
+    let (_, body) = deserialize_cell_from_boc(&context, &params.body, "message body")?;
+    let slice = slice_from_cell(body.clone())?;
+    let function_id = if slice.remaining_bits() >= 32 {
+        slice.clone().get_next_u32().ok()
+    } else {
+        None
+    };
+
+    let mut interface = None;
+    let mut decoded = None;
+    let comment = if function_id == Some(0) {
+        read_comment(&body)
+    } else {
+        None
+    };
+    if comment.is_some() {
+        interface = Some(KnownInterface::Comment);
+    } else if function_id == Some(ENCRYPTED_COMMENT_FUNCTION_ID) {
+        interface = Some(KnownInterface::EncryptedComment);
+    } else if let Some((known, body)) = decode_known(&slice, params.is_internal) {
+        interface = Some(known);
+        decoded = Some(body);
+    }
+
+    Ok(ResultOfDecodeMessageBodyHeuristic {
+        function_id,
+        interface,
+        decoded,
+        comment,
+        cells: dump_cells(&body),
+    })
+}
@@ -1,5 +1,6 @@
 use crate::encoding::{decode_abi_number, slice_from_cell};
 use crate::{abi::types::Abi, boc::internal::deserialize_cell_from_boc};
+use crate::abi::function_id::{is_signature_or_id, overload_variants, select_function};
 use crate::abi::{Error, FunctionHeader};
 use crate::boc::internal::deserialize_object_from_boc;
 use crate::client::ClientContext;
@@ -97,7 +98,8 @@ pub struct ParamsOfDecodeMessage {
     #[serde(default)]
     pub allow_partial: bool,
 
-    /// Function name or function id if is known in advance
+    /// Function name, function signature (e.g. `transfer(address,uint128)`)
+    /// or function id if is known in advance
     pub function_name: Option<String>,
 
     // For external (inbound and outbound) messages data_layout parameter is ignored.
@@ -112,14 +114,22 @@ pub fn decode_message(
     context: Arc<ClientContext>,
     params: ParamsOfDecodeMessage,
 ) -> ClientResult<DecodedMessageBody> {
-    let (abi, message) = prepare_decode(&context, &params)?;
+    let message = prepare_decode(&context, &params)?;
     if let Some(body) = message.body() {
         let data_layout = match message.header() {
             ton_block::CommonMsgInfo::ExtInMsgInfo(_) => Some(DataLayout::Input),
             ton_block::CommonMsgInfo::ExtOutMsgInfo(_) => Some(DataLayout::Output),
             ton_block::CommonMsgInfo::IntMsgInfo(_) => params.data_layout,
         };
-        decode_body(abi, body, message.is_internal(), params.allow_partial, params.function_name, data_layout)
+        decode_body_with_abi(
+            &context,
+            &params.abi,
+            body,
+            message.is_internal(),
+            params.allow_partial,
+            params.function_name.clone(),
+            data_layout,
+        )
     } else {
         Err(Error::invalid_message_for_decode(
             "The message body is empty",
@@ -147,7 +157,8 @@ pub struct ParamsOfDecodeMessageBody {
     #[serde(default)]
     pub allow_partial: bool,
 
-    /// Function name or function id if is known in advance
+    /// Function name, function signature (e.g. `transfer(address,uint128)`)
+    /// or function id if is known in advance
     pub function_name: Option<String>,
 
     // By default SDK tries to decode as output and then if decode is not successfull - tries as input.
@@ -161,24 +172,91 @@ pub fn decode_message_body(
     context: Arc<ClientContext>,
     params: ParamsOfDecodeMessageBody,
 ) -> ClientResult<DecodedMessageBody> {
-    let abi = params.abi.abi()?;
     let (_, body) = deserialize_cell_from_boc(&context, &params.body, "message body")?;
     let body = slice_from_cell(body)?;
-    decode_body(abi, body, params.is_internal, params.allow_partial, params.function_name, params.data_layout)
+    decode_body_with_abi(
+        &context,
+        &params.abi,
+        body,
+        params.is_internal,
+        params.allow_partial,
+        params.function_name,
+        params.data_layout,
+    )
 }
 
 fn prepare_decode(
     context: &ClientContext,
     params: &ParamsOfDecodeMessage,
-) -> ClientResult<(AbiContract, ton_block::Message)> {
-    let abi = params.abi.abi()?;
+) -> ClientResult<ton_block::Message> {
     let message = deserialize_object_from_boc(context, &params.message, "message")
         .map_err(|x| Error::invalid_message_for_decode(x))?;
-    Ok((abi, message.object))
+    Ok(message.object)
+}
+
+/// Decodes the body resolving the function overloads.
+///
+/// The function selected by the signature or the id is decoded with the ABI without
+/// the other overloads. The body of the unknown function is also tried with the overloads
+/// of the overloaded functions, since the ABI parser keeps only the last overload.
+fn decode_body_with_abi(
+    context: &ClientContext,
+    abi: &Abi,
+    body: SliceData,
+    is_internal: bool,
+    allow_partial: bool,
+    function_name: Option<String>,
+    data_layout: Option<DataLayout>,
+) -> ClientResult<DecodedMessageBody> {
+    if let Some(selector) = function_name.as_deref().filter(|x| is_signature_or_id(x)) {
+        if let Some(selected) = select_function(&abi.resolve_json_string(context)?, selector)? {
+            let contract = AbiContract::load(selected.abi_json.as_bytes())
+                .map_err(|err| Error::invalid_json(err))?;
+            return decode_body(
+                &contract,
+                body,
+                is_internal,
+                allow_partial,
+                Some(selected.name),
+                data_layout,
+            );
+        }
+    }
+
+    let is_unknown = function_name.is_none();
+    let result = decode_body(
+        &abi.resolve_abi(context)?,
+        body.clone(),
+        is_internal,
+        allow_partial,
+        function_name,
+        data_layout.clone(),
+    );
+    match result {
+        Err(err) if is_unknown => {
+            for variant in overload_variants(&abi.resolve_json_string(context)?)? {
+                let contract = AbiContract::load(variant.as_bytes())
+                    .map_err(|err| Error::invalid_json(err))?;
+                let decoded = decode_body(
+                    &contract,
+                    body.clone(),
+                    is_internal,
+                    allow_partial,
+                    None,
+                    data_layout.clone(),
+                );
+                if decoded.is_ok() {
+                    return decoded;
+                }
+            }
+            Err(err)
+        }
+        result => result,
+    }
 }
 
-fn decode_body(
-    abi: AbiContract,
+pub(crate) fn decode_body(
+    abi: &AbiContract,
     body: SliceData,
     is_internal: bool,
     allow_partial: bool,
@@ -196,7 +274,7 @@ fn decode_body(
 const ERROR_TIP: &str = "The message body does not match the specified ABI. Tip: Please check that you specified message's body, not full BOC.";
 
 fn decode_unknown_function(
-    abi: AbiContract,
+    abi: &AbiContract,
     body: SliceData,
     is_internal: bool,
     allow_partial: bool,
@@ -240,14 +318,14 @@ fn decode_unknown_function(
 }
 
 fn decode_with_function(
-    abi: AbiContract,
+    abi: &AbiContract,
     body: SliceData,
     is_internal: bool,
     allow_partial: bool,
     function_name: String,
     data_layout: Option<DataLayout>,
 ) -> ClientResult<DecodedMessageBody> {
-    let variant = find_abi_function(&abi, &function_name)?;
+    let variant = find_abi_function(abi, &function_name)?;
     match variant {
         AbiFunctionVariant::Function(function) => {
             let decode_output = || {
@@ -317,7 +395,7 @@ fn find_abi_function<'a>(abi: &'a AbiContract, name: &str) -> ClientResult<AbiFu
     } else {
         let function_id: u32 = decode_abi_number(name)?;
         if let Ok(function) = abi.function_by_id(function_id, true)
-            .or_else(|_| abi.function_by_id(function_id, true))
+            .or_else(|_| abi.function_by_id(function_id, false))
         {
             Ok(AbiFunctionVariant::Function(function))
         } else if let Ok(event) = abi.event_by_id(function_id) {
@@ -356,7 +434,7 @@ pub async fn get_signature_data(
     context: Arc<ClientContext>,
     params: ParamsOfGetSignatureData,
 ) -> ClientResult<ResultOfGetSignatureData> {
-    let abi = params.abi.abi()?;
+    let abi = params.abi.resolve_abi(&context)?;
     let message: ton_block::Message = deserialize_object_from_boc(&context, &params.message, "message")?.object;
     if let Some(body) = message.body() {
         let address = message.dst()
@@ -2,11 +2,13 @@ use crate::abi::internal::{
     add_sign_to_message, add_sign_to_message_body, create_tvc_image, try_to_sign_message,
     update_pubkey,
 };
+use crate::abi::comment::{build_comment_body, ParamsOfEncodeCommentBody};
+use crate::abi::function_id::{is_signature_or_id, select_function};
 use crate::abi::{Abi, Error, FunctionHeader, Signer};
 use crate::boc::internal::{deserialize_cell_from_boc, get_boc_hash};
 use crate::boc::tvc::{resolve_state_init_cell, state_init_with_code};
 use crate::client::ClientContext;
-use crate::encoding::{account_decode, account_encode, decode_abi_number, hex_decode};
+use crate::encoding::{account_decode, account_encode, hex_decode, slice_from_cell};
 use crate::error::ClientResult;
 use serde_json::Value;
 use std::str::FromStr;
@@ -90,6 +92,8 @@ impl DeploySet {
 #[derive(Serialize, Deserialize, Clone, Debug, ApiType, Default)]
 pub struct CallSet {
     /// Function name that is being called.
+    /// Or function signature (e.g. `transfer(address,uint128)`), required to call
+    /// the overloaded function.
     /// Or function id encoded as string in hex (starting with 0x).
     pub function_name: String,
 
@@ -189,10 +193,24 @@ impl CallSet {
         abi: &str,
         internal: bool,
     ) -> ClientResult<FunctionCallSet> {
-        let contract = Contract::load(abi.as_bytes()).map_err(|x| Error::invalid_json(x))?;
+        let (abi, func) = match select_function(abi, &self.function_name)? {
+            Some(selected) => (selected.abi_json, selected.name),
+            None if self.function_name.contains('(') => {
+                return Err(Error::invalid_function_name(&self.function_name))
+            }
+            None if is_signature_or_id(&self.function_name) => {
+                return Err(Error::invalid_function_id(
+                    &self.function_name,
+                    "function is not found in contract ABI",
+                ))
+            }
+            None => (abi.to_string(), self.function_name.clone()),
+        };
+        // Parsed ABI is needed only to resolve the header of the external message
         let header = if internal {
             None
         } else {
+            let contract = Contract::load(abi.as_bytes()).map_err(|x| Error::invalid_json(x))?;
             resolve_header(
                 self.header.as_ref(),
                 pubkey,
@@ -202,19 +220,8 @@ impl CallSet {
             )?
         };
 
-        let func = match decode_abi_number::<u32>(&self.function_name) {
-            Ok(id) => {
-                &contract
-                    .function_by_id(id, true)
-                    .map_err(|e| Error::invalid_function_id(&self.function_name, e))?
-                    .name
-            }
-            Err(_) => &self.function_name,
-        }
-        .clone();
-
         Ok(FunctionCallSet {
-            abi: abi.to_string(),
+            abi,
             func,
             header: header.as_ref().map(|x| header_to_string(x)),
             input: self
@@ -502,8 +509,8 @@ pub async fn encode_message(
     context: Arc<ClientContext>,
     params: ParamsOfEncodeMessage,
 ) -> ClientResult<ResultOfEncodeMessage> {
-    let abi_contract = params.abi.abi()?;
-    let abi_string = params.abi.json_string()?;
+    let abi_contract = params.abi.resolve_abi(&context)?;
+    let abi_string = params.abi.resolve_json_string(&context)?;
 
     let public = params.signer.resolve_public_key(context.clone()).await?;
     let (message, data_to_sign, address) = if let Some(deploy_set) = params.deploy_set {
@@ -598,6 +605,11 @@ pub struct ParamsOfEncodeInternalMessage {
 
     /// Enable Instant Hypercube Routing for the message. Default is false.
     pub enable_ihr: Option<bool>,
+
+    /// Text comment of the transfer message. See `encode_comment_body`.
+    ///
+    /// Can't be specified together with `call_set` or `deploy_set`.
+    pub comment: Option<ParamsOfEncodeCommentBody>,
 }
 
 #[derive(Serialize, Deserialize, ApiType, Default, Debug)]
@@ -650,13 +662,19 @@ pub fn encode_internal_message(
         )
     })?);
 
+    if params.comment.is_some() && (params.call_set.is_some() || params.deploy_set.is_some()) {
+        return Err(Error::invalid_comment(
+            "comment can't be specified together with `call_set` or `deploy_set`",
+        ));
+    }
+
     let (message, address) = if let Some(deploy_set) = params.deploy_set {
         let abi = params
             .abi
             .ok_or_else(|| Error::invalid_abi("abi is undefined"))?;
 
-        let abi_contract = abi.abi()?;
-        let abi_string = abi.json_string()?;
+        let abi_contract = abi.resolve_abi(&context)?;
+        let abi_string = abi.resolve_json_string(&context)?;
 
         let workchain_id = deploy_set
             .workchain_id
@@ -706,7 +724,7 @@ pub fn encode_internal_message(
             let abi = params
                 .abi
                 .ok_or_else(|| Error::invalid_abi("abi is undefined"))?
-                .json_string()?;
+                .resolve_json_string(&context)?;
             let message = ton_sdk::Contract::construct_call_int_message_json(
                 address.clone(),
                 src_address,
@@ -719,13 +737,17 @@ pub fn encode_internal_message(
 
             (message.serialized_message, address)
         } else {
+            let body = match params.comment {
+                Some(comment) => Some(slice_from_cell(build_comment_body(&context, comment)?)?),
+                None => None,
+            };
             let message = ton_sdk::Contract::construct_int_message_with_body(
                 address.clone(),
                 src_address,
                 ihr_disabled,
                 bounce,
                 value,
-                None,
+                body,
             )
             .map_err(|err| Error::encode_run_message_failed(err, None))?;
             (message.serialized_message, address)
@@ -802,7 +824,7 @@ pub async fn encode_message_body(
     context: Arc<ClientContext>,
     params: ParamsOfEncodeMessageBody,
 ) -> ClientResult<ResultOfEncodeMessageBody> {
-    let abi = params.abi.json_string()?;
+    let abi = params.abi.resolve_json_string(&context)?;
 
     let public = params.signer.resolve_public_key(context.clone()).await?;
     let call = params.call_set.to_function_call_set(
@@ -914,7 +936,7 @@ pub fn attach_signature(
 ) -> ClientResult<ResultOfAttachSignature> {
     let (boc, _) = deserialize_cell_from_boc(&context, &params.message, "message")?;
     let signed = add_sign_to_message(
-        &params.abi.json_string()?,
+        &params.abi.resolve_json_string(&context)?,
         &hex_decode(&params.signature)?,
         Some(&hex_decode(&params.public_key)?),
         &boc.bytes("message")?,
@@ -955,7 +977,7 @@ pub fn attach_signature_to_message_body(
 ) -> ClientResult<ResultOfAttachSignatureToMessageBody> {
     let (boc, _) = deserialize_cell_from_boc(&context, &params.message, "message body")?;
     let signed = add_sign_to_message_body(
-        &params.abi.json_string()?,
+        &params.abi.resolve_json_string(&context)?,
         &hex_decode(&params.signature)?,
         Some(&hex_decode(&params.public_key)?),
         &boc.bytes("message body")?,
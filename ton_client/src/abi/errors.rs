@@ -19,6 +19,9 @@ pub enum ErrorCode {
     EncodeInitialDataFailed = 314,
     InvalidFunctionName = 315,
     PubKeyNotSupported = 316,
+    AbiNotRegistered = 317,
+    CodeAbiNotFound = 318,
+    InvalidComment = 319,
 }
 
 pub struct Error;
@@ -137,4 +140,34 @@ impl Error {
             )
         )
     }
+
+    pub fn abi_not_registered(handle: u32) -> ClientError {
+        error(
+            ErrorCode::AbiNotRegistered,
+            format!("ABI with handle {} is not registered", handle),
+        )
+    }
+
+    pub fn abi_handle_requires_context(handle: u32) -> ClientError {
+        error(
+            ErrorCode::AbiNotRegistered,
+            format!("ABI handle {} can be resolved only with the client context", handle),
+        )
+    }
+
+    pub fn code_abi_not_found(address: &str, code_hash: Option<&str>) -> ClientError {
+        let mut message = format!("ABI is not registered for the account {}", address);
+        match code_hash {
+            Some(code_hash) => message.push_str(&format!(" and its code hash {}", code_hash)),
+            None => message.push_str(" and the account is not found"),
+        }
+        error(ErrorCode::CodeAbiNotFound, message)
+    }
+
+    pub fn invalid_comment<E: Display>(err: E) -> ClientError {
+        error(
+            ErrorCode::InvalidComment,
+            format!("Invalid comment body: {}", err),
+        )
+    }
 }
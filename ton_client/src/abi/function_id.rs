@@ -1,14 +1,18 @@
 use crate::client::ClientContext;
+use crate::encoding::decode_abi_number;
 use crate::error::ClientResult;
+use serde_json::Value;
 use std::sync::Arc;
 
-use super::{Abi, Error};
+use super::compatibility::param_signature;
+use super::{Abi, AbiParam, Error};
 
 #[derive(Serialize, Deserialize, ApiType, Default)]
 pub struct ParamsOfCalcFunctionId {
     /// Contract ABI.
     pub abi: Abi,
-    /// Contract function name
+    /// Contract function name, function signature (e.g. `transfer(address,uint128)`)
+    /// or function id encoded as string in hex (starting with 0x).
     pub function_name: String,
     /// If set to `true` output function ID will be returned which is used in contract response.
     /// Default is `false`
@@ -21,14 +25,202 @@ pub struct ResultOfCalcFunctionId {
     pub function_id: u32,
 }
 
-/// Calculates contract function ID by contract ABI
+/// Function selected in the ABI by `select_function`.
+pub(crate) struct SelectedFunction {
+    /// ABI JSON without the other overloads of the function.
+    pub abi_json: String,
+    /// Function name.
+    pub name: String,
+}
+
+/// Returns `true` if the function selector is a signature or a function id
+/// rather than a function name.
+pub(crate) fn is_signature_or_id(selector: &str) -> bool {
+    selector.contains('(') || decode_abi_number::<u32>(selector).is_ok()
+}
+
+/// Builds the signature of the ABI JSON function: name and input types, e.g. `transfer(address,uint128)`.
+pub(crate) fn function_signature(function: &Value) -> Option<String> {
+    let name = function["name"].as_str()?;
+    let inputs: Vec<AbiParam> = serde_json::from_value(function["inputs"].clone()).ok()?;
+    let inputs = inputs.iter().map(param_signature).collect::<Vec<_>>();
+    Some(format!("{}({})", name, inputs.join(",")))
+}
+
+/// Function names of the ABI JSON. Other function properties are skipped without building
+/// the JSON tree.
+#[derive(Deserialize)]
+struct AbiFunctionNames {
+    #[serde(default)]
+    functions: Vec<AbiFunctionName>,
+}
+
+#[derive(Deserialize)]
+struct AbiFunctionName {
+    name: String,
+}
+
+fn abi_functions(abi: &Value) -> Vec<Value> {
+    abi["functions"].as_array().cloned().unwrap_or_default()
+}
+
+fn overloads(functions: &[Value], name: &str) -> Vec<usize> {
+    functions
+        .iter()
+        .enumerate()
+        .filter(|(_, function)| function["name"] == name)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Removes the other overloads of the function with the `index` from the ABI JSON.
+pub(crate) fn without_overloads(abi: &Value, index: usize) -> Value {
+    let functions = abi_functions(abi);
+    let name = &functions[index]["name"];
+    let mut abi = abi.clone();
+    abi["functions"] = functions
+        .iter()
+        .enumerate()
+        .filter(|(i, function)| *i == index || function["name"] != *name)
+        .map(|(_, function)| function.clone())
+        .collect();
+    abi
+}
+
+/// The ABI parser keeps only the last overload of the function with the overloaded name.
+/// Returns the ABI JSON variants, each containing one overload of every overloaded function,
+/// so every overload is available in some variant. Returns no variants if there are no overloads.
+pub(crate) fn overload_variants(abi_json: &str) -> ClientResult<Vec<String>> {
+    let abi: Value = serde_json::from_str(abi_json).map_err(|err| Error::invalid_json(err))?;
+    let functions = abi_functions(&abi);
+    let groups = functions
+        .iter()
+        .map(|function| overloads(&functions, function["name"].as_str().unwrap_or_default()))
+        .collect::<Vec<_>>();
+    let count = groups.iter().map(|group| group.len()).max().unwrap_or_default();
+    if count < 2 {
+        return Ok(Vec::new());
+    }
+    let mut variants = Vec::with_capacity(count);
+    for variant in 0..count {
+        let mut abi = abi.clone();
+        abi["functions"] = functions
+            .iter()
+            .zip(&groups)
+            .enumerate()
+            .filter(|(i, (_, group))| group.get(variant).or_else(|| group.last()) == Some(i))
+            .map(|(_, (function, _))| function.clone())
+            .collect();
+        variants.push(abi.to_string());
+    }
+    Ok(variants)
+}
+
+/// Selects the ABI function by the name, the signature (e.g. `transfer(address,uint128)`)
+/// or the input or output function id encoded as `0x` prefixed hex string.
+///
+/// Returns `None` if there is no such function. Plain name of the overloaded function
+/// is ambiguous and is rejected.
+pub(crate) fn select_function(
+    abi_json: &str,
+    selector: &str,
+) -> ClientResult<Option<SelectedFunction>> {
+    let selector = selector.trim();
+    if !is_signature_or_id(selector) {
+        return select_function_by_name(abi_json, selector);
+    }
+
+    let abi: Value = serde_json::from_str(abi_json).map_err(|err| Error::invalid_json(err))?;
+    let functions = abi_functions(&abi);
+
+    if selector.contains('(') {
+        let signature = selector.split_whitespace().collect::<String>();
+        let index = functions
+            .iter()
+            .position(|function| function_signature(function).as_deref() == Some(&signature));
+        return Ok(index.map(|index| SelectedFunction {
+            abi_json: without_overloads(&abi, index).to_string(),
+            name: functions[index]["name"].as_str().unwrap_or_default().to_string(),
+        }));
+    }
+
+    let id = match decode_abi_number::<u32>(selector) {
+        Ok(id) => id,
+        Err(_) => return Ok(None),
+    };
+    let mut variants = overload_variants(abi_json)?;
+    if variants.is_empty() {
+        variants.push(abi_json.to_string());
+    }
+    for variant in variants {
+        let contract = ton_abi::Contract::load(variant.as_bytes())
+            .map_err(|err| Error::invalid_json(err))?;
+        let name = match contract
+            .function_by_id(id, true)
+            .or_else(|_| contract.function_by_id(id, false))
+        {
+            Ok(function) => function.name.clone(),
+            Err(_) => continue,
+        };
+        return Ok(Some(SelectedFunction {
+            abi_json: variant,
+            name,
+        }));
+    }
+    Ok(None)
+}
+
+/// Selects the function by the plain name. Only the function names are read from
+/// the ABI JSON, so the common case of the function without overloads is cheap.
+fn select_function_by_name(
+    abi_json: &str,
+    name: &str,
+) -> ClientResult<Option<SelectedFunction>> {
+    let names: AbiFunctionNames =
+        serde_json::from_str(abi_json).map_err(|err| Error::invalid_json(err))?;
+    match names.functions.iter().filter(|function| function.name == name).count() {
+        0 => Ok(None),
+        1 => Ok(Some(SelectedFunction {
+            abi_json: abi_json.to_string(),
+            name: name.to_string(),
+        })),
+        _ => {
+            let abi: Value =
+                serde_json::from_str(abi_json).map_err(|err| Error::invalid_json(err))?;
+            let functions = abi_functions(&abi);
+            let signatures = overloads(&functions, name)
+                .iter()
+                .filter_map(|i| function_signature(&functions[*i]))
+                .collect::<Vec<_>>();
+            Err(Error::invalid_function_id(
+                name,
+                format!(
+                    "function is overloaded, select it by the signature: {}",
+                    signatures.join(", ")
+                ),
+            ))
+        }
+    }
+}
+
+/// Calculates contract function ID by contract ABI.
+///
+/// The function can be specified by the name, by the signature
+/// (e.g. `transfer(address,uint128)`) which is required for the overloaded functions,
+/// or by the function id.
 #[api_function]
 pub fn calc_function_id(
-    _context: Arc<ClientContext>,
+    context: Arc<ClientContext>,
     params: ParamsOfCalcFunctionId,
 ) -> ClientResult<ResultOfCalcFunctionId> {
-    let abi = params.abi.abi()?;
-    let function = abi.function(&params.function_name)
+    let selected = select_function(
+        &params.abi.resolve_json_string(&context)?,
+        &params.function_name,
+    )?
+    .ok_or_else(|| Error::invalid_function_name(&params.function_name))?;
+    let abi = ton_abi::Contract::load(selected.abi_json.as_bytes())
+        .map_err(|err| Error::invalid_json(err))?;
+    let function = abi.function(&selected.name)
         .map_err(|_| Error::invalid_function_name(&params.function_name))?;
 
     let function_id = if params.output.unwrap_or_default() {
@@ -46,13 +46,14 @@ pub fn update_initial_data(
 ) -> ClientResult<ResultOfUpdateInitialData> {
     let (_, mut data) = deserialize_cell_from_boc(&context, &params.data, "contract data")?;
 
-    if !params.abi.abi()?.data_map_supported() {
+    if !params.abi.resolve_abi(&context)?.data_map_supported() {
         return Err(Error::invalid_abi(
             "This functionality is available only for contracts with ABI < 2.4. For ABI versions >= 2.4 use decode_account_data"
         ));
     }
 
     data = update_initial_data_internal(
+        &context,
         &params.initial_data,
         &params.abi,
         &params.initial_pubkey,
@@ -65,6 +66,7 @@ pub fn update_initial_data(
 }
 
 fn update_initial_data_internal(
+    context: &ClientContext,
     initial_data: &Option<Value>,
     abi: &Abi,
     initial_pubkey: &Option<String>,
@@ -72,7 +74,7 @@ fn update_initial_data_internal(
 ) -> ClientResult<Cell> {
     let data = match initial_data {
         Some(init_data) => {
-            let abi = abi.json_string()?;
+            let abi = abi.resolve_json_string(context)?;
             let data = slice_from_cell(data)?;
             ton_abi::json_abi::update_contract_data(&abi, &init_data.to_string(), data)
                 .map_err(|err| Error::encode_init_data_failed(err))?
@@ -132,19 +134,20 @@ pub fn encode_initial_data(
     context: Arc<ClientContext>,
     params: ParamsOfEncodeInitialData,
 ) -> ClientResult<ResultOfEncodeInitialData> {
-    let abi = params.abi.abi()?;
+    let abi = params.abi.resolve_abi(&context)?;
     let data = if !abi.data_map_supported() {
         if params.initial_pubkey.is_some() {
             return Err(Error::initial_pubkey_not_supported(abi.version()));
         }
         builder_to_cell(ton_abi::json_abi::encode_storage_fields(
-                &params.abi.json_string()?,
+                &params.abi.resolve_json_string(&context)?,
                 params.initial_data.map(|data| data.to_string()).as_deref(),
             )
             .map_err(|err| Error::encode_init_data_failed(err))?
         )?
     } else {
         update_initial_data_internal(
+            &context,
             &params.initial_data,
             &params.abi,
             &params.initial_pubkey,
@@ -192,7 +195,7 @@ pub fn decode_initial_data(
     let (_, data) = deserialize_cell_from_boc(&context, &params.data, "contract data")?;
     let data = slice_from_cell(data)?;
 
-    let abi = params.abi.abi()?;
+    let abi = params.abi.resolve_abi(&context)?;
 
     if !abi.data_map_supported() {
         return Err(Error::invalid_abi(
@@ -0,0 +1,124 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! ABIs of the standard interfaces recognized by `decode_message_body_heuristic`.
+//! Only the functions and events significant for the message display are included.
+
+/// TIP-3.1 token wallet.
+pub(crate) const TIP3_TOKEN_WALLET_ABI: &str = r#"{
+    "ABI version": 2,
+    "version": "2.2",
+    "header": ["pubkey", "time", "expire"],
+    "functions": [
+        {
+            "name": "transfer",
+            "inputs": [
+                {"name": "amount", "type": "uint128"},
+                {"name": "recipient", "type": "address"},
+                {"name": "deployWalletValue", "type": "uint128"},
+                {"name": "remainingGasTo", "type": "address"},
+                {"name": "notify", "type": "bool"},
+                {"name": "payload", "type": "cell"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "transferToWallet",
+            "inputs": [
+                {"name": "amount", "type": "uint128"},
+                {"name": "recipientTokenWallet", "type": "address"},
+                {"name": "remainingGasTo", "type": "address"},
+                {"name": "notify", "type": "bool"},
+                {"name": "payload", "type": "cell"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "acceptTransfer",
+            "id": "0x67A0B95F",
+            "inputs": [
+                {"name": "amount", "type": "uint128"},
+                {"name": "sender", "type": "address"},
+                {"name": "remainingGasTo", "type": "address"},
+                {"name": "notify", "type": "bool"},
+                {"name": "payload", "type": "cell"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "acceptMint",
+            "id": "0x4384F298",
+            "inputs": [
+                {"name": "amount", "type": "uint128"},
+                {"name": "remainingGasTo", "type": "address"},
+                {"name": "notify", "type": "bool"},
+                {"name": "payload", "type": "cell"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "burn",
+            "inputs": [
+                {"name": "amount", "type": "uint128"},
+                {"name": "remainingGasTo", "type": "address"},
+                {"name": "callbackTo", "type": "address"},
+                {"name": "payload", "type": "cell"}
+            ],
+            "outputs": []
+        }
+    ],
+    "events": [],
+    "data": [],
+    "fields": []
+}"#;
+
+/// SafeMultisig and SetcodeMultisig wallets.
+pub(crate) const MULTISIG_ABI: &str = r#"{
+    "ABI version": 2,
+    "header": ["pubkey", "time", "expire"],
+    "functions": [
+        {
+            "name": "sendTransaction",
+            "inputs": [
+                {"name": "dest", "type": "address"},
+                {"name": "value", "type": "uint128"},
+                {"name": "bounce", "type": "bool"},
+                {"name": "flags", "type": "uint8"},
+                {"name": "payload", "type": "cell"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "submitTransaction",
+            "inputs": [
+                {"name": "dest", "type": "address"},
+                {"name": "value", "type": "uint128"},
+                {"name": "bounce", "type": "bool"},
+                {"name": "allBalance", "type": "bool"},
+                {"name": "payload", "type": "cell"}
+            ],
+            "outputs": [
+                {"name": "transId", "type": "uint64"}
+            ]
+        },
+        {
+            "name": "confirmTransaction",
+            "inputs": [
+                {"name": "transactionId", "type": "uint64"}
+            ],
+            "outputs": []
+        }
+    ],
+    "events": [],
+    "data": []
+}"#;
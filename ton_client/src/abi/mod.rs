@@ -14,14 +14,22 @@
 #[cfg(test)]
 mod tests;
 
+pub mod bindings;
+pub(crate) mod code_registry;
+pub(crate) mod comment;
+pub(crate) mod compatibility;
 pub(crate) mod decode_boc;
 pub(crate) mod decode_data;
+pub(crate) mod decode_heuristic;
 pub(crate) mod decode_message;
 pub(crate) mod encode_account;
 pub(crate) mod encode_boc;
 pub(crate) mod encode_message;
 pub(crate) mod function_id;
 pub(crate) mod init_data;
+pub(crate) mod known_abis;
+pub(crate) mod registry;
+pub(crate) mod validate;
 
 mod errors;
 mod internal;
@@ -30,8 +38,30 @@ mod types;
 
 use serde::{Deserialize, Deserializer};
 
+pub use code_registry::{
+    decode_message_auto, register_code_abi, remove_code_abi, ParamsOfDecodeMessageAuto,
+    ParamsOfRegisterCodeAbi, ParamsOfRemoveCodeAbi, ResultOfDecodeMessageAuto,
+};
+pub use comment::{
+    decode_comment_body, encode_comment_body, CommentEncryption, ParamsOfDecodeCommentBody,
+    ParamsOfEncodeCommentBody, ResultOfDecodeCommentBody, ResultOfEncodeCommentBody,
+    COMMENT_FUNCTION_ID, ENCRYPTED_COMMENT_FUNCTION_ID,
+};
+pub use compatibility::{
+    check_compatibility, AbiChange, AbiChangeKind, ParamsOfCheckAbiCompatibility,
+    ResultOfCheckAbiCompatibility,
+};
 pub use decode_boc::{decode_boc, ParamsOfDecodeBoc, ResultOfDecodeBoc};
-pub use decode_data::{decode_account_data, ParamsOfDecodeAccountData, ResultOfDecodeAccountData};
+pub use decode_data::{
+    decode_account_data, decode_account_data_lazy, decode_account_data_map, get_data_map_value,
+    AccountDataMap, DataMapKey, ParamsOfDecodeAccountData, ParamsOfDecodeAccountDataLazy,
+    ParamsOfDecodeAccountDataMap, ParamsOfGetDataMapValue, ResultOfDecodeAccountData,
+    ResultOfDecodeAccountDataLazy, ResultOfDecodeAccountDataMap, ResultOfGetDataMapValue,
+};
+pub use decode_heuristic::{
+    decode_message_body_heuristic, BodyCell, BodyField, BodyFieldKind, KnownInterface,
+    ParamsOfDecodeMessageBodyHeuristic, ResultOfDecodeMessageBodyHeuristic,
+};
 pub use decode_message::{
     decode_message, decode_message_body, get_signature_data, DecodedMessageBody, MessageBodyType,
     ParamsOfDecodeMessage, ParamsOfDecodeMessageBody, DataLayout, ParamsOfGetSignatureData,
@@ -57,7 +87,13 @@ pub use init_data::{
     ParamsOfEncodeInitialData, ParamsOfDecodeInitialData, ParamsOfUpdateInitialData,
     ResultOfEncodeInitialData, ResultOfDecodeInitialData, ResultOfUpdateInitialData,
 };
+pub use registry::{
+    register_abi, remove_abi, ParamsOfRegisterAbi, ParamsOfRemoveAbi, ResultOfRegisterAbi,
+};
 pub use signing::Signer;
+pub use validate::{
+    validate, AbiDiagnostic, AbiDiagnosticSeverity, ParamsOfValidateAbi, ResultOfValidateAbi,
+};
 pub use types::{
     Abi, AbiContract, AbiData, AbiEvent, AbiFunction, AbiHandle, AbiParam, FunctionHeader,
 };
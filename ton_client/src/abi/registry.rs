@@ -0,0 +1,97 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::sync::Arc;
+
+use lockfree::map::ReadGuard;
+
+use crate::abi::{Abi, AbiHandle, Error};
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+
+/// ABI parsed at the registration.
+pub(crate) struct RegisteredAbi {
+    pub contract: Arc<ton_abi::Contract>,
+    pub json: String,
+}
+
+pub(crate) fn get_registered_abi<'context>(
+    context: &'context ClientContext,
+    handle: &AbiHandle,
+) -> ClientResult<ReadGuard<'context, u32, RegisteredAbi>> {
+    context
+        .abis
+        .get(&handle.0)
+        .ok_or_else(|| Error::abi_not_registered(handle.0))
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfRegisterAbi {
+    /// Contract ABI to register.
+    pub abi: Abi,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfRegisterAbi {
+    /// ABI handle.
+    ///
+    /// Can be passed as `Abi::Handle` to any function accepting ABI.
+    pub handle: AbiHandle,
+}
+
+/// Parses ABI and registers it in the client context.
+///
+/// Functions receiving the returned handle as `Abi::Handle` use the parsed ABI
+/// without parsing the ABI JSON on each call. It is recommended for hot paths
+/// like message decoding and transaction iterators.
+///
+/// Registered ABI must be removed with `remove_abi` when it is not needed anymore.
+#[api_function]
+pub fn register_abi(
+    context: Arc<ClientContext>,
+    params: ParamsOfRegisterAbi,
+) -> ClientResult<ResultOfRegisterAbi> {
+    let json = params.abi.resolve_json_string(&context)?;
+    let contract =
+        ton_abi::Contract::load(json.as_bytes()).map_err(|err| Error::invalid_json(err))?;
+    Ok(ResultOfRegisterAbi {
+        handle: register_parsed_abi(&context, contract, json),
+    })
+}
+
+/// Registers the already parsed ABI.
+pub(crate) fn register_parsed_abi(
+    context: &ClientContext,
+    contract: ton_abi::Contract,
+    json: String,
+) -> AbiHandle {
+    let id = context.get_next_id();
+    assert!(context
+        .abis
+        .insert(id, RegisteredAbi { contract: Arc::new(contract), json })
+        .is_none());
+    AbiHandle(id)
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfRemoveAbi {
+    /// ABI handle returned by `register_abi`.
+    pub handle: AbiHandle,
+}
+
+/// Removes ABI registered with `register_abi`.
+#[api_function]
+pub fn remove_abi(context: Arc<ClientContext>, params: ParamsOfRemoveAbi) -> ClientResult<()> {
+    context.abis.remove(&params.handle.0);
+    Ok(())
+}
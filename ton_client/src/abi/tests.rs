@@ -14,15 +14,19 @@ use crate::boc::{
     parse_message, ParamsOfDecodeStateInit, ParamsOfGetCodeFromTvc, ParamsOfParse,
     ResultOfDecodeStateInit, ResultOfGetCodeFromTvc, ResultOfEncodeBoc,
 };
+use crate::client::NetworkMock;
 use crate::crypto::KeyPair;
 use crate::encoding::account_decode;
+use crate::net::NetworkConfig;
 use crate::tests::{TestClient, EVENTS, EVENTS_OLD, HELLO, T24_INIT_DATA};
 use crate::utils::conversion::abi_uint;
 use crate::{
     abi::decode_message::{DecodedMessageBody, MessageBodyType, ParamsOfDecodeMessage},
     boc::ResultOfParse,
 };
+use crate::{ClientConfig, ClientContext};
 use std::future::Future;
+use std::sync::Arc;
 
 use crate::boc::tvc::resolve_state_init_cell;
 use ever_struct::scheme::TVC;
@@ -777,6 +781,7 @@ async fn test_encode_internal_message_run(
                 value: "1000000000".to_string(),
                 bounce: Some(true),
                 enable_ihr: None,
+                comment: None,
             },
         )
         .await?;
@@ -837,6 +842,7 @@ async fn test_encode_internal_message_deploy(
                 value: "1000000000".to_string(),
                 bounce: None,
                 enable_ihr: None,
+                comment: None,
             },
         )
         .await?;
@@ -1662,3 +1668,1089 @@ fn test_abi_v24_changes() {
         .unwrap_err();
     assert_eq!(error.code, ErrorCode::PubKeyNotSupported as u32);
 }
+
+const MAP_DATA_ABI: &str = r#"{
+	"ABI version": 2,
+	"version": "2.1",
+	"header": ["time"],
+	"functions": [],
+	"data": [],
+	"events": [],
+	"fields": [
+		{"name":"owner","type":"uint32"},
+		{"name":"balances","type":"map(int32,uint64)"},
+		{"name":"total","type":"uint32"}
+	]
+}"#;
+
+#[test]
+fn test_decode_account_data_map() {
+    let client = TestClient::new();
+    let abi_contract: AbiContract = serde_json::from_str(MAP_DATA_ABI).unwrap();
+    let data = client
+        .request::<_, ResultOfAbiEncodeBoc>(
+            "abi.encode_boc",
+            ParamsOfAbiEncodeBoc {
+                params: abi_contract.fields,
+                data: json!({
+                    "owner": 1,
+                    "balances": {"-2": 20, "-1": 10, "0": 0, "5": 50, "7": 70},
+                    "total": 150,
+                }),
+                boc_cache: None,
+            },
+        )
+        .unwrap()
+        .boc;
+    let abi = Abi::Json(MAP_DATA_ABI.to_owned());
+
+    let lazy: ResultOfDecodeAccountDataLazy = client
+        .request(
+            "abi.decode_account_data_lazy",
+            ParamsOfDecodeAccountDataLazy {
+                abi: abi.clone(),
+                data: data.clone(),
+                allow_partial: false,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        lazy.data,
+        json!({
+            "owner": "1",
+            "balances": null,
+            "total": "150",
+        })
+    );
+    assert_eq!(
+        lazy.maps,
+        vec![AccountDataMap {
+            name: "balances".to_owned(),
+            is_empty: false,
+        }]
+    );
+
+    let page = |field: &str, start_key: Option<&str>, end_key: Option<&str>, limit: u32| {
+        client.request::<_, ResultOfDecodeAccountDataMap>(
+            "abi.decode_account_data_map",
+            ParamsOfDecodeAccountDataMap {
+                abi: abi.clone(),
+                data: data.clone(),
+                field: field.to_owned(),
+                start_key: start_key.map(str::to_owned),
+                end_key: end_key.map(str::to_owned),
+                limit: Some(limit),
+            },
+        )
+    };
+
+    let first = page("balances", None, None, 2).unwrap();
+    assert_eq!(first.entries, json!({"-2": "20", "-1": "10"}));
+    assert_eq!(first.next_key.as_deref(), Some("0"));
+
+    let second = page("balances", first.next_key.as_deref(), None, 2).unwrap();
+    assert_eq!(second.entries, json!({"0": "0", "5": "50"}));
+    assert_eq!(second.next_key.as_deref(), Some("7"));
+
+    let last = page("balances", second.next_key.as_deref(), None, 2).unwrap();
+    assert_eq!(last.entries, json!({"7": "70"}));
+    assert_eq!(last.next_key, None);
+
+    let range = page("balances", Some("-1"), Some("5"), 10).unwrap();
+    assert_eq!(range.entries, json!({"-1": "10", "0": "0"}));
+    assert_eq!(range.next_key, None);
+
+    // Page starts from the next existing key
+    let missing_start = page("balances", Some("1"), None, 1).unwrap();
+    assert_eq!(missing_start.entries, json!({"5": "50"}));
+    assert_eq!(missing_start.next_key.as_deref(), Some("7"));
+    let below_min = page("balances", Some("-100"), None, 1).unwrap();
+    assert_eq!(below_min.entries, json!({"-2": "20"}));
+    assert_eq!(below_min.next_key.as_deref(), Some("-1"));
+    let above_max = page("balances", Some("8"), None, 10).unwrap();
+    assert_eq!(above_max.entries, json!({}));
+    assert_eq!(above_max.next_key, None);
+
+    let error = page("owner", None, None, 10).unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidAbi as u32);
+    let error = page("balances", Some("abc"), None, 10).unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidData as u32);
+}
+
+const MAP_VALUE_ABI: &str = r#"{
+	"ABI version": 2,
+	"version": "2.1",
+	"header": ["time"],
+	"functions": [],
+	"data": [],
+	"events": [],
+	"fields": [
+		{"name":"balances","type":"map(address,uint128)"},
+		{"name":"allowances","type":"map(address,map(address,uint128))"},
+		{
+			"name":"positions",
+			"type":"map(uint64,tuple)",
+			"components":[
+				{"name":"x","type":"int32"},
+				{"name":"y","type":"int32"}
+			]
+		}
+	]
+}"#;
+
+#[test]
+fn test_get_data_map_value() {
+    let client = TestClient::new();
+    let owner = "0:1111111111111111111111111111111111111111111111111111111111111111";
+    let spender = "0:2222222222222222222222222222222222222222222222222222222222222222";
+    let abi_contract: AbiContract = serde_json::from_str(MAP_VALUE_ABI).unwrap();
+    let data = client
+        .request::<_, ResultOfAbiEncodeBoc>(
+            "abi.encode_boc",
+            ParamsOfAbiEncodeBoc {
+                params: abi_contract.fields,
+                data: json!({
+                    "balances": {owner: 100, spender: 200},
+                    "allowances": {owner: {spender: 50}},
+                    // Struct key {uint32 a; uint32 b} = {1, 2} packed into uint64
+                    "positions": {"4294967298": {"x": -3, "y": 4}},
+                }),
+                boc_cache: None,
+            },
+        )
+        .unwrap()
+        .boc;
+
+    let get = |field: &str, keys: Vec<DataMapKey>| {
+        client
+            .request::<_, ResultOfGetDataMapValue>(
+                "abi.get_data_map_value",
+                ParamsOfGetDataMapValue {
+                    abi: Abi::Json(MAP_VALUE_ABI.to_owned()),
+                    data: data.clone(),
+                    field: field.to_owned(),
+                    keys,
+                },
+            )
+            .map(|result| result.value)
+    };
+    let key = |value: &str| DataMapKey::Value {
+        value: value.to_owned(),
+    };
+
+    assert_eq!(get("balances", vec![key(owner)]).unwrap(), Some(json!("100")));
+    assert_eq!(get("balances", vec![key(spender)]).unwrap(), Some(json!("200")));
+    assert_eq!(
+        get(
+            "balances",
+            vec![key("0:3333333333333333333333333333333333333333333333333333333333333333")]
+        )
+        .unwrap(),
+        None
+    );
+
+    assert_eq!(
+        get("allowances", vec![key(owner), key(spender)]).unwrap(),
+        Some(json!("50"))
+    );
+    assert_eq!(get("allowances", vec![key(spender), key(owner)]).unwrap(), None);
+    let error = get("allowances", vec![key(owner), key(spender), key(owner)]).unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidData as u32);
+
+    let struct_key = |a: u32, b: u32| DataMapKey::Struct {
+        params: vec![
+            AbiParam {
+                name: "a".to_owned(),
+                param_type: "uint32".to_owned(),
+                ..Default::default()
+            },
+            AbiParam {
+                name: "b".to_owned(),
+                param_type: "uint32".to_owned(),
+                ..Default::default()
+            },
+        ],
+        value: json!({"a": a, "b": b}),
+    };
+    assert_eq!(
+        get("positions", vec![struct_key(1, 2)]).unwrap(),
+        Some(json!({"x": "-3", "y": "4"}))
+    );
+    assert_eq!(get("positions", vec![struct_key(2, 1)]).unwrap(), None);
+    assert_eq!(
+        get("positions", vec![key("4294967298")]).unwrap(),
+        Some(json!({"x": "-3", "y": "4"}))
+    );
+}
+
+#[test]
+fn test_register_abi() {
+    let state = deserialize_object_from_base64::<StateInit>(ACCOUNT_STATE, "state").unwrap();
+    let data = serialize_cell_to_base64(&state.object.data.unwrap(), "data").unwrap();
+
+    let client = TestClient::new();
+    let handle = client
+        .request::<_, ResultOfRegisterAbi>(
+            "abi.register_abi",
+            ParamsOfRegisterAbi {
+                abi: Abi::Json(ACCOUNT_ABI.to_owned()),
+            },
+        )
+        .unwrap()
+        .handle;
+
+    let decode = |abi: Abi| {
+        client.request::<_, ResultOfDecodeAccountData>(
+            "abi.decode_account_data",
+            ParamsOfDecodeAccountData {
+                data: data.clone(),
+                abi,
+                allow_partial: false,
+            },
+        )
+    };
+    assert_eq!(
+        decode(Abi::Handle(handle)).unwrap().data,
+        decode(Abi::Json(ACCOUNT_ABI.to_owned())).unwrap().data,
+    );
+
+    // Registered ABI is shared, not copied
+    let context = client.context();
+    assert!(std::sync::Arc::ptr_eq(
+        &Abi::Handle(handle).resolve_abi(&context).unwrap(),
+        &Abi::Handle(handle).resolve_abi(&context).unwrap(),
+    ));
+
+    let _: () = client
+        .request("abi.remove_abi", ParamsOfRemoveAbi { handle })
+        .unwrap();
+    let error = decode(Abi::Handle(handle)).unwrap_err();
+    assert_eq!(error.code, ErrorCode::AbiNotRegistered as u32);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_decode_message_auto() {
+    let storage_path =
+        std::env::temp_dir().join(format!("tonclient-{}", rand::random::<u32>()));
+    let new_context = || {
+        Arc::new(
+            ClientContext::new(ClientConfig {
+                network: NetworkConfig {
+                    endpoints: Some(vec!["a".into()]),
+                    ..Default::default()
+                },
+                local_storage_path: Some(storage_path.to_string_lossy().to_string()),
+                ..Default::default()
+            })
+            .unwrap(),
+        )
+    };
+    let context = new_context();
+    let abi = TestClient::abi(EVENTS, Some(2));
+    let address = format!("0:{}", "1".repeat(64));
+    let code_hash = "2".repeat(64);
+
+    let message = encode_message(
+        context.clone(),
+        ParamsOfEncodeMessage {
+            abi: abi.clone(),
+            address: Some(address.clone()),
+            call_set: CallSet::some_with_function_and_input("returnValue", json!({ "id": "0x1" })),
+            signer: Signer::None,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap()
+    .message;
+
+    NetworkMock::build()
+        .url("a")
+        .info(context.env.now_ms(), 1000)
+        .ok(&json!({ "data": { "accounts": [{ "code_hash": code_hash }] } }).to_string())
+        .ok(&json!({ "data": { "accounts": [] } }).to_string())
+        .reset_client(&context)
+        .await;
+
+    let decode = |context: Arc<ClientContext>| {
+        decode_message_auto(
+            context,
+            ParamsOfDecodeMessageAuto {
+                message: message.clone(),
+                ..Default::default()
+            },
+        )
+    };
+
+    register_code_abi(
+        context.clone(),
+        ParamsOfRegisterCodeAbi {
+            abi: abi.clone(),
+            code_hash: Some(code_hash.clone()),
+            address: None,
+        },
+    )
+    .await
+    .unwrap();
+    let result = decode(context.clone()).await.unwrap();
+    assert_eq!(result.decoded.name, "returnValue");
+    assert_eq!(result.decoded.body_type, MessageBodyType::Input);
+    assert_eq!(result.address, address);
+    assert_eq!(result.code_hash, Some(code_hash.clone()));
+
+    // ABI registered for the address is used without the network
+    register_code_abi(
+        context.clone(),
+        ParamsOfRegisterCodeAbi {
+            abi: abi.clone(),
+            code_hash: None,
+            address: Some(address.clone()),
+        },
+    )
+    .await
+    .unwrap();
+    let result = decode(context.clone()).await.unwrap();
+    assert_eq!(result.decoded.name, "returnValue");
+    assert_eq!(result.code_hash, None);
+
+    // Registered ABI decodes transaction tree and iterator messages
+    let parsed = parse_message(context.clone(), ParamsOfParse { boc: message.clone() })
+        .unwrap()
+        .parsed;
+    let mut resolver = crate::abi::code_registry::CodeAbiResolver::default();
+    resolver.resolve(&context, &[&parsed]).await;
+    let decoded = resolver.decode_body(&context, &parsed).unwrap();
+    assert_eq!(decoded.name, "returnValue");
+    assert_eq!(decoded.body_type, MessageBodyType::Input);
+
+    // Registry is persisted in the local storage
+    let result = decode(new_context()).await.unwrap();
+    assert_eq!(result.decoded.name, "returnValue");
+
+    remove_code_abi(
+        context.clone(),
+        ParamsOfRemoveCodeAbi {
+            code_hash: Some(code_hash),
+            address: Some(address),
+        },
+    )
+    .await
+    .unwrap();
+    let error = decode(context.clone()).await.unwrap_err();
+    assert_eq!(error.code, ErrorCode::CodeAbiNotFound as u32);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_code_abi_storage_keys() {
+    use crate::abi::code_registry::{address_key, code_hash_key, CodeAbiResolver};
+    use crate::client::storage::KeyValueStorage;
+
+    let storage_path =
+        std::env::temp_dir().join(format!("tonclient-{}", rand::random::<u32>()));
+    let storage = crate::client::LocalStorage::new(
+        Some(storage_path.to_string_lossy().to_string()),
+        "code_abis".to_string(),
+    )
+    .await
+    .unwrap();
+    let keys = vec![
+        code_hash_key(&"A".repeat(64)),
+        address_key(&format!("0:{}", "1".repeat(64))).unwrap(),
+        address_key(&format!("-1:{}", "2".repeat(64))).unwrap(),
+    ];
+    assert_eq!(keys[2], format!("address_n1_{}", "2".repeat(64)));
+    for key in &keys {
+        storage.put_str(key, key).await.unwrap();
+        assert_eq!(storage.get_str(key).await.unwrap().as_ref(), Some(key));
+    }
+
+    // Registry is not touched until a code ABI is used in the context
+    let context = Arc::new(
+        ClientContext::new(ClientConfig {
+            local_storage_path: Some(storage_path.join("unused").to_string_lossy().to_string()),
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+    let message = json!({ "msg_type": 0, "dst": format!("0:{}", "1".repeat(64)), "body": "" });
+    let mut resolver = CodeAbiResolver::default();
+    resolver.resolve(&context, &[&message]).await;
+    assert_eq!(resolver.decode_body(&context, &message), None);
+    assert!(!storage_path.join("unused").exists());
+}
+
+#[test]
+fn test_bindings_numbers() {
+    use crate::abi::bindings::{decode_output, encode_input};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+    struct Params {
+        #[serde(with = "crate::abi::bindings::number")]
+        value: u128,
+        #[serde(with = "crate::abi::bindings::number")]
+        delta: i32,
+        #[serde(with = "crate::abi::bindings::number_option")]
+        flags: Option<u8>,
+        #[serde(with = "crate::abi::bindings::number_vec")]
+        ids: Vec<u64>,
+    }
+
+    let params: Params = decode_output(Some(json!({
+        "value": "0x0de0b6b3a7640000",
+        "delta": "-0x10",
+        "flags": 3,
+        "ids": ["1", "0x2", 3],
+    })))
+    .unwrap();
+    assert_eq!(
+        params,
+        Params {
+            value: 1_000_000_000_000_000_000,
+            delta: -16,
+            flags: Some(3),
+            ids: vec![1, 2, 3],
+        }
+    );
+    assert_eq!(
+        encode_input(&params).unwrap(),
+        json!({
+            "value": "1000000000000000000",
+            "delta": "-16",
+            "flags": "3",
+            "ids": ["1", "2", "3"],
+        })
+    );
+
+    let params: Params = decode_output(Some(json!({
+        "value": "1",
+        "delta": "0",
+        "flags": null,
+        "ids": [],
+    })))
+    .unwrap();
+    assert_eq!(params.flags, None);
+
+    assert!(decode_output::<Params>(Some(json!({
+        "value": "-1",
+        "delta": "0",
+        "flags": null,
+        "ids": [],
+    })))
+    .is_err());
+}
+
+#[test]
+fn test_validate() {
+    use crate::abi::{AbiDiagnostic, AbiDiagnosticSeverity, ParamsOfValidateAbi, ResultOfValidateAbi};
+
+    let client = TestClient::new();
+    let validate = |abi: Value| {
+        client
+            .request::<_, ResultOfValidateAbi>(
+                "abi.validate",
+                ParamsOfValidateAbi {
+                    abi: Abi::Json(abi.to_string()),
+                },
+            )
+            .unwrap()
+    };
+    let find = |result: &ResultOfValidateAbi, path: &str| -> Option<AbiDiagnostic> {
+        result.diagnostics.iter().find(|x| x.path == path).cloned()
+    };
+
+    let result = client
+        .request::<_, ResultOfValidateAbi>(
+            "abi.validate",
+            ParamsOfValidateAbi {
+                abi: TestClient::abi(HELLO, Some(2)),
+            },
+        )
+        .unwrap();
+    assert!(result.is_valid);
+
+    let result = validate(json!({
+        "ABI version": 2,
+        "version": "2.3",
+        "header": ["time", "nonce", "time"],
+        "functions": [
+            {
+                "name": "send",
+                "inputs": [
+                    {"name": "dest", "type": "address"},
+                    {"name": "value", "type": "uint512"},
+                    {"name": "items", "type": "map(bool,uint32)"},
+                    {"name": "info", "type": "tuple", "components": [
+                        {"name": "x", "type": "fixed128"}
+                    ]},
+                    {"name": "next", "type": "ref(cell)"}
+                ],
+                "outputs": []
+            },
+            {"name": "send", "inputs": [], "outputs": [], "id": "42"}
+        ],
+        "events": [],
+        "data": [
+            {"key": 1, "name": "owner", "type": "uint256"},
+            {"key": 1, "name": "counter", "type": "uint32"}
+        ],
+        "fields": [
+            {"name": "owner", "type": "uint256", "init": true}
+        ]
+    }));
+    assert!(!result.is_valid);
+    for path in &[
+        "header[1]",
+        "header[2]",
+        "functions[0].inputs[1].type",
+        "functions[0].inputs[2].type",
+        "functions[0].inputs[3].components[0].type",
+        "functions[0].inputs[4].type",
+        "functions[1].id",
+        "data[1].key",
+        "fields[0].init",
+    ] {
+        let diagnostic = find(&result, path).unwrap_or_else(|| panic!("no diagnostic for {}", path));
+        assert_eq!(diagnostic.severity, AbiDiagnosticSeverity::Error, "{}", path);
+    }
+    let diagnostic = find(&result, "data[1].name").unwrap();
+    assert_eq!(diagnostic.severity, AbiDiagnosticSeverity::Warning);
+    // Overloaded function
+    let diagnostic = find(&result, "functions[1].name").unwrap();
+    assert_eq!(diagnostic.severity, AbiDiagnosticSeverity::Warning);
+
+    let result = validate(json!({
+        "ABI version": 2,
+        "version": "2.2",
+        "functions": [
+            {"name": "first", "inputs": [], "outputs": [], "id": "0x00000001"},
+            {"name": "second", "inputs": [], "outputs": [], "id": "0x00000001"}
+        ],
+        "events": []
+    }));
+    assert!(!result.is_valid);
+    let diagnostic = find(&result, "functions[1]").unwrap();
+    assert_eq!(diagnostic.severity, AbiDiagnosticSeverity::Error);
+    assert!(diagnostic.message.contains("0x00000001"));
+
+    let result = validate(json!({"ABI version": 3}));
+    assert!(!result.is_valid);
+    assert!(find(&result, "ABI version").is_some());
+
+    let result = validate(json!([]));
+    assert!(!result.is_valid);
+    assert_eq!(result.diagnostics[0].path, "");
+}
+
+#[test]
+fn test_check_compatibility() {
+    use crate::abi::{AbiChangeKind, ParamsOfCheckAbiCompatibility, ResultOfCheckAbiCompatibility};
+
+    let client = TestClient::new();
+    let old_abi = json!({
+        "ABI version": 2,
+        "version": "2.2",
+        "header": ["time", "expire"],
+        "functions": [
+            {
+                "name": "transfer",
+                "inputs": [
+                    {"name": "dest", "type": "address"},
+                    {"name": "value", "type": "uint128"}
+                ],
+                "outputs": []
+            },
+            {"name": "getBalance", "inputs": [], "outputs": [{"name": "value0", "type": "uint128"}]},
+            {"name": "burn", "inputs": [], "outputs": []}
+        ],
+        "events": [
+            {"name": "Transferred", "inputs": [{"name": "value", "type": "uint128"}]},
+            {"name": "Burned", "inputs": []}
+        ],
+        "data": [],
+        "fields": [
+            {"name": "_pubkey", "type": "uint256"},
+            {"name": "_timestamp", "type": "uint64"},
+            {"name": "balance", "type": "uint128"},
+            {"name": "owner", "type": "address"}
+        ]
+    });
+    let check = |new_abi: &Value| {
+        client
+            .request::<_, ResultOfCheckAbiCompatibility>(
+                "abi.check_compatibility",
+                ParamsOfCheckAbiCompatibility {
+                    old_abi: Abi::Json(old_abi.to_string()),
+                    new_abi: Abi::Json(new_abi.to_string()),
+                },
+            )
+            .unwrap()
+    };
+    let change = |result: &ResultOfCheckAbiCompatibility, path: &str| {
+        result
+            .changes
+            .iter()
+            .find(|x| x.path == path)
+            .map(|x| x.kind.clone())
+    };
+
+    let result = check(&old_abi);
+    assert!(result.is_compatible);
+    assert!(result.changes.is_empty());
+
+    // Compatible changes
+    let mut new_abi = old_abi.clone();
+    new_abi["functions"][0]["inputs"][1]["name"] = json!("amount");
+    new_abi["functions"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({"name": "mint", "inputs": [], "outputs": []}));
+    let result = check(&new_abi);
+    assert!(result.is_compatible);
+    assert_eq!(
+        change(&result, "functions[0].inputs[1]"),
+        Some(AbiChangeKind::Compatible)
+    );
+    assert_eq!(change(&result, "functions[3]"), Some(AbiChangeKind::Compatible));
+
+    // Added field breaks decoding of the account data with the old ABI
+    let mut new_abi = old_abi.clone();
+    new_abi["fields"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({"name": "supply", "type": "uint128"}));
+    let result = check(&new_abi);
+    assert!(!result.is_compatible);
+    assert_eq!(change(&result, "fields[4]"), Some(AbiChangeKind::Breaking));
+
+    // Breaking changes
+    let mut new_abi = old_abi.clone();
+    new_abi["header"] = json!(["time", "expire", "pubkey"]);
+    new_abi["functions"][0]["inputs"][1]["type"] = json!("uint64");
+    new_abi["functions"].as_array_mut().unwrap().remove(2);
+    new_abi["events"].as_array_mut().unwrap().remove(1);
+    new_abi["fields"] = json!([
+        {"name": "_pubkey", "type": "uint256"},
+        {"name": "_timestamp", "type": "uint64"},
+        {"name": "owner", "type": "address"},
+        {"name": "balance", "type": "uint128"}
+    ]);
+    let result = check(&new_abi);
+    assert!(!result.is_compatible);
+    for path in &[
+        "header",
+        "functions[0]",
+        "functions[0].inputs",
+        "functions[2]",
+        "events[1]",
+        "fields[2]",
+        "fields[3]",
+    ] {
+        assert_eq!(change(&result, path), Some(AbiChangeKind::Breaking), "{}", path);
+    }
+}
+
+#[test]
+fn test_decode_message_body_heuristic() {
+    use crate::abi::{
+        BodyFieldKind, KnownInterface, ParamsOfDecodeMessageBodyHeuristic,
+        ResultOfDecodeMessageBodyHeuristic,
+    };
+    use std::str::FromStr;
+    use ton_block::MsgAddressInt;
+
+    let client = TestClient::new();
+    let decode = |body: String| {
+        client
+            .request::<_, ResultOfDecodeMessageBodyHeuristic>(
+                "abi.decode_message_body_heuristic",
+                ParamsOfDecodeMessageBodyHeuristic {
+                    body,
+                    is_internal: true,
+                },
+            )
+            .unwrap()
+    };
+    let address = "0:1111111111111111111111111111111111111111111111111111111111111111";
+
+    // Comment
+    let mut builder = BuilderData::new();
+    builder.append_u32(0).unwrap();
+    builder.append_raw(b"Hello", 40).unwrap();
+    let body = serialize_cell_to_base64(&builder.into_cell().unwrap(), "body").unwrap();
+    let result = decode(body);
+    assert_eq!(result.function_id, Some(0));
+    assert_eq!(result.interface, Some(KnownInterface::Comment));
+    assert_eq!(result.comment.as_deref(), Some("Hello"));
+
+    // Standard interface: multisig `submitTransaction` body built by hand
+    // with the function id used by the deployed multisig wallets
+    let mut builder = BuilderData::new();
+    builder.append_u32(0x4cee646c).unwrap();
+    MsgAddressInt::from_str(address)
+        .unwrap()
+        .write_to(&mut builder)
+        .unwrap();
+    builder.append_raw(&1_000_000_000u128.to_be_bytes(), 128).unwrap();
+    builder.append_bit_zero().unwrap();
+    builder.append_bit_zero().unwrap();
+    builder
+        .checked_append_reference(BuilderData::new().into_cell().unwrap())
+        .unwrap();
+    let body = serialize_cell_to_base64(&builder.into_cell().unwrap(), "body").unwrap();
+    let result = decode(body);
+    assert_eq!(result.function_id, Some(0x4cee646c));
+    assert_eq!(result.interface, Some(KnownInterface::Multisig));
+    let decoded = result.decoded.unwrap();
+    assert_eq!(decoded.name, "submitTransaction");
+    let value = decoded.value.unwrap();
+    assert_eq!(value["dest"], address);
+    assert_eq!(value["value"], "1000000000");
+    assert_eq!(value["bounce"], false);
+
+    // Unknown body
+    let mut builder = BuilderData::new();
+    builder.append_u32(0x12345678).unwrap();
+    MsgAddressInt::from_str(address)
+        .unwrap()
+        .write_to(&mut builder)
+        .unwrap();
+    builder.append_u64(42).unwrap();
+    let mut child = BuilderData::new();
+    child.append_u32(7).unwrap();
+    builder
+        .checked_append_reference(child.into_cell().unwrap())
+        .unwrap();
+    let body = serialize_cell_to_base64(&builder.into_cell().unwrap(), "body").unwrap();
+    let result = decode(body);
+    assert_eq!(result.function_id, Some(0x12345678));
+    assert_eq!(result.interface, None);
+    assert_eq!(result.decoded, None);
+    assert_eq!(result.cells.len(), 2);
+    assert_eq!(result.cells[0].refs, vec![1]);
+    let fields = &result.cells[0].fields;
+    assert_eq!(fields[0].kind, BodyFieldKind::FunctionId);
+    assert_eq!(fields[0].value, "305419896");
+    assert_eq!(fields[1].kind, BodyFieldKind::Address);
+    assert_eq!(fields[1].offset, 32);
+    assert_eq!(fields[1].value, address);
+    assert_eq!(fields[2].kind, BodyFieldKind::Uint);
+    assert_eq!(fields[2].bit_length, 64);
+    assert_eq!(fields[2].value, "42");
+    assert_eq!(result.cells[1].bit_length, 32);
+    assert_eq!(result.cells[1].data, "00000007");
+}
+
+#[test]
+fn test_comment_body() {
+    use crate::abi::{
+        CommentEncryption, ParamsOfDecodeCommentBody, ParamsOfEncodeCommentBody,
+        ResultOfDecodeCommentBody, ResultOfEncodeCommentBody,
+    };
+
+    let client = TestClient::new();
+    let encode = |comment: &str, encryption: Option<CommentEncryption>| {
+        client
+            .request::<_, ResultOfEncodeCommentBody>(
+                "abi.encode_comment_body",
+                ParamsOfEncodeCommentBody {
+                    comment: comment.to_string(),
+                    encryption,
+                },
+            )
+            .unwrap()
+            .body
+    };
+    let decode = |body: String, secret: Option<String>| {
+        client.request::<_, ResultOfDecodeCommentBody>(
+            "abi.decode_comment_body",
+            ParamsOfDecodeCommentBody { body, secret },
+        )
+    };
+
+    let result = decode(encode("Hello", None), None).unwrap();
+    assert_eq!(result.comment, "Hello");
+    assert_eq!(result.sender_public, None);
+
+    // Long comment is split into the cell chain
+    let long = "Привет, ".repeat(100);
+    let body = encode(&long, None);
+    let cell = deserialize_cell_from_base64(&body, "body").unwrap().1;
+    assert_eq!(cell.references_count(), 1);
+    assert_eq!(decode(body, None).unwrap().comment, long);
+
+    // Encrypted comment
+    let sender: KeyPair = client.request_no_params("crypto.nacl_box_keypair").unwrap();
+    let recipient: KeyPair = client.request_no_params("crypto.nacl_box_keypair").unwrap();
+    let body = encode(
+        &long,
+        Some(CommentEncryption {
+            their_public: recipient.public.clone(),
+            secret: sender.secret.clone(),
+        }),
+    );
+    let error = decode(body.clone(), None).unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidComment as u32);
+    let result = decode(body, Some(recipient.secret.clone())).unwrap();
+    assert_eq!(result.comment, long);
+    assert_eq!(result.sender_public, Some(sender.public.clone()));
+
+    let error = decode(
+        serialize_cell_to_base64(&BuilderData::new().into_cell().unwrap(), "body").unwrap(),
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidComment as u32);
+
+    // Transfer message with the comment
+    let message: ResultOfEncodeInternalMessage = client
+        .request(
+            "abi.encode_internal_message",
+            ParamsOfEncodeInternalMessage {
+                address: Some(
+                    "0:1111111111111111111111111111111111111111111111111111111111111111"
+                        .to_string(),
+                ),
+                value: "1000000000".to_string(),
+                comment: Some(ParamsOfEncodeCommentBody {
+                    comment: "Hello".to_string(),
+                    encryption: None,
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let parsed: ResultOfParse = client
+        .request(
+            "boc.parse_message",
+            ParamsOfParse {
+                boc: message.message,
+            },
+        )
+        .unwrap();
+    let body = parsed.parsed["body"].as_str().unwrap().to_string();
+    assert_eq!(decode(body, None).unwrap().comment, "Hello");
+}
+
+const OVERLOADS_ABI: &str = r#"{
+    "ABI version": 2,
+    "version": "2.2",
+    "header": ["time"],
+    "functions": [
+        {
+            "name": "transfer",
+            "inputs": [
+                {"name": "to", "type": "address"},
+                {"name": "amount", "type": "uint128"}
+            ],
+            "outputs": [
+                {"name": "ok", "type": "bool"}
+            ]
+        },
+        {
+            "name": "transfer",
+            "inputs": [
+                {"name": "to", "type": "address"},
+                {"name": "amount", "type": "uint128"},
+                {"name": "payload", "type": "cell"}
+            ],
+            "outputs": []
+        },
+        {
+            "name": "burn",
+            "id": "0x0000000A",
+            "inputs": [
+                {"name": "amount", "type": "uint128"}
+            ],
+            "outputs": []
+        }
+    ],
+    "events": [],
+    "data": [],
+    "fields": []
+}"#;
+
+#[test]
+fn test_function_selectors() {
+    let client = TestClient::new();
+    let abi = Abi::Json(OVERLOADS_ABI.to_string());
+    let address = "0:1111111111111111111111111111111111111111111111111111111111111111";
+
+    let function_id = |function_name: &str| {
+        client
+            .request::<_, ResultOfCalcFunctionId>(
+                "abi.calc_function_id",
+                ParamsOfCalcFunctionId {
+                    abi: abi.clone(),
+                    function_name: function_name.to_owned(),
+                    ..Default::default()
+                },
+            )
+            .map(|result| result.function_id)
+    };
+    let transfer_id = function_id("transfer(address,uint128)").unwrap();
+    let transfer_with_payload_id = function_id("transfer(address, uint128, cell)").unwrap();
+    assert_ne!(transfer_id, transfer_with_payload_id);
+    assert_eq!(function_id("burn").unwrap(), 0x0A);
+    assert_eq!(function_id("burn(uint128)").unwrap(), 0x0A);
+    assert_eq!(
+        function_id(&format!("0x{:08x}", transfer_id)).unwrap(),
+        transfer_id
+    );
+    let error = function_id("transfer").unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidFunctionId as u32);
+    let error = function_id("transfer(address)").unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidFunctionName as u32);
+    let error = function_id("mint").unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidFunctionName as u32);
+
+    let encode = |function_name: &str, input: Value| {
+        client
+            .request::<_, ResultOfEncodeMessageBody>(
+                "abi.encode_message_body",
+                ParamsOfEncodeMessageBody {
+                    abi: abi.clone(),
+                    call_set: CallSet::some_with_function_and_input(function_name, input)
+                        .unwrap(),
+                    is_internal: true,
+                    signer: Signer::None,
+                    ..Default::default()
+                },
+            )
+            .map(|result| result.body)
+    };
+    let decode = |body: String, function_name: Option<&str>| {
+        client
+            .request::<_, DecodedMessageBody>(
+                "abi.decode_message_body",
+                ParamsOfDecodeMessageBody {
+                    abi: abi.clone(),
+                    body,
+                    is_internal: true,
+                    function_name: function_name.map(str::to_owned),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+    };
+
+    // The first overload is not visible to the ABI parser, it is found among the overloads
+    let body = encode(
+        "transfer(address,uint128)",
+        json!({"to": address, "amount": 5}),
+    )
+    .unwrap();
+    let decoded = decode(body.clone(), None);
+    assert_eq!(decoded.name, "transfer");
+    assert_eq!(decoded.value, Some(json!({"to": address, "amount": "5"})));
+    let decoded = decode(body, Some(&format!("0x{:08x}", transfer_id)));
+    assert_eq!(decoded.value, Some(json!({"to": address, "amount": "5"})));
+
+    let body = encode(
+        &format!("0x{:08x}", transfer_with_payload_id),
+        json!({"to": address, "amount": 7, "payload": ""}),
+    )
+    .unwrap();
+    let decoded = decode(body, Some("transfer(address,uint128,cell)"));
+    assert_eq!(decoded.name, "transfer");
+    assert_eq!(decoded.value.unwrap()["amount"], "7");
+
+    let error = encode("transfer", json!({"to": address, "amount": 5})).unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidFunctionId as u32);
+}
+
+#[allow(dead_code)]
+mod hello_bindings {
+    crate::abi::bindings::abi_bindings!(Hello, "src/tests/contracts/abi_v2/Hello.abi.json");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_abi_bindings() {
+    use crate::tvm::{AccountForExecutor, ParamsOfRunExecutor, ResultOfRunExecutor};
+    use hello_bindings::{Hello, SayHelloInput, SayHelloOutput, TouchInput};
+
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(HELLO, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let deploy_message: ResultOfEncodeMessage = client
+        .request_async(
+            "abi.encode_message",
+            ParamsOfEncodeMessage {
+                abi: abi.clone(),
+                deploy_set: DeploySet::some_with_tvc(tvc),
+                call_set: CallSet::some_with_function("constructor"),
+                signer: Signer::Keys { keys: keys.clone() },
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let deployed: ResultOfRunExecutor = client
+        .request_async(
+            "tvm.run_executor",
+            ParamsOfRunExecutor {
+                message: deploy_message.message,
+                account: AccountForExecutor::Uninit,
+                return_updated_account: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let hello = Hello::new(client.context(), deploy_message.address.clone()).unwrap();
+
+    // Message encoded with the typed input is the same as the one encoded with JSON
+    let encoded = hello
+        .encode_touch(&TouchInput {}, Signer::Keys { keys: keys.clone() })
+        .await
+        .unwrap();
+    assert_eq!(encoded.address, deploy_message.address);
+    let decoded: DecodedMessageBody = client
+        .request_async(
+            "abi.decode_message",
+            ParamsOfDecodeMessage {
+                abi: abi.clone(),
+                message: encoded.message,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(decoded.body_type, MessageBodyType::Input);
+    assert_eq!(decoded.name, "touch");
+
+    let output: SayHelloOutput = hello
+        .run_say_hello(&deployed.account, &SayHelloInput {})
+        .await
+        .unwrap();
+    let expected: crate::tvm::ResultOfRunTvm = client
+        .request_async(
+            "tvm.run_tvm",
+            crate::tvm::ParamsOfRunTvm {
+                message: client
+                    .encode_message(ParamsOfEncodeMessage {
+                        abi: abi.clone(),
+                        address: Some(deploy_message.address),
+                        call_set: CallSet::some_with_function("sayHello"),
+                        signer: Signer::None,
+                        ..Default::default()
+                    })
+                    .await
+                    .unwrap()
+                    .message,
+                account: deployed.account,
+                abi: Some(abi),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        output.value0.to_string(),
+        expected.decoded.unwrap().output.unwrap()["value0"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    );
+}
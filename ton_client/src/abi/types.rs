@@ -1,3 +1,4 @@
+use crate::abi::registry::get_registered_abi;
 use crate::abi::Error;
 use crate::error::{ClientError, ClientResult};
 use crate::ClientContext;
@@ -5,8 +6,8 @@ use std::convert::TryInto;
 use std::sync::Arc;
 use ton_abi::{Token, TokenValue};
 
-#[derive(Serialize, Deserialize, Clone, Debug, ApiType, Default)]
-pub struct AbiHandle(u32);
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, ApiType, Default, PartialEq)]
+pub struct AbiHandle(pub u32);
 
 #[derive(Serialize, Deserialize, Clone, Debug, ApiType)]
 #[serde(tag = "type", content = "value")]
@@ -31,9 +32,7 @@ impl Abi {
                 Ok(serde_json::to_string(abi).map_err(|err| Error::invalid_abi(err))?)
             }
             Self::Json(abi) => Ok(abi.clone()),
-            _ => Err(crate::client::Error::not_implemented(
-                "ABI handles are not supported yet",
-            )),
+            Self::Handle(handle) => Err(Error::abi_handle_requires_context(handle.0)),
         }
     }
 
@@ -41,6 +40,23 @@ impl Abi {
         ton_abi::Contract::load(self.json_string()?.as_bytes())
             .map_err(|x| Error::invalid_json(x))
     }
+
+    /// Returns ABI JSON. ABI handle is resolved with the context ABI registry.
+    pub fn resolve_json_string(&self, context: &ClientContext) -> ClientResult<String> {
+        match self {
+            Self::Handle(handle) => Ok(get_registered_abi(context, handle)?.json.clone()),
+            _ => self.json_string(),
+        }
+    }
+
+    /// Returns parsed ABI. ABI handle is resolved with the context ABI registry
+    /// and the ABI parsed at the registration is shared without copying.
+    pub fn resolve_abi(&self, context: &ClientContext) -> ClientResult<Arc<ton_abi::Contract>> {
+        match self {
+            Self::Handle(handle) => Ok(get_registered_abi(context, handle)?.contract.clone()),
+            _ => Ok(Arc::new(self.abi()?)),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, ApiType, Default)]
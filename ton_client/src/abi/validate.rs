@@ -0,0 +1,555 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::abi::function_id::{function_signature, without_overloads};
+use crate::abi::Abi;
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+
+const SUPPORTED_VERSIONS: &[(u8, u8)] = &[(1, 0), (2, 0), (2, 1), (2, 2), (2, 3), (2, 4)];
+const HEADER_FIELDS: &[&str] = &["time", "expire", "pubkey"];
+
+#[derive(Serialize, Deserialize, ApiType, Debug, PartialEq, Clone)]
+pub enum AbiDiagnosticSeverity {
+    /// ABI can't be used: it is rejected by the ABI parser or produces wrong messages.
+    Error,
+    /// ABI can be used, but it contains deprecated or suspicious constructions.
+    Warning,
+}
+
+impl Default for AbiDiagnosticSeverity {
+    fn default() -> Self {
+        AbiDiagnosticSeverity::Error
+    }
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct AbiDiagnostic {
+    /// Location of the problem within the ABI JSON,
+    /// e.g. `functions[1].inputs[0].type`. Empty for the whole ABI.
+    pub path: String,
+
+    /// Diagnostic severity.
+    pub severity: AbiDiagnosticSeverity,
+
+    /// Problem description.
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfValidateAbi {
+    /// Contract ABI to validate.
+    pub abi: Abi,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfValidateAbi {
+    /// `true` if the ABI has no errors. Warnings do not affect the flag.
+    pub is_valid: bool,
+
+    /// Found problems in the order of their location in the ABI.
+    pub diagnostics: Vec<AbiDiagnostic>,
+}
+
+struct Validator {
+    version: (u8, u8),
+    diagnostics: Vec<AbiDiagnostic>,
+}
+
+impl Validator {
+    fn error(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.diagnostics.push(AbiDiagnostic {
+            path: path.into(),
+            severity: AbiDiagnosticSeverity::Error,
+            message: message.into(),
+        });
+    }
+
+    fn warning(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.diagnostics.push(AbiDiagnostic {
+            path: path.into(),
+            severity: AbiDiagnosticSeverity::Warning,
+            message: message.into(),
+        });
+    }
+
+    fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|x| x.severity == AbiDiagnosticSeverity::Error)
+    }
+
+    fn array<'a>(&mut self, abi: &'a Value, path: &str, field: &str) -> &'a [Value] {
+        match abi.get(field) {
+            None | Some(Value::Null) => &[],
+            Some(Value::Array(items)) => items,
+            Some(_) => {
+                self.error(join(path, field), format!("`{}` must be an array", field));
+                &[]
+            }
+        }
+    }
+
+    fn name<'a>(&mut self, item: &'a Value, path: &str) -> Option<&'a str> {
+        match item["name"].as_str() {
+            Some(name) if !name.is_empty() => Some(name),
+            Some(_) => {
+                self.error(join(path, "name"), "name is empty");
+                None
+            }
+            None => {
+                self.error(join(path, "name"), "name is missing or is not a string");
+                None
+            }
+        }
+    }
+
+    fn check_version(&mut self, abi: &Value) {
+        let abi_version = match abi.get("ABI version") {
+            None => None,
+            Some(value) => match value.as_u64() {
+                Some(version @ 1..=2) => Some(version as u8),
+                _ => {
+                    self.error("ABI version", format!("unsupported ABI version {}", value));
+                    return;
+                }
+            },
+        };
+        let version = match abi.get("version") {
+            None | Some(Value::Null) => None,
+            Some(value) => match value.as_str().and_then(parse_version) {
+                Some(version) => Some(version),
+                None => {
+                    self.error("version", format!("invalid ABI version {}, expected `2.x`", value));
+                    return;
+                }
+            },
+        };
+        self.version = match (abi_version, version) {
+            (None, None) => {
+                self.warning("", "ABI version is not specified, `2.0` is assumed");
+                (2, 0)
+            }
+            (Some(2), None) => {
+                self.warning("version", "`version` is not specified, `2.0` is assumed");
+                (2, 0)
+            }
+            (Some(major), None) => (major, 0),
+            (Some(major), Some(version)) if major != version.0 => {
+                self.error(
+                    "version",
+                    format!(
+                        "`version` {}.{} doesn't match `ABI version` {}",
+                        version.0, version.1, major
+                    ),
+                );
+                version
+            }
+            (_, Some(version)) => version,
+        };
+        if !SUPPORTED_VERSIONS.contains(&self.version) {
+            self.error(
+                "version",
+                format!("unsupported ABI version {}.{}", self.version.0, self.version.1),
+            );
+        }
+    }
+
+    fn check_header(&mut self, abi: &Value) {
+        let header = self.array(abi, "", "header");
+        if !header.is_empty() && self.version.0 < 2 {
+            self.warning("header", "header is supported since ABI 2.0 and is ignored");
+        }
+        let mut names = HashSet::new();
+        for (i, item) in header.iter().enumerate() {
+            let path = format!("header[{}]", i);
+            match item.as_str() {
+                Some(name) if !HEADER_FIELDS.contains(&name) => self.error(
+                    path,
+                    format!(
+                        "unknown header field `{}`, expected one of {}",
+                        name,
+                        HEADER_FIELDS.join(", ")
+                    ),
+                ),
+                Some(name) if !names.insert(name) => {
+                    self.error(path, format!("duplicate header field `{}`", name))
+                }
+                Some(_) => {}
+                None => self.error(path, "header field must be a string"),
+            }
+        }
+    }
+
+    fn check_params(&mut self, params: &[Value], path: &str, fields: bool) {
+        let mut names = HashSet::new();
+        for (i, param) in params.iter().enumerate() {
+            let path = format!("{}[{}]", path, i);
+            if let Some(name) = self.name(param, &path) {
+                if !names.insert(name) {
+                    self.error(join(&path, "name"), format!("duplicate parameter `{}`", name));
+                }
+            }
+            self.check_param_type(param, &path);
+            match param.get("init") {
+                None | Some(Value::Bool(false)) => {}
+                Some(Value::Bool(true)) if !fields => {
+                    self.warning(
+                        join(&path, "init"),
+                        "`init` is applicable only to the contract fields and is ignored",
+                    );
+                }
+                Some(Value::Bool(true)) if self.version < (2, 4) => {
+                    self.error(join(&path, "init"), "`init` fields are supported since ABI 2.4");
+                }
+                Some(Value::Bool(true)) => {}
+                Some(_) => self.error(join(&path, "init"), "`init` must be a boolean"),
+            }
+        }
+    }
+
+    fn check_param_type(&mut self, param: &Value, path: &str) {
+        let type_path = join(path, "type");
+        let kind = match param["type"].as_str() {
+            Some(kind) => kind,
+            None => {
+                self.error(type_path, "type is missing or is not a string");
+                return;
+            }
+        };
+        let components = match &param["components"] {
+            Value::Null => &[][..],
+            Value::Array(components) => components.as_slice(),
+            _ => {
+                self.error(join(path, "components"), "`components` must be an array");
+                &[][..]
+            }
+        };
+        let mut has_tuple = false;
+        if let Err(err) = self.check_type(kind, &mut has_tuple) {
+            self.error(type_path, err);
+        }
+        if has_tuple {
+            if components.is_empty() {
+                self.warning(join(path, "components"), "tuple has no components");
+            }
+            self.check_params(components, &join(path, "components"), false);
+        } else if !components.is_empty() {
+            self.warning(
+                join(path, "components"),
+                format!("`components` are ignored for the `{}` type", kind),
+            );
+        }
+    }
+
+    fn check_type(&self, kind: &str, has_tuple: &mut bool) -> Result<(), String> {
+        if let Some(inner) = kind.strip_suffix(']') {
+            let pos = inner
+                .rfind('[')
+                .ok_or_else(|| format!("invalid array type `{}`", kind))?;
+            let size = &inner[pos + 1..];
+            if !size.is_empty() && size.parse::<u64>().is_err() {
+                return Err(format!("invalid array size in `{}`", kind));
+            }
+            return self.check_type(&inner[..pos], has_tuple);
+        }
+        if let Some(inner) = unwrap_type(kind, "optional") {
+            self.require_version(kind, (2, 1))?;
+            return self.check_type(inner, has_tuple);
+        }
+        if let Some(inner) = unwrap_type(kind, "ref") {
+            self.require_version(kind, (2, 4))?;
+            return self.check_type(inner, has_tuple);
+        }
+        if let Some(args) = unwrap_type(kind, "map") {
+            let (key, value) = args
+                .split_once(',')
+                .ok_or_else(|| format!("invalid map type `{}`", kind))?;
+            let key = key.trim();
+            if key != "address" && int_bits(key, "uint").or_else(|| int_bits(key, "int")).is_none() {
+                return Err(format!(
+                    "invalid map key type `{}`, expected integer or address",
+                    key
+                ));
+            }
+            return self.check_type(value.trim(), has_tuple);
+        }
+        if let Some(bits) = int_bits(kind, "uint").or_else(|| int_bits(kind, "int")) {
+            return check_range(kind, bits, 1, 256);
+        }
+        if let Some(size) = int_bits(kind, "varuint").or_else(|| int_bits(kind, "varint")) {
+            return if size == 16 || size == 32 {
+                Ok(())
+            } else {
+                Err(format!("invalid type `{}`, expected size 16 or 32", kind))
+            };
+        }
+        if let Some(size) = int_bits(kind, "fixedbytes") {
+            return check_range(kind, size, 1, 32);
+        }
+        match kind {
+            "tuple" => {
+                *has_tuple = true;
+                Ok(())
+            }
+            "bool" | "address" | "cell" | "bytes" => Ok(()),
+            "string" => self.require_version(kind, (2, 1)),
+            "address_std" => self.require_version(kind, (2, 4)),
+            "gram" | "token" => Ok(()),
+            _ => Err(format!("unknown type `{}`", kind)),
+        }
+    }
+
+    fn require_version(&self, kind: &str, version: (u8, u8)) -> Result<(), String> {
+        if self.version < version {
+            Err(format!(
+                "type `{}` is supported since ABI {}.{}",
+                kind, version.0, version.1
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_functions(&mut self, abi: &Value, section: &str) {
+        let mut names = HashSet::new();
+        let mut signatures = HashSet::new();
+        for (i, item) in self.array(abi, "", section).iter().enumerate() {
+            let path = format!("{}[{}]", section, i);
+            if let Some(name) = self.name(item, &path) {
+                // Functions can be overloaded, the overloads are selected by the signature
+                match function_signature(item).filter(|_| section == "functions") {
+                    Some(signature) if !signatures.insert(signature.clone()) => self.error(
+                        join(&path, "name"),
+                        format!("duplicate function `{}`", signature),
+                    ),
+                    Some(signature) if !names.insert(name) => self.warning(
+                        join(&path, "name"),
+                        format!(
+                            "function `{}` is overloaded, it can be called only by the signature `{}`",
+                            name, signature
+                        ),
+                    ),
+                    None if !names.insert(name) => {
+                        self.error(join(&path, "name"), format!("duplicate name `{}`", name))
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(id) = item.get("id").filter(|id| !id.is_null()) {
+                if id.as_str().and_then(parse_id).is_none() {
+                    self.error(
+                        join(&path, "id"),
+                        format!("invalid id {}, expected 0x-prefixed 32-bit hex", id),
+                    );
+                }
+            }
+            let inputs = self.array(item, &path, "inputs");
+            self.check_params(inputs, &join(&path, "inputs"), false);
+            if section == "functions" {
+                let outputs = self.array(item, &path, "outputs");
+                self.check_params(outputs, &join(&path, "outputs"), false);
+            }
+        }
+    }
+
+    fn check_data(&mut self, abi: &Value) {
+        let data = self.array(abi, "", "data");
+        if !data.is_empty() && self.version >= (2, 4) {
+            self.warning("data", "`data` is deprecated since ABI 2.4, use `init` fields instead");
+        }
+        let fields = self.array(abi, "", "fields");
+        if !fields.is_empty() && self.version < (2, 1) {
+            self.warning("fields", "`fields` are supported since ABI 2.1 and are ignored");
+        }
+        self.check_params(fields, "fields", true);
+        let field_names = fields
+            .iter()
+            .filter_map(|field| field["name"].as_str())
+            .collect::<HashSet<_>>();
+
+        let mut keys = HashSet::new();
+        let mut names = HashSet::new();
+        for (i, item) in data.iter().enumerate() {
+            let path = format!("data[{}]", i);
+            match item["key"].as_u64() {
+                Some(key) if !keys.insert(key) => {
+                    self.error(join(&path, "key"), format!("duplicate data key {}", key))
+                }
+                Some(_) => {}
+                None => self.error(join(&path, "key"), "key is missing or is not an integer"),
+            }
+            if let Some(name) = self.name(item, &path) {
+                if !names.insert(name) {
+                    self.error(join(&path, "name"), format!("duplicate data `{}`", name));
+                } else if !field_names.is_empty() && !field_names.contains(name) {
+                    self.warning(
+                        join(&path, "name"),
+                        format!("data `{}` has no corresponding field in `fields`", name),
+                    );
+                }
+            }
+            self.check_param_type(item, &path);
+        }
+    }
+
+    /// Checks function and event ids calculated by the ABI parser
+    /// in the same way as `calc_function_id` does.
+    fn check_ids(&mut self, json: &str, abi: &Value) {
+        let contract = match ton_abi::Contract::load(json.as_bytes()) {
+            Ok(contract) => contract,
+            Err(err) => {
+                self.error("", format!("ABI is rejected by the parser: {}", err));
+                return;
+            }
+        };
+        let items = self.array(abi, "", "functions");
+        let functions = items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| {
+                let name = item["name"].as_str()?;
+                let ids = |contract: &ton_abi::Contract| {
+                    let function = contract.function(name).ok()?;
+                    Some((function.get_input_id(), function.get_output_id()))
+                };
+                let (input_id, output_id) =
+                    if items.iter().filter(|other| other["name"] == name).count() > 1 {
+                        // The parser keeps only the last overload, so the overload ids
+                        // are calculated with the other overloads removed
+                        let abi = without_overloads(abi, i).to_string();
+                        ids(&ton_abi::Contract::load(abi.as_bytes()).ok()?)?
+                    } else {
+                        ids(&contract)?
+                    };
+                Some((format!("functions[{}]", i), input_id, output_id))
+            })
+            .collect::<Vec<_>>();
+        let mut input_ids = HashMap::new();
+        let mut output_ids = HashMap::new();
+        for (path, input_id, output_id) in &functions {
+            if let Some(other) = input_ids.insert(*input_id, path) {
+                self.error(
+                    path.clone(),
+                    format!("function id 0x{:08x} is already used by {}", input_id, other),
+                );
+            }
+            output_ids.insert(*output_id, path);
+        }
+
+        let mut event_ids = HashMap::new();
+        for (i, item) in self.array(abi, "", "events").iter().enumerate() {
+            let event = match item["name"].as_str().and_then(|name| contract.events().get(name)) {
+                Some(event) => event,
+                None => continue,
+            };
+            let path = format!("events[{}]", i);
+            let id = event.get_function_id();
+            if let Some(other) = event_ids.insert(id, path.clone()) {
+                self.error(
+                    path,
+                    format!("event id 0x{:08x} is already used by {}", id, other),
+                );
+            } else if let Some(function) = output_ids.get(&id) {
+                self.warning(
+                    path,
+                    format!(
+                        "event id 0x{:08x} is the same as the output id of {}, \
+                            messages can't be decoded unambiguously",
+                        id, function
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn join(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u8, u8)> {
+    let (major, minor) = version.split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+fn parse_id(id: &str) -> Option<u32> {
+    u32::from_str_radix(id.strip_prefix("0x")?, 16).ok()
+}
+
+fn unwrap_type<'a>(kind: &'a str, wrapper: &str) -> Option<&'a str> {
+    kind.strip_prefix(wrapper)?.strip_prefix('(')?.strip_suffix(')')
+}
+
+fn int_bits(kind: &str, prefix: &str) -> Option<u64> {
+    kind.strip_prefix(prefix)?.parse().ok()
+}
+
+fn check_range(kind: &str, size: u64, min: u64, max: u64) -> Result<(), String> {
+    if size < min || size > max {
+        Err(format!(
+            "invalid type `{}`, expected size from {} to {}",
+            kind, min, max
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Validates contract ABI.
+///
+/// Unlike the functions accepting ABI, which fail on the first problem with an error of the ABI
+/// parser, this function checks the whole ABI and reports all found problems with their location
+/// within the ABI JSON: version and header consistency, unknown parameter types, duplicate names,
+/// function and event ids, `init` fields and data keys.
+///
+/// Function ids are checked only if there are no other errors, because they are calculated
+/// by the ABI parser.
+#[api_function]
+pub fn validate(
+    context: Arc<ClientContext>,
+    params: ParamsOfValidateAbi,
+) -> ClientResult<ResultOfValidateAbi> {
+    let json = params.abi.resolve_json_string(&context)?;
+    let mut validator = Validator {
+        version: (2, 0),
+        diagnostics: Vec::new(),
+    };
+    match serde_json::from_str::<Value>(&json) {
+        Ok(abi) if abi.is_object() => {
+            validator.check_version(&abi);
+            validator.check_header(&abi);
+            validator.check_functions(&abi, "functions");
+            validator.check_functions(&abi, "events");
+            validator.check_data(&abi);
+            if !validator.has_errors() {
+                validator.check_ids(&json, &abi);
+            }
+        }
+        Ok(_) => validator.error("", "ABI must be a JSON object"),
+        Err(err) => validator.error("", format!("invalid JSON: {}", err)),
+    }
+    Ok(ResultOfValidateAbi {
+        is_valid: !validator.has_errors(),
+        diagnostics: validator.diagnostics,
+    })
+}
@@ -24,6 +24,8 @@ use super::std_client_env::ClientEnv;
 #[cfg(feature = "wasm-base")]
 use super::wasm_client_env::ClientEnv;
 use super::{AppRequestResult, Error, ParamsOfAppRequest};
+use crate::abi::code_registry::CodeAbiRegistry;
+use crate::abi::registry::RegisteredAbi;
 use crate::abi::AbiConfig;
 use crate::boc::{cache::Bocs, BocConfig};
 use crate::client::storage::KeyValueStorage;
@@ -36,7 +38,9 @@ use crate::error::ClientResult;
 use crate::json_interface::interop::ResponseType;
 use crate::json_interface::request::Request;
 use crate::net::{NetworkConfig, NetworkContext, ServerLink};
-use crate::processing::SdkServices;
+use crate::processing::{
+    MonitorStorage, Outbox, ProcessingStats, ResendingMessages, SdkServices, Sequencers,
+};
 use crate::proofs::ProofsConfig;
 
 #[derive(Default)]
@@ -67,6 +71,10 @@ pub struct ClientContext {
     pub(crate) boxes: Boxes,
     pub(crate) derived_keys: DerivedKeys,
 
+    // abi module
+    pub(crate) abis: LockfreeMap<u32, RegisteredAbi>,
+    pub(crate) code_abis: CodeAbiRegistry,
+
     // boc module
     pub(crate) bocs: Arc<Bocs>,
 
@@ -75,6 +83,12 @@ pub struct ClientContext {
 
     // processing module
     pub(crate) message_monitor: Arc<MessageMonitor<SdkServices>>,
+    pub(crate) outbox: Outbox,
+    pub(crate) sequencers: Sequencers,
+    pub(crate) resending_messages: ResendingMessages,
+    pub(crate) processing_stats: Arc<ProcessingStats>,
+    /// Stops serving of the processing metrics when the context is dropped.
+    _processing_metrics_shutdown: Option<oneshot::Sender<()>>,
 
     // proofs module
     pub(crate) proofs_storage: RwLock<Option<Arc<dyn KeyValueStorage>>>,
@@ -108,7 +122,11 @@ impl ClientContext {
 
         let server_link =
             if config.network.server_address.is_some() || config.network.endpoints.is_some() {
-                Some(ServerLink::new(config.network.clone(), env.clone())?)
+                Some(ServerLink::new(
+                    config.network.clone(),
+                    env.clone(),
+                    config.local_storage_path.clone(),
+                )?)
             } else {
                 None
             };
@@ -121,16 +139,43 @@ impl ClientContext {
             iterators: Default::default(),
             network_uid: Default::default(),
         });
-        let message_monitor = Arc::new(MessageMonitor::new(SdkServices::new(
-            net.clone(),
-            bocs.clone(),
-        )));
+        let sdk_services = SdkServices::new(net.clone(), bocs.clone());
+        let message_monitor = Arc::new(if config.network.persistent_message_monitor {
+            MessageMonitor::with_storage(
+                sdk_services,
+                Arc::new(MonitorStorage::new(config.local_storage_path.clone())),
+            )
+        } else {
+            MessageMonitor::new(sdk_services)
+        });
+        let processing_stats = Arc::new(ProcessingStats::default());
+        let processing_metrics_shutdown = match &config.processing_metrics_address {
+            Some(address) => {
+                // The listener must not keep the stats alive after the context is dropped
+                let stats = Arc::downgrade(&processing_stats);
+                let (shutdown, shutdown_receiver) = oneshot::channel();
+                env.serve_text(
+                    address,
+                    move || stats.upgrade().map(|stats| stats.prometheus()),
+                    shutdown_receiver,
+                )?;
+                Some(shutdown)
+            }
+            None => None,
+        };
         Ok(Self {
             net,
             message_monitor,
+            outbox: Default::default(),
+            sequencers: Default::default(),
+            resending_messages: Default::default(),
+            processing_stats,
+            _processing_metrics_shutdown: processing_metrics_shutdown,
             config,
             env: env.clone(),
             debots: LockfreeMap::new(),
+            abis: LockfreeMap::new(),
+            code_abis: Default::default(),
             boxes: Default::default(),
             bocs,
             network_params: RwLock::new(None),
@@ -195,6 +240,16 @@ pub struct ClientConfig {
     /// Default (recommended) value is "~/.tonclient" for native environments and ".tonclient"
     /// for web-browser.
     pub local_storage_path: Option<String>,
+
+    /// Address to serve the processing metrics in the Prometheus text format,
+    /// e.g. `127.0.0.1:9100`.
+    ///
+    /// Any HTTP request to this address returns the metrics available with
+    /// `processing.get_stats`. The metrics are served without authentication, so bind
+    /// it to a loopback or otherwise protected interface. Is not supported in the browser.
+    ///
+    /// Default is `None` (metrics are not served).
+    pub processing_metrics_address: Option<String>,
 }
 
 fn deserialize_binding_config<'de, D: Deserializer<'de>>(
@@ -243,6 +298,7 @@ impl Default for ClientConfig {
             boc: Default::default(),
             proofs: Default::default(),
             local_storage_path: Default::default(),
+            processing_metrics_address: Default::default(),
         }
     }
 }
@@ -322,6 +322,24 @@ impl NetworkMockBuilder {
         }))
     }
 
+    pub fn status_with_headers(
+        &mut self,
+        status: u16,
+        body: &str,
+        headers: &[(&str, &str)],
+    ) -> &mut Self {
+        self.push_fetch(Ok(FetchResult {
+            url: self.url.clone(),
+            status,
+            body: body.to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            remote_address: None,
+        }))
+    }
+
     pub fn network_err(&mut self) -> &mut Self {
         self.push_fetch(Err(crate::client::Error::http_request_send_error(
             "Network error",
@@ -28,6 +28,7 @@ use std::convert::TryFrom;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::runtime::Runtime;
 #[cfg(test)]
 use tokio::sync::RwLock;
@@ -131,6 +132,52 @@ impl ClientEnv {
         self.async_runtime_handle.block_on(future)
     }
 
+    /// Serves the text returned by `get_text` over HTTP on the specified address.
+    /// Serving is stopped and the address is released when the `shutdown` sender
+    /// is dropped or `get_text` returns `None`.
+    pub fn serve_text(
+        &self,
+        address: &str,
+        get_text: impl Fn() -> Option<String> + Send + Sync + 'static,
+        mut shutdown: tokio::sync::oneshot::Receiver<()>,
+    ) -> ClientResult<()> {
+        let listener = std::net::TcpListener::bind(address)
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+            .map_err(|err| {
+                Error::internal_error(format!("Can not listen on {}: {}", address, err))
+            })?;
+        let _guard = self.async_runtime_handle.enter();
+        let listener = tokio::net::TcpListener::from_std(listener).map_err(|err| {
+            Error::internal_error(format!("Can not listen on {}: {}", address, err))
+        })?;
+        self.spawn(async move {
+            loop {
+                let mut stream = tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => stream,
+                        Err(_) => break,
+                    },
+                    _ = &mut shutdown => break,
+                };
+                let text = match get_text() {
+                    Some(text) => text,
+                    None => break,
+                };
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\n\
+                    Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    text.len(),
+                    text
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+        Ok(())
+    }
+
     /// Connects to the websocket endpoint
     pub async fn websocket_connect(
         &self,
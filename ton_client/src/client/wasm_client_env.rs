@@ -351,6 +351,16 @@ impl ClientEnv {
         wasm_bindgen_futures::spawn_local(future);
     }
 
+    /// HTTP serving is not available in the browser
+    pub fn serve_text(
+        &self,
+        _address: &str,
+        _get_text: impl Fn() -> Option<String> + Send + Sync + 'static,
+        _shutdown: tokio::sync::oneshot::Receiver<()>,
+    ) -> ClientResult<()> {
+        Err(Error::not_implemented("HTTP serving is not supported in wasm"))
+    }
+
     /// Connects to the websocket endpoint
     pub async fn websocket_connect(
         &self,
@@ -408,6 +408,7 @@ impl ContractCall {
                 message: fixed_msg.clone(),
                 abi: None,
                 send_events: true,
+                user_data: None,
             },
             callback.clone(),
         )
@@ -22,7 +22,7 @@ use crate::boc::internal::deserialize_cell_from_base64;
 use crate::crypto::{remove_signing_box, RegisteredSigningBox, SigningBoxHandle};
 use crate::encoding::{decode_abi_number, slice_from_cell};
 use crate::error::{ClientError, ClientResult};
-use crate::net::{query_collection, NetworkConfig, ParamsOfQueryCollection};
+use crate::net::{fetch_collection, NetworkConfig, ParamsOfQueryCollection};
 use crate::processing::{process_message, ParamsOfProcessMessage, ProcessingEvent};
 use crate::tvm::{run_tvm, ParamsOfRunTvm};
 use crate::{ClientConfig, ClientContext};
@@ -638,7 +638,7 @@ impl DEngine {
     }
 
     pub(crate) async fn load_state(ton: TonClient, addr: String) -> Result<String, String> {
-        let account_request = query_collection(
+        let account_request = fetch_collection(
             ton,
             ParamsOfQueryCollection {
                 collection: "accounts".to_owned(),
@@ -823,6 +823,8 @@ impl DEngine {
             ParamsOfProcessMessage {
                 message_encode_params: call_params,
                 send_events: true,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             callback,
         )
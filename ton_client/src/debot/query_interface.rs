@@ -4,7 +4,7 @@ use super::dinterface::{
 use super::TonClient;
 use crate::abi::Abi;
 use crate::debot::json_lib_utils::{pack, Value};
-use crate::net::{wait_for_collection, query_collection, query, OrderBy, ParamsOfQueryCollection, ParamsOfWaitForCollection, SortDirection, ParamsOfQuery};
+use crate::net::{wait_for_collection, fetch_collection, query, OrderBy, ParamsOfQueryCollection, ParamsOfWaitForCollection, SortDirection, ParamsOfQuery};
 use serde_json::Value as JsonValue;
 
 const ABI: &str = r#"
@@ -140,7 +140,7 @@ impl QueryInterface {
     ) -> Result<Vec<JsonValue>, QueryStatus> {
         let filter: Option<JsonValue> =
             Some(serde_json::from_str(&filter).map_err(|_| QueryStatus::FilterError)?);
-        let result = query_collection(
+        let result = fetch_collection(
             self.ton.clone(),
             ParamsOfQueryCollection {
                 collection,
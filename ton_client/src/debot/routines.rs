@@ -6,7 +6,7 @@ use crate::crypto::{
     ParamsOfSigningBoxSign, SigningBoxHandle,
 };
 use crate::encoding::{decode_abi_bigint, decode_abi_number};
-use crate::net::{query_collection, ParamsOfQueryCollection};
+use crate::net::{fetch_collection, ParamsOfQueryCollection};
 use super::TonClient;
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -285,7 +285,7 @@ pub(super) async fn get_account(
     args: &serde_json::Value,
 ) -> Result<serde_json::Value, String> {
     let addr = get_arg(&args, "addr")?.to_lowercase();
-    let mut accounts = query_collection(
+    let mut accounts = fetch_collection(
         ton.clone(),
         ParamsOfQueryCollection {
             collection: "accounts".to_owned(),
@@ -18,7 +18,7 @@ use crate::crypto::{
     ParamsOfNaclSignKeyPairFromSecret, ParamsOfSigningBoxSign, RegisteredSigningBox,
 };
 use crate::encoding::decode_abi_bigint;
-use crate::net::{query_collection, OrderBy, ParamsOfQueryCollection, SortDirection};
+use crate::net::{fetch_collection, OrderBy, ParamsOfQueryCollection, SortDirection};
 use serde_json::Value;
 
 const ABI: &str = r#"
@@ -708,7 +708,7 @@ impl SdkInterface {
         let code_hash = decode_abi_bigint(&code_hash)
             .map_err(|e| format!("failed to parse integer \"{}\": {}", code_hash, e))?;
 
-        let accounts = query_collection(
+        let accounts = fetch_collection(
             self.ton.clone(),
             ParamsOfQueryCollection {
                 collection: "accounts".to_owned(),
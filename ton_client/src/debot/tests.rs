@@ -444,6 +444,7 @@ async fn encode_internal_message(client: Arc<TestClient>, abi: &str, addr: Strin
             value: "1000000000000000".to_owned(),
             bounce: None,
             enable_ihr: None,
+            comment: None,
         }
     ).await.unwrap();
     r.message
@@ -27,6 +27,8 @@ fn register_client(handlers: &mut RuntimeHandlers) {
     module.register_type::<crate::error::ClientError>();
     module.register_type::<crate::client::ClientConfig>();
     module.register_type::<crate::net::NetworkConfig>();
+    module.register_type::<crate::net::QueryCacheConfig>();
+    module.register_type::<crate::net::RateLimitConfig>();
     module.register_type::<crate::client::BindingConfig>();
     module.register_type::<crate::net::NetworkQueriesProtocol>();
     module.register_type::<crate::crypto::CryptoConfig>();
@@ -320,6 +322,17 @@ fn register_abi(handlers: &mut RuntimeHandlers) {
     module.register_type::<crate::abi::AbiFunction>();
     module.register_type::<crate::abi::AbiContract>();
     module.register_type::<crate::abi::DataLayout>();
+    module.register_type::<crate::abi::AbiDiagnosticSeverity>();
+    module.register_type::<crate::abi::AbiDiagnostic>();
+    module.register_type::<crate::abi::AbiChangeKind>();
+    module.register_type::<crate::abi::AbiChange>();
+    module.register_type::<crate::abi::KnownInterface>();
+    module.register_type::<crate::abi::BodyFieldKind>();
+    module.register_type::<crate::abi::BodyField>();
+    module.register_type::<crate::abi::BodyCell>();
+    module.register_type::<crate::abi::CommentEncryption>();
+    module.register_type::<crate::abi::AccountDataMap>();
+    module.register_type::<crate::abi::DataMapKey>();
 
     module.register_async_fn(
         crate::abi::encode_message_body,
@@ -357,6 +370,18 @@ fn register_abi(handlers: &mut RuntimeHandlers) {
         crate::abi::decode_account_data,
         crate::abi::decode_data::decode_account_data_api,
     );
+    module.register_sync_fn(
+        crate::abi::decode_account_data_lazy,
+        crate::abi::decode_data::decode_account_data_lazy_api,
+    );
+    module.register_sync_fn(
+        crate::abi::decode_account_data_map,
+        crate::abi::decode_data::decode_account_data_map_api,
+    );
+    module.register_sync_fn(
+        crate::abi::get_data_map_value,
+        crate::abi::decode_data::get_data_map_value_api,
+    );
     module.register_sync_fn(
         crate::abi::update_initial_data,
         crate::abi::init_data::update_initial_data_api,
@@ -385,6 +410,46 @@ fn register_abi(handlers: &mut RuntimeHandlers) {
         crate::abi::get_signature_data,
         crate::abi::decode_message::get_signature_data_api,
     );
+    module.register_sync_fn(
+        crate::abi::register_abi,
+        crate::abi::registry::register_abi_api,
+    );
+    module.register_sync_fn(
+        crate::abi::remove_abi,
+        crate::abi::registry::remove_abi_api,
+    );
+    module.register_async_fn(
+        crate::abi::register_code_abi,
+        crate::abi::code_registry::register_code_abi_api,
+    );
+    module.register_async_fn(
+        crate::abi::remove_code_abi,
+        crate::abi::code_registry::remove_code_abi_api,
+    );
+    module.register_async_fn(
+        crate::abi::decode_message_auto,
+        crate::abi::code_registry::decode_message_auto_api,
+    );
+    module.register_sync_fn(
+        crate::abi::validate,
+        crate::abi::validate::validate_api,
+    );
+    module.register_sync_fn(
+        crate::abi::check_compatibility,
+        crate::abi::compatibility::check_compatibility_api,
+    );
+    module.register_sync_fn(
+        crate::abi::decode_message_body_heuristic,
+        crate::abi::decode_heuristic::decode_message_body_heuristic_api,
+    );
+    module.register_sync_fn(
+        crate::abi::encode_comment_body,
+        crate::abi::comment::encode_comment_body_api,
+    );
+    module.register_sync_fn(
+        crate::abi::decode_comment_body,
+        crate::abi::comment::decode_comment_body_api,
+    );
     module.register();
 }
 
@@ -482,6 +547,7 @@ fn register_net(handlers: &mut RuntimeHandlers) {
     module.register_type::<crate::net::AggregationFn>();
     module.register_type::<crate::net::TransactionNode>();
     module.register_type::<crate::net::MessageNode>();
+    module.register_type::<crate::net::DecodedEvent>();
 
     module.register_async_fn(crate::net::query, crate::net::queries::query_api);
     module.register_async_fn(crate::net::batch_query, crate::net::batch::batch_query_api);
@@ -506,6 +572,10 @@ fn register_net(handlers: &mut RuntimeHandlers) {
         super::net::subscribe_collection_api,
     );
     module.register_async_fn_with_callback(super::net::subscribe, super::net::subscribe_api);
+    module.register_async_fn_with_callback(
+        super::net::subscribe_events,
+        super::net::subscribe_events_api,
+    );
     module.register_async_fn_no_args(crate::net::suspend, crate::net::suspend_api);
     module.register_async_fn_no_args(crate::net::resume, crate::net::resume_api);
     module.register_async_fn(
@@ -540,6 +610,22 @@ fn register_net(handlers: &mut RuntimeHandlers) {
         crate::net::iterators::transaction_iterator::resume_transaction_iterator,
         crate::net::iterators::transaction_iterator::resume_transaction_iterator_api,
     );
+    module.register_async_fn(
+        crate::net::iterators::counterparties_iterator::create_counterparties_iterator,
+        crate::net::iterators::counterparties_iterator::create_counterparties_iterator_api,
+    );
+    module.register_async_fn(
+        crate::net::iterators::counterparties_iterator::resume_counterparties_iterator,
+        crate::net::iterators::counterparties_iterator::resume_counterparties_iterator_api,
+    );
+    module.register_async_fn(
+        crate::net::iterators::account_transactions_iterator::create_account_transactions_iterator,
+        crate::net::iterators::account_transactions_iterator::create_account_transactions_iterator_api,
+    );
+    module.register_async_fn(
+        crate::net::iterators::account_transactions_iterator::resume_account_transactions_iterator,
+        crate::net::iterators::account_transactions_iterator::resume_account_transactions_iterator_api,
+    );
     module.register_async_fn(
         crate::net::iterators::iterator_next,
         crate::net::iterators::iterator_next_api,
@@ -552,6 +638,10 @@ fn register_net(handlers: &mut RuntimeHandlers) {
         crate::net::get_signature_id,
         crate::net::network_params::get_signature_id_api,
     );
+    module.register_async_fn_no_args(
+        crate::net::get_rate_limit_stats,
+        crate::net::rate_limiter::get_rate_limit_stats_api,
+    );
     module.register();
 }
 
@@ -570,6 +660,14 @@ fn register_processing(handlers: &mut RuntimeHandlers) {
     module.register_type::<crate::processing::ProcessingEvent>();
     module.register_type::<crate::processing::ResultOfProcessMessage>();
     module.register_type::<crate::processing::DecodedOutput>();
+    module.register_type::<crate::processing::DryRunOptions>();
+    module.register_type::<crate::processing::DryRunResult>();
+    module.register_type::<crate::processing::TransactionTreeOptions>();
+    module.register_type::<crate::processing::ResultOfTransactionTree>();
+    module.register_type::<crate::processing::LatencyStats>();
+    module.register_type::<crate::processing::RempStatusStats>();
+    module.register_type::<crate::processing::EndpointFailures>();
+    module.register_type::<crate::processing::RempStatusUpdate>();
 
     module.register_type::<crate::processing::MessageMonitoringTransactionCompute>();
     module.register_type::<crate::processing::MessageMonitoringTransaction>();
@@ -579,6 +677,8 @@ fn register_processing(handlers: &mut RuntimeHandlers) {
     module.register_type::<crate::processing::MonitoredMessage>();
     module.register_type::<crate::processing::MessageMonitoringStatus>();
     module.register_type::<crate::processing::MessageSendingParams>();
+    module.register_type::<crate::processing::OutboxMessage>();
+    module.register_type::<crate::processing::OutboxMessageResult>();
 
     module.register_sync_fn(
         crate::processing::monitor_messages,
@@ -600,6 +700,22 @@ fn register_processing(handlers: &mut RuntimeHandlers) {
         crate::processing::send_messages,
         crate::processing::send_messages_api,
     );
+    module.register_async_fn_no_args(
+        crate::processing::get_outbox,
+        crate::processing::outbox::get_outbox_api,
+    );
+    module.register_async_fn(
+        crate::processing::resume_outbox,
+        crate::processing::outbox::resume_outbox_api,
+    );
+    module.register_async_fn(
+        crate::processing::get_sequencer_info,
+        crate::processing::sequencer::get_sequencer_info_api,
+    );
+    module.register_sync_fn(
+        crate::processing::get_stats,
+        crate::processing::stats::get_stats_api,
+    );
 
     module.register_async_fn_with_callback(
         super::processing::send_message,
@@ -613,6 +729,14 @@ fn register_processing(handlers: &mut RuntimeHandlers) {
         super::processing::process_message,
         super::processing::process_message_api,
     );
+    module.register_async_fn_with_callback(
+        super::processing::process_sequenced_message,
+        super::processing::process_sequenced_message_api,
+    );
+    module.register_async_fn_with_callback(
+        super::processing::subscribe_remp_statuses,
+        super::processing::subscribe_remp_statuses_api,
+    );
 
     module.register();
 }
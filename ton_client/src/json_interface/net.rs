@@ -15,7 +15,10 @@
 use super::request::Request;
 use crate::client::ClientContext;
 use crate::error::ClientResult;
-use crate::net::{ParamsOfSubscribeCollection, ResultOfSubscribeCollection, ResultOfSubscription};
+use crate::net::{
+    DecodedEvent, ParamsOfSubscribeCollection, ParamsOfSubscribeEvents,
+    ResultOfSubscribeCollection, ResultOfSubscription,
+};
 use crate::net::subscriptions::ParamsOfSubscribe;
 
 /// Creates a collection subscription
@@ -135,3 +138,37 @@ pub(crate) async fn subscribe(
 
     crate::net::subscribe(context, params, callback).await
 }
+
+/// Creates a subscription for the contract events
+///
+/// Triggers for each event emitted by the contracts with the specified `addresses`.
+/// Events are decoded with the specified `abi` and delivered
+/// as `DecodedEvent` objects with the block and transaction context.
+///
+/// If `event_names` is specified then only events with the
+/// matching names are delivered.
+///
+/// If `start_time` is specified then the library delivers all the
+/// events stored since `start_time` first and then switches to the live mode.
+///
+/// The same notes on network reconnection as for `subscribe_collection` are
+/// applicable here: library reports errors with `responseType` == 101
+/// and the error object passed via `params`.
+///
+/// The subscription must be closed with `unsubscribe`.
+#[api_function]
+pub(crate) async fn subscribe_events(
+    context: std::sync::Arc<ClientContext>,
+    params: ParamsOfSubscribeEvents,
+    callback: std::sync::Arc<Request>,
+) -> ClientResult<ResultOfSubscribeCollection> {
+    let callback = move |result: ClientResult<DecodedEvent>| {
+        match result {
+            Ok(event) => callback.response(event, crate::net::SubscriptionResponseType::Ok as u32),
+            Err(err) => callback.response(err, crate::net::SubscriptionResponseType::Error as u32),
+        }
+        futures::future::ready(())
+    };
+
+    crate::net::subscribe_events(context, params, callback).await
+}
@@ -16,9 +16,11 @@ use super::request::Request;
 use crate::client::ClientContext;
 use crate::error::ClientResult;
 use crate::processing::{
-    ParamsOfProcessMessage, ParamsOfSendMessage, ParamsOfWaitForTransaction, ProcessingEvent,
-    ProcessingResponseType, ResultOfProcessMessage, ResultOfSendMessage,
+    ParamsOfProcessMessage, ParamsOfProcessSequencedMessage, ParamsOfSendMessage,
+    ParamsOfSubscribeRempStatuses, ParamsOfWaitForTransaction, ProcessingEvent,
+    ProcessingResponseType, RempStatusUpdate, ResultOfProcessMessage, ResultOfSendMessage,
 };
+use crate::net::ResultOfSubscribeCollection;
 use std::sync::Arc;
 
 /// Creates message, sends it to the network and monitors its processing.
@@ -52,6 +54,30 @@ pub(crate) async fn process_message(
     crate::processing::process_message(context, params, callback).await
 }
 
+/// Creates message and processes it in the queue of the destination account.
+///
+/// Messages to the same account are processed one by one: the next message is created
+/// only after the processing of the previous one is finished. Each message gets the unique
+/// increasing `time` header, so the messages created at the same time are not rejected
+/// by the contract replay protection and are delivered in the order of the calls.
+///
+/// Expired messages are recreated with the new `time` and `expire` headers and signed
+/// again, the retry configuration is the same as for `process_message`.
+///
+/// The state of the account queue can be checked with `get_sequencer_info`.
+#[api_function]
+pub(crate) async fn process_sequenced_message(
+    context: Arc<ClientContext>,
+    params: ParamsOfProcessSequencedMessage,
+    request: Arc<Request>,
+) -> ClientResult<ResultOfProcessMessage> {
+    let callback = move |event: ProcessingEvent| {
+        request.response(event, ProcessingResponseType::ProcessingEvent as u32);
+        futures::future::ready(())
+    };
+    crate::processing::process_sequenced_message(context, params, callback).await
+}
+
 /// Sends message to the network
 ///
 /// Sends message to the network and returns the last generated shard block of the destination account
@@ -109,3 +135,33 @@ pub(crate) async fn wait_for_transaction(
     };
     crate::processing::wait_for_transaction(context, params, callback).await
 }
+
+/// Creates a subscription for the REMP statuses of the messages.
+///
+/// Tracks the messages with the specified ids, including the messages
+/// sent by other clients, and delivers their REMP statuses as `RempStatusUpdate`
+/// objects with `responseType` == 100.
+///
+/// `Finalized` and `RejectedByFullnode` are the final statuses: no more updates
+/// are delivered for the message after them.
+///
+/// Library reports subscription errors with `responseType` == 101
+/// and the error object passed via `params`.
+///
+/// The subscription must be closed with `net.unsubscribe`.
+#[api_function]
+pub(crate) async fn subscribe_remp_statuses(
+    context: Arc<ClientContext>,
+    params: ParamsOfSubscribeRempStatuses,
+    callback: Arc<Request>,
+) -> ClientResult<ResultOfSubscribeCollection> {
+    let callback = move |result: ClientResult<RempStatusUpdate>| {
+        match result {
+            Ok(status) => callback.response(status, crate::net::SubscriptionResponseType::Ok as u32),
+            Err(err) => callback.response(err, crate::net::SubscriptionResponseType::Error as u32),
+        }
+        futures::future::ready(())
+    };
+
+    crate::processing::subscribe_remp_statuses(context, params, callback).await
+}
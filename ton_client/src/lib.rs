@@ -23,6 +23,10 @@ extern crate lazy_static;
 #[macro_use]
 extern crate log;
 
+// Generated contract bindings refer to the crate as `::ton_client`
+#[cfg(test)]
+extern crate self as ton_client;
+
 pub mod abi;
 pub mod boc;
 pub mod client;
@@ -23,6 +23,7 @@ pub enum ErrorCode {
     QueryTransactionTreeTimeout = 616,
     GraphqlConnectionError = 617,
     WrongWebscoketProtocolSequence = 618,
+    TooManyRequests = 619,
 }
 
 pub struct Error;
@@ -205,4 +206,13 @@ impl Error {
             format!("Wrong webscoket protocol sequence: {}", err),
         )
     }
+
+    pub(crate) fn too_many_requests(response: &FetchResult, retry_after: Option<u64>) -> ClientError {
+        let mut err = error(
+            ErrorCode::TooManyRequests,
+            format!("Too many requests: {}", response.body),
+        );
+        err.data = json!({ "retry_after": retry_after });
+        err
+    }
 }
@@ -0,0 +1,290 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use futures::Future;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::abi::decode_message::decode_body;
+use crate::abi::{Abi, MessageBodyType};
+use crate::boc::internal::deserialize_cell_from_boc;
+use crate::client::ClientContext;
+use crate::encoding::slice_from_cell;
+use crate::error::ClientResult;
+use crate::net::{
+    OrderBy, ParamsOfQueryCollection, ResultOfSubscribeCollection, ResultOfSubscription,
+    SortDirection, MESSAGES_COLLECTION,
+};
+
+const EXT_OUT_MSG_TYPE: u8 = 2;
+const HISTORY_PAGE_SIZE: u32 = 50;
+
+const EVENT_FIELDS: &str = "id src body created_at created_lt block_id";
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfSubscribeEvents {
+    /// Addresses of the contracts which events must be delivered.
+    pub addresses: Vec<String>,
+
+    /// Contract ABI used to decode events.
+    pub abi: Abi,
+
+    /// Names of the ABI events that must be delivered.
+    ///
+    /// If this parameter is missing or an empty list then all events
+    /// described in the ABI are delivered.
+    pub event_names: Option<Vec<String>>,
+
+    /// Starting time to deliver events from.
+    ///
+    /// If the application specifies this parameter then the library
+    /// delivers all the events with `created_at` >= `start_time` stored in
+    /// the history before switching to the live mode.
+    /// Otherwise only new events are delivered.
+    ///
+    /// Must be specified in seconds.
+    pub start_time: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug)]
+pub struct DecodedEvent {
+    /// Address of the contract that emitted the event.
+    pub address: String,
+
+    /// Event name.
+    pub name: String,
+
+    /// Event parameters.
+    pub value: Option<Value>,
+
+    /// Id of the external outbound message containing the event.
+    pub message_id: String,
+
+    /// Id of the block containing the event message.
+    pub block_id: Option<String>,
+
+    /// Id of the transaction that emitted the event.
+    ///
+    /// This field is available only for the events delivered from the history.
+    pub transaction_id: Option<String>,
+
+    /// Event message creation time in seconds.
+    pub created_at: u32,
+
+    /// Logical time of the event message creation.
+    pub created_lt: String,
+}
+
+struct EventDecoder {
+    context: Arc<ClientContext>,
+    abi: Arc<ton_abi::Contract>,
+    event_names: HashSet<String>,
+}
+
+impl EventDecoder {
+    fn new(context: Arc<ClientContext>, params: &ParamsOfSubscribeEvents) -> ClientResult<Self> {
+        Ok(Self {
+            abi: params.abi.resolve_abi(&context)?,
+            context,
+            event_names: params
+                .event_names
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+        })
+    }
+
+    fn decode(&self, message: &Value) -> ClientResult<Option<DecodedEvent>> {
+        let body = match message["body"].as_str() {
+            Some(body) => body,
+            None => return Ok(None),
+        };
+        let (_, body) = deserialize_cell_from_boc(&self.context, body, "event body")?;
+        let decoded = match decode_body(
+            &self.abi,
+            slice_from_cell(body)?,
+            false,
+            false,
+            None,
+            None,
+        ) {
+            Ok(decoded) => decoded,
+            Err(_) => return Ok(None),
+        };
+        if decoded.body_type != MessageBodyType::Event {
+            return Ok(None);
+        }
+        if !self.event_names.is_empty() && !self.event_names.contains(&decoded.name) {
+            return Ok(None);
+        }
+        Ok(Some(DecodedEvent {
+            address: message["src"].as_str().unwrap_or_default().to_string(),
+            name: decoded.name,
+            value: decoded.value,
+            message_id: message["id"].as_str().unwrap_or_default().to_string(),
+            block_id: message["block_id"].as_str().map(|x| x.to_string()),
+            transaction_id: message["src_transaction"]["id"]
+                .as_str()
+                .map(|x| x.to_string()),
+            created_at: message["created_at"].as_u64().unwrap_or_default() as u32,
+            created_lt: message["created_lt"].as_str().unwrap_or_default().to_string(),
+        }))
+    }
+}
+
+fn events_filter(addresses: &[String]) -> Value {
+    json!({
+        "src": { "in": addresses },
+        "msg_type": { "eq": EXT_OUT_MSG_TYPE },
+    })
+}
+
+async fn deliver_history<F: Future<Output = ()> + Send>(
+    decoder: &EventDecoder,
+    addresses: &[String],
+    start_time: u32,
+    delivered: &mut HashSet<String>,
+    callback: &(impl Fn(ClientResult<DecodedEvent>) -> F + Send + Sync),
+) -> ClientResult<()> {
+    let server_link = decoder.context.get_server_link()?;
+    let mut history_filter = events_filter(addresses);
+    history_filter["created_at"] = json!({ "ge": start_time });
+    let mut filter = history_filter.clone();
+    loop {
+        let messages = server_link
+            .query_collection(
+                ParamsOfQueryCollection {
+                    collection: MESSAGES_COLLECTION.to_string(),
+                    filter: Some(filter.clone()),
+                    result: format!("{} src_transaction {{ id }}", EVENT_FIELDS),
+                    order: Some(vec![
+                        OrderBy {
+                            path: "created_lt".to_string(),
+                            direction: SortDirection::ASC,
+                        },
+                        OrderBy {
+                            path: "id".to_string(),
+                            direction: SortDirection::ASC,
+                        },
+                    ]),
+                    limit: Some(HISTORY_PAGE_SIZE),
+                },
+                None,
+            )
+            .await?;
+        let messages = messages.as_array().cloned().unwrap_or_default();
+        for message in &messages {
+            let id = message["id"].as_str().unwrap_or_default().to_string();
+            if !delivered.insert(id) {
+                continue;
+            }
+            if let Some(event) = decoder.decode(message)? {
+                callback(Ok(event)).await;
+            }
+        }
+        match messages.last() {
+            Some(last) if messages.len() >= HISTORY_PAGE_SIZE as usize => {
+                filter = next_history_page_filter(&history_filter, last);
+            }
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Filter for the history messages following the `last` one in `(created_lt, id)` order.
+///
+/// Messages with the same `created_lt` can be split between pages,
+/// so the `id` is used to continue from the exact position.
+pub(crate) fn next_history_page_filter(history_filter: &Value, last: &Value) -> Value {
+    let mut same_lt = history_filter.clone();
+    same_lt["created_lt"] = json!({ "eq": last["created_lt"] });
+    same_lt["id"] = json!({ "gt": last["id"] });
+
+    let mut filter = history_filter.clone();
+    filter["created_lt"] = json!({ "gt": last["created_lt"] });
+    filter["OR"] = same_lt;
+    filter
+}
+
+/// Creates a subscription for the contract events.
+///
+/// Delivers events emitted by the contracts with the specified `addresses`
+/// decoded with the specified `abi`. If `event_names` is specified then only
+/// the events with matching names are delivered.
+///
+/// If `start_time` is specified then the library delivers events
+/// stored in the history since `start_time` first and then switches
+/// to the live mode. Events are not lost or duplicated at the moment of switching
+/// because the live subscription is started before the history is read.
+///
+/// The subscription must be closed with `unsubscribe`.
+pub async fn subscribe_events<F: Future<Output = ()> + Send>(
+    context: Arc<ClientContext>,
+    params: ParamsOfSubscribeEvents,
+    callback: impl Fn(ClientResult<DecodedEvent>) -> F + Send + Sync + 'static,
+) -> ClientResult<ResultOfSubscribeCollection> {
+    let decoder = EventDecoder::new(context.clone(), &params)?;
+
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let handle = context
+        .net
+        .subscribe_collection(
+            MESSAGES_COLLECTION.to_string(),
+            Some(events_filter(&params.addresses)),
+            EVENT_FIELDS.to_string(),
+            move |result: ClientResult<ResultOfSubscription>| {
+                let _ = sender.send(result);
+                futures::future::ready(())
+            },
+        )
+        .await?;
+
+    let addresses = params.addresses;
+    let start_time = params.start_time;
+    context.env.spawn(async move {
+        let mut delivered = HashSet::new();
+        if let Some(start_time) = start_time {
+            if let Err(err) =
+                deliver_history(&decoder, &addresses, start_time, &mut delivered, &callback).await
+            {
+                callback(Err(err)).await;
+            }
+        }
+        // Receiver is closed when the subscription is removed with `unsubscribe`
+        while let Some(result) = receiver.recv().await {
+            match result {
+                Ok(ResultOfSubscription { result: message }) => {
+                    if message.is_null() {
+                        continue;
+                    }
+                    let id = message["id"].as_str().unwrap_or_default();
+                    if delivered.remove(id) {
+                        continue;
+                    }
+                    match decoder.decode(&message) {
+                        Ok(Some(event)) => callback(Ok(event)).await,
+                        Ok(None) => {}
+                        Err(err) => callback(Err(err)).await,
+                    }
+                }
+                Err(err) => callback(Err(err)).await,
+            }
+        }
+    });
+
+    Ok(ResultOfSubscribeCollection { handle })
+}
@@ -0,0 +1,317 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::abi::code_registry::CodeAbiResolver;
+use crate::abi::Abi;
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use crate::net::iterators::{register_iterator, ResultOfIteratorNext};
+use crate::net::transaction_tree::MessageNode;
+use crate::net::{
+    fetch_collection, ChainIterator, OrderBy, ParamsOfQueryCollection, RegisteredIterator,
+    SortDirection, TRANSACTIONS_COLLECTION,
+};
+
+const ACCOUNT_TRANSACTION_FIELDS: &str = r#"
+    id
+    account_addr
+    lt
+    now
+    aborted
+    balance_delta(format:DEC)
+    in_message {
+        id
+        src
+        dst
+        value(format:DEC)
+        msg_type
+        body
+    }
+    out_messages {
+        id
+        src
+        dst
+        value(format:DEC)
+        msg_type
+        body
+    }
+"#;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ResumeState {
+    account: String,
+    order: SortDirection,
+    result_fields: String,
+    last_lt: Option<String>,
+    has_more: bool,
+}
+
+pub(crate) struct AccountTransactionsIterator {
+    account: String,
+    order: SortDirection,
+    result_fields: String,
+    abi_registry: Option<Vec<Abi>>,
+    last_lt: Option<String>,
+    has_more: bool,
+}
+
+impl AccountTransactionsIterator {
+    pub fn new(params: ParamsOfCreateAccountTransactionsIterator) -> Self {
+        Self {
+            account: params.account,
+            order: params.order.unwrap_or(SortDirection::DESC),
+            result_fields: params.result.unwrap_or_default(),
+            abi_registry: params.abi_registry,
+            last_lt: None,
+            has_more: true,
+        }
+    }
+
+    pub fn resume(params: ParamsOfResumeAccountTransactionsIterator) -> ClientResult<Self> {
+        let resume = ResumeState::deserialize(&params.resume_state).map_err(|e| {
+            crate::client::Error::internal_error(format!("Invalid iterator resume state: {}", e))
+        })?;
+        Ok(Self {
+            account: resume.account,
+            order: resume.order,
+            result_fields: resume.result_fields,
+            abi_registry: params.abi_registry,
+            last_lt: resume.last_lt,
+            has_more: resume.has_more,
+        })
+    }
+
+    fn get_resume_state_value(&self) -> ClientResult<Value> {
+        serde_json::to_value(ResumeState {
+            account: self.account.clone(),
+            order: self.order.clone(),
+            result_fields: self.result_fields.clone(),
+            last_lt: self.last_lt.clone(),
+            has_more: self.has_more,
+        })
+        .map_err(|e| {
+            crate::client::Error::internal_error(format!(
+                "Can't serialize iterator resume state: {}",
+                e
+            ))
+        })
+    }
+
+    fn filter(&self) -> Value {
+        let mut filter = json!({
+            "account_addr": { "eq": self.account },
+        });
+        if let Some(last_lt) = &self.last_lt {
+            filter["lt"] = match self.order {
+                SortDirection::ASC => json!({ "gt": last_lt }),
+                SortDirection::DESC => json!({ "lt": last_lt }),
+            };
+        }
+        filter
+    }
+
+    /// Sets `decoded_body` of the inbound and outbound messages of the transactions.
+    async fn decode_messages(&self, context: &Arc<ClientContext>, transactions: &mut [Value]) {
+        let messages = transactions
+            .iter()
+            .flat_map(|transaction| {
+                std::iter::once(&transaction["in_message"])
+                    .chain(transaction["out_messages"].as_array().into_iter().flatten())
+            })
+            .filter(|message| message.is_object())
+            .collect::<Vec<_>>();
+        let mut code_abis = CodeAbiResolver::default();
+        let mut decoded =
+            MessageNode::decode_bodies(&messages, context, &self.abi_registry, &mut code_abis)
+                .await
+                .into_iter();
+        let mut set_decoded = |message: &mut Value| {
+            if message.is_object() {
+                message["decoded_body"] =
+                    serde_json::to_value(decoded.next().flatten()).unwrap_or_default();
+            }
+        };
+        for transaction in transactions {
+            set_decoded(&mut transaction["in_message"]);
+            if let Some(out_messages) = transaction["out_messages"].as_array_mut() {
+                out_messages.iter_mut().for_each(&mut set_decoded);
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ChainIterator for AccountTransactionsIterator {
+    async fn next(
+        &mut self,
+        context: &Arc<ClientContext>,
+        limit: u32,
+        return_resume_state: bool,
+    ) -> ClientResult<ResultOfIteratorNext> {
+        let limit = limit.max(1);
+        let mut items = Vec::new();
+        if self.has_more {
+            // One extra item is requested to detect the end of the history
+            items = fetch_collection(
+                context.clone(),
+                ParamsOfQueryCollection {
+                    collection: TRANSACTIONS_COLLECTION.to_string(),
+                    filter: Some(self.filter()),
+                    result: format!("{} {}", ACCOUNT_TRANSACTION_FIELDS, self.result_fields),
+                    order: Some(vec![OrderBy {
+                        path: "lt".to_string(),
+                        direction: self.order.clone(),
+                    }]),
+                    limit: Some(limit + 1),
+                },
+            )
+            .await?
+            .result;
+            self.has_more = items.len() > limit as usize;
+            items.truncate(limit as usize);
+            if let Some(last) = items.last() {
+                self.last_lt = last["lt"].as_str().map(|x| x.to_string());
+            }
+            self.decode_messages(context, &mut items).await;
+        }
+
+        let resume_state = if return_resume_state {
+            Some(self.get_resume_state_value()?)
+        } else {
+            None
+        };
+
+        Ok(ResultOfIteratorNext {
+            items,
+            has_more: self.has_more,
+            resume_state,
+        })
+    }
+
+    fn after_remove(&mut self, _context: &Arc<ClientContext>) {}
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfCreateAccountTransactionsIterator {
+    /// Account address.
+    pub account: String,
+
+    /// Iteration order.
+    ///
+    /// `DESC` iterates from the newest transactions to the oldest ones,
+    /// `ASC` iterates from the oldest transactions to the newest ones.
+    ///
+    /// Default is `DESC`.
+    pub order: Option<SortDirection>,
+
+    /// Projection (result) string.
+    ///
+    /// List of the additional fields that must be returned for iterated items.
+    /// This field is the same as the `result` parameter of
+    /// the `query_collection` function.
+    pub result: Option<String>,
+
+    /// List of contract ABIs that will be used to decode message bodies.
+    ///
+    /// Library tries to decode each message body using ABIs from the registry
+    /// in the specified order.
+    pub abi_registry: Option<Vec<Abi>>,
+}
+
+/// Creates account transactions iterator.
+///
+/// Iterates the transactions of the specified account ordered by the logical time.
+/// Iteration order is specified with `order` parameter:
+/// newest transactions first (default) or oldest transactions first.
+///
+/// Iterated item is a JSON object with transaction data. The minimal set of returned
+/// fields is:
+/// ```text
+/// id
+/// account_addr
+/// lt
+/// now
+/// aborted
+/// balance_delta(format:DEC)
+/// in_message {
+///     id
+///     src
+///     dst
+///     value(format:DEC)
+///     msg_type
+///     body
+///     decoded_body
+/// }
+/// out_messages {
+///     id
+///     src
+///     dst
+///     value(format:DEC)
+///     msg_type
+///     body
+///     decoded_body
+/// }
+/// ```
+/// Application can request an additional fields in the `result` parameter.
+///
+/// `decoded_body` contains the message body decoded with the first suitable ABI
+/// from `abi_registry` or with the ABI registered for the message account by
+/// `abi.register_code_abi`, `null` if the body can't be decoded.
+///
+/// Application should call the `remove_iterator` when iterator is no longer required.
+#[api_function]
+pub async fn create_account_transactions_iterator(
+    context: Arc<ClientContext>,
+    params: ParamsOfCreateAccountTransactionsIterator,
+) -> ClientResult<RegisteredIterator> {
+    register_iterator(
+        &context,
+        Box::new(AccountTransactionsIterator::new(params)),
+    )
+    .await
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfResumeAccountTransactionsIterator {
+    /// Iterator state from which to resume.
+    ///
+    /// Same as value returned from `iterator_next`.
+    pub resume_state: Value,
+
+    /// List of contract ABIs that will be used to decode message bodies.
+    pub abi_registry: Option<Vec<Abi>>,
+}
+
+/// Resumes account transactions iterator.
+///
+/// The iterator stays exactly at the same position where the `resume_state` was caught.
+/// Note that `resume_state` doesn't store the ABI registry. If the application requires
+/// message decoding then it must pass the ABI registry again in `abi_registry` parameter.
+///
+/// Application should call the `remove_iterator` when iterator is no longer required.
+#[api_function]
+pub async fn resume_account_transactions_iterator(
+    context: Arc<ClientContext>,
+    params: ParamsOfResumeAccountTransactionsIterator,
+) -> ClientResult<RegisteredIterator> {
+    register_iterator(
+        &context,
+        Box::new(AccountTransactionsIterator::resume(params)?),
+    )
+    .await
+}
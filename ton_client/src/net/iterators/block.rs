@@ -19,7 +19,7 @@ use serde_json::Value;
 use std::fmt;
 
 use crate::error::ClientResult;
-use crate::net::{query_collection, OrderBy, ParamsOfQueryCollection, SortDirection};
+use crate::net::{fetch_collection, OrderBy, ParamsOfQueryCollection, SortDirection};
 use crate::ClientContext;
 use serde::Serializer;
 use ton_block::ShardIdent;
@@ -301,7 +301,7 @@ impl MasterBlock {
         limit: u32,
         fields: &str,
     ) -> ClientResult<Vec<Value>> {
-        query_collection(
+        fetch_collection(
             context.clone(),
             ParamsOfQueryCollection {
                 collection: "blocks".to_string(),
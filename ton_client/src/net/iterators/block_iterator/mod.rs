@@ -30,7 +30,7 @@ use crate::net::iterators::block::{
 };
 use crate::net::iterators::block_iterator::state::StateBuilder;
 use crate::net::iterators::{query_by_ids, register_iterator, ResultOfIteratorNext};
-use crate::net::{query_collection, ChainIterator, ParamsOfQueryCollection, RegisteredIterator};
+use crate::net::{fetch_collection, ChainIterator, ParamsOfQueryCollection, RegisteredIterator};
 use ton_block::ShardIdent;
 
 mod branch;
@@ -234,7 +234,7 @@ impl BlockIterator {
             let by_prev_ids = prev_ids_by(NextLink::ByPrev);
             let by_prev_alt_ids = prev_ids_by(NextLink::ByPrevAlt);
 
-            let mut blocks = query_collection(
+            let mut blocks = fetch_collection(
                 context.clone(),
                 ParamsOfQueryCollection {
                     collection: "blocks".to_string(),
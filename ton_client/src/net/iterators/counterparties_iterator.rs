@@ -0,0 +1,185 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use crate::net::iterators::{register_iterator, ResultOfIteratorNext};
+use crate::net::{query_counterparties, ChainIterator, ParamsOfQueryCounterparties, RegisteredIterator};
+
+const COUNTERPARTY_FIELDS: &str = "counterparty last_message_id last_message_at cursor";
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ResumeState {
+    account: String,
+    result_fields: String,
+    cursor: Option<String>,
+    has_more: bool,
+}
+
+pub(crate) struct CounterpartiesIterator {
+    account: String,
+    result_fields: String,
+    cursor: Option<String>,
+    has_more: bool,
+}
+
+impl CounterpartiesIterator {
+    pub fn new(params: ParamsOfCreateCounterpartiesIterator) -> Self {
+        Self {
+            account: params.account,
+            result_fields: params.result.unwrap_or_default(),
+            cursor: None,
+            has_more: true,
+        }
+    }
+
+    pub fn resume(params: ParamsOfResumeCounterpartiesIterator) -> ClientResult<Self> {
+        let resume = ResumeState::deserialize(&params.resume_state).map_err(|e| {
+            crate::client::Error::internal_error(format!("Invalid iterator resume state: {}", e))
+        })?;
+        Ok(Self {
+            account: resume.account,
+            result_fields: resume.result_fields,
+            cursor: resume.cursor,
+            has_more: resume.has_more,
+        })
+    }
+
+    fn get_resume_state_value(&self) -> ClientResult<Value> {
+        serde_json::to_value(ResumeState {
+            account: self.account.clone(),
+            result_fields: self.result_fields.clone(),
+            cursor: self.cursor.clone(),
+            has_more: self.has_more,
+        })
+        .map_err(|e| {
+            crate::client::Error::internal_error(format!(
+                "Can't serialize iterator resume state: {}",
+                e
+            ))
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ChainIterator for CounterpartiesIterator {
+    async fn next(
+        &mut self,
+        context: &Arc<ClientContext>,
+        limit: u32,
+        return_resume_state: bool,
+    ) -> ClientResult<ResultOfIteratorNext> {
+        let limit = limit.max(1);
+        let mut items = Vec::new();
+        if self.has_more {
+            // One extra item is requested to detect the end of the list
+            items = query_counterparties(
+                context.clone(),
+                ParamsOfQueryCounterparties {
+                    account: self.account.clone(),
+                    result: format!("{} {}", COUNTERPARTY_FIELDS, self.result_fields),
+                    first: Some(limit + 1),
+                    after: self.cursor.clone(),
+                },
+            )
+            .await?
+            .result;
+            self.has_more = items.len() > limit as usize;
+            items.truncate(limit as usize);
+            if let Some(last) = items.last() {
+                self.cursor = last["cursor"].as_str().map(|x| x.to_string());
+            }
+        }
+
+        let resume_state = if return_resume_state {
+            Some(self.get_resume_state_value()?)
+        } else {
+            None
+        };
+
+        Ok(ResultOfIteratorNext {
+            items,
+            has_more: self.has_more,
+            resume_state,
+        })
+    }
+
+    fn after_remove(&mut self, _context: &Arc<ClientContext>) {}
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfCreateCounterpartiesIterator {
+    /// Account address.
+    pub account: String,
+
+    /// Projection (result) string.
+    ///
+    /// List of the additional fields that must be returned for iterated items.
+    /// This field is the same as the `result` parameter of
+    /// the `query_counterparties` function.
+    pub result: Option<String>,
+}
+
+/// Creates counterparties iterator.
+///
+/// Iterates the accounts that the specified account has interacted with,
+/// sorted by the time of the last internal message between accounts
+/// (the most recent counterparties first).
+///
+/// Iterated item is a JSON object with counterparty data. The minimal set of returned
+/// fields is:
+/// ```text
+/// counterparty
+/// last_message_id
+/// last_message_at
+/// cursor
+/// ```
+/// Application can request an additional fields in the `result` parameter.
+///
+/// *Attention* this iterator retrieves data from 'Counterparties' service which is not supported in
+/// the opensource version of DApp Server. See `query_counterparties` for details.
+///
+/// Application should call the `remove_iterator` when iterator is no longer required.
+#[api_function]
+pub async fn create_counterparties_iterator(
+    context: Arc<ClientContext>,
+    params: ParamsOfCreateCounterpartiesIterator,
+) -> ClientResult<RegisteredIterator> {
+    register_iterator(&context, Box::new(CounterpartiesIterator::new(params))).await
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfResumeCounterpartiesIterator {
+    /// Iterator state from which to resume.
+    ///
+    /// Same as value returned from `iterator_next`.
+    pub resume_state: Value,
+}
+
+/// Resumes counterparties iterator.
+///
+/// The iterator stays exactly at the same position where the `resume_state` was caught.
+///
+/// Application should call the `remove_iterator` when iterator is no longer required.
+#[api_function]
+pub async fn resume_counterparties_iterator(
+    context: Arc<ClientContext>,
+    params: ParamsOfResumeCounterpartiesIterator,
+) -> ClientResult<RegisteredIterator> {
+    register_iterator(&context, Box::new(CounterpartiesIterator::resume(params)?)).await
+}
@@ -11,8 +11,10 @@
 * limitations under the License.
 */
 
+pub(crate) mod account_transactions_iterator;
 pub(crate) mod block;
 pub(crate) mod block_iterator;
+pub(crate) mod counterparties_iterator;
 pub(crate) mod transaction;
 pub(crate) mod transaction_iterator;
 
@@ -21,7 +23,7 @@ mod tests;
 
 use crate::client::ClientContext;
 use crate::error::ClientResult;
-use crate::net::{query_collection, ParamsOfQueryCollection};
+use crate::net::{fetch_collection, ParamsOfQueryCollection};
 use rand::RngCore;
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
@@ -187,7 +189,7 @@ pub(crate) async fn query_by_ids(
         let mut query_queue: HashSet<String> = head_ids.iter().cloned().collect();
         while !query_queue.is_empty() {
             let portion_ids: Vec<String> = query_queue.iter().cloned().collect();
-            let portion = query_collection(
+            let portion = fetch_collection(
                 client.clone(),
                 ParamsOfQueryCollection {
                     collection: collection.to_string(),
@@ -4,7 +4,10 @@ use crate::tests::{TestClient};
 use super::*;
 use serde_json::Value;
 use std::collections::HashSet;
-use crate::net::ResultOfQueryCollection;
+use crate::net::{
+    ParamsOfCreateAccountTransactionsIterator, ParamsOfCreateCounterpartiesIterator,
+    ParamsOfResumeAccountTransactionsIterator, ResultOfQueryCollection,
+};
 
 async fn query_ids_in_range(
     client: &TestClient,
@@ -217,6 +220,113 @@ async fn transaction_iterator() {
     assert_eq!(extra_ids, HashSet::default(), "Extra iterated");
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn account_transactions_iterator() {
+    let client = TestClient::new();
+    let account = client.giver_address().await;
+
+    let iterator: RegisteredIterator = client
+        .request_async(
+            "net.create_account_transactions_iterator",
+            ParamsOfCreateAccountTransactionsIterator {
+                account: account.clone(),
+                abi_registry: Some(vec![TestClient::giver_abi()]),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let first: ResultOfIteratorNext = client
+        .request_async(
+            "net.iterator_next",
+            json!({
+                "iterator": iterator.handle,
+                "return_resume_state": true,
+                "limit": 3,
+            }),
+        )
+        .await
+        .unwrap();
+    remove_iterator(&client, iterator.handle).await;
+    assert!(first.items.len() > 0);
+    for transaction in &first.items {
+        assert_eq!(transaction["account_addr"], account);
+    }
+    if !first.has_more {
+        return;
+    }
+
+    let iterator: RegisteredIterator = client
+        .request_async(
+            "net.resume_account_transactions_iterator",
+            ParamsOfResumeAccountTransactionsIterator {
+                resume_state: first.resume_state.unwrap(),
+                abi_registry: None,
+            },
+        )
+        .await
+        .unwrap();
+    let second: ResultOfIteratorNext = client
+        .request_async(
+            "net.iterator_next",
+            json!({
+                "iterator": iterator.handle,
+                "limit": 3,
+            }),
+        )
+        .await
+        .unwrap();
+    remove_iterator(&client, iterator.handle).await;
+
+    // Newest transactions are iterated first
+    let lt = |transaction: &Value| {
+        u64::from_str_radix(transaction["lt"].as_str().unwrap().trim_start_matches("0x"), 16)
+            .unwrap()
+    };
+    let lts: Vec<u64> = first.items.iter().chain(second.items.iter()).map(lt).collect();
+    assert!(lts.windows(2).all(|pair| pair[0] > pair[1]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn counterparties_iterator() {
+    if TestClient::node_se() {
+        return;
+    }
+    let client = TestClient::new();
+    let account = client.giver_address().await;
+
+    let iterator: RegisteredIterator = client
+        .request_async(
+            "net.create_counterparties_iterator",
+            ParamsOfCreateCounterpartiesIterator {
+                account,
+                result: None,
+            },
+        )
+        .await
+        .unwrap();
+    let mut counterparties = HashSet::new();
+    let mut has_more = true;
+    while has_more && counterparties.len() < 20 {
+        let next: ResultOfIteratorNext = client
+            .request_async(
+                "net.iterator_next",
+                json!({
+                    "iterator": iterator.handle,
+                    "limit": 5,
+                }),
+            )
+            .await
+            .unwrap();
+        for item in next.items {
+            let counterparty = item["counterparty"].as_str().unwrap().to_string();
+            assert!(counterparties.insert(counterparty), "Iterated twice");
+        }
+        has_more = next.has_more;
+    }
+    remove_iterator(&client, iterator.handle).await;
+}
+
 /*
 
 const iterator = await client.net.create_block_iterator({
@@ -14,10 +14,19 @@
 pub use batch::{batch_query, ParamsOfBatchQuery, ResultOfBatchQuery};
 pub(crate) use endpoint::Endpoint;
 pub use errors::{Error, ErrorCode};
+pub use events::{subscribe_events, DecodedEvent, ParamsOfSubscribeEvents};
+pub use iterators::account_transactions_iterator::{
+    create_account_transactions_iterator, resume_account_transactions_iterator,
+    ParamsOfCreateAccountTransactionsIterator, ParamsOfResumeAccountTransactionsIterator,
+};
 pub use iterators::block_iterator::{
     create_block_iterator, resume_block_iterator, ParamsOfCreateBlockIterator,
     ParamsOfResumeBlockIterator,
 };
+pub use iterators::counterparties_iterator::{
+    create_counterparties_iterator, resume_counterparties_iterator,
+    ParamsOfCreateCounterpartiesIterator, ParamsOfResumeCounterpartiesIterator,
+};
 pub use iterators::transaction_iterator::{
     create_transaction_iterator, resume_transaction_iterator, ParamsOfCreateTransactionIterator,
     ParamsOfResumeTransactionIterator,
@@ -32,6 +41,9 @@ pub use queries::{
     ParamsOfQuery, ParamsOfWaitForCollection, ResultOfAggregateCollection, ResultOfQuery,
     ResultOfQueryCollection, ResultOfWaitForCollection,
 };
+pub(crate) use queries::fetch_collection;
+pub use query_cache::QueryCacheConfig;
+pub use rate_limiter::{get_rate_limit_stats, RateLimitConfig, ResultOfGetRateLimitStats};
 pub(crate) use server_link::{EndpointStat, NetworkState, ServerLink, MAX_TIMEOUT};
 pub use subscriptions::{
     subscribe, subscribe_collection, unsubscribe, ParamsOfSubscribe, ParamsOfSubscribeCollection,
@@ -46,6 +58,7 @@ pub use transaction_tree::{
     query_transaction_tree, MessageNode, ParamsOfQueryTransactionTree,
     ResultOfQueryTransactionTree, TransactionNode,
 };
+pub use typed::{query_collection_typed, ParamsOfQueryCollectionTyped, TypedCollection};
 pub use types::{
     NetworkConfig, NetworkQueriesProtocol, ACCOUNTS_COLLECTION, BLOCKS_COLLECTION,
     MESSAGES_COLLECTION, TRANSACTIONS_COLLECTION,
@@ -57,14 +70,18 @@ use crate::error::ClientResult;
 pub(crate) mod batch;
 mod endpoint;
 mod errors;
+pub(crate) mod events;
 mod gql;
 pub(crate) mod iterators;
 pub(crate) mod network_params;
 pub(crate) mod queries;
+mod query_cache;
+pub(crate) mod rate_limiter;
 mod server_link;
 pub(crate) mod subscriptions;
 mod ton_gql;
 pub(crate) mod transaction_tree;
+pub(crate) mod typed;
 pub(crate) mod types;
 mod websocket_link;
 
@@ -94,6 +94,21 @@ pub struct ResultOfQueryCollection {
 pub async fn query_collection(
     context: std::sync::Arc<ClientContext>,
     params: ParamsOfQueryCollection,
+) -> ClientResult<ResultOfQueryCollection> {
+    let server_link = context.get_server_link()?;
+    let result = server_link.query_collection_cached(params).await;
+    Ok(ResultOfQueryCollection {
+        result: deserialize_result(result, server_link).await?,
+    })
+}
+
+/// Queries collection data bypassing the query cache.
+///
+/// Is used by the library itself, so the internal processing (e.g. accounts
+/// fetched to run the executor) always works with the actual data.
+pub(crate) async fn fetch_collection(
+    context: std::sync::Arc<ClientContext>,
+    params: ParamsOfQueryCollection,
 ) -> ClientResult<ResultOfQueryCollection> {
     let server_link = context.get_server_link()?;
     let result = server_link.query_collection(params, None).await;
@@ -0,0 +1,334 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use tokio::sync::{Mutex, RwLock};
+
+use crate::client::storage::{InMemoryKeyValueStorage, KeyValueStorage};
+use crate::client::ClientEnv;
+use crate::error::ClientResult;
+use crate::net::{
+    ParamsOfQueryCollection, ACCOUNTS_COLLECTION, BLOCKS_COLLECTION, MESSAGES_COLLECTION,
+    TRANSACTIONS_COLLECTION,
+};
+
+const STORAGE_NAME: &str = "query_cache";
+const INDEX_KEY: &str = "index";
+
+// Final `status` values of the cached collections
+const BLOCK_FINALIZED: u64 = 2;
+const TRANSACTION_FINALIZED: u64 = 3;
+const MESSAGE_FINALIZED: u64 = 5;
+
+pub fn default_accounts_ttl() -> u32 {
+    5000
+}
+
+pub fn default_max_entries() -> u32 {
+    1000
+}
+
+fn deserialize_accounts_ttl<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or(default_accounts_ttl()))
+}
+
+fn deserialize_max_entries<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or(default_max_entries()))
+}
+
+fn deserialize_cache_in_local_storage<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<bool, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ApiType)]
+pub struct QueryCacheConfig {
+    /// Cache query results in the local storage. Default is `false`.
+    ///
+    /// If this value is `true` then the cached results survive the client restarts
+    /// and are available when the network is not reachable.
+    /// Otherwise results are cached in memory.
+    #[serde(default, deserialize_with = "deserialize_cache_in_local_storage")]
+    pub cache_in_local_storage: bool,
+
+    /// Lifetime of the cached `accounts` query results.
+    ///
+    /// Account state is mutable so its cached results are expired after this
+    /// timeout. `0` disables caching of `accounts` queries.
+    ///
+    /// Must be specified in milliseconds. Default is 5000 (5 sec).
+    #[serde(
+        default = "default_accounts_ttl",
+        deserialize_with = "deserialize_accounts_ttl"
+    )]
+    pub accounts_ttl: u32,
+
+    /// Maximum number of the cached query results.
+    ///
+    /// The oldest results are evicted when the limit is exceeded. Default is 1000.
+    #[serde(
+        default = "default_max_entries",
+        deserialize_with = "deserialize_max_entries"
+    )]
+    pub max_entries: u32,
+}
+
+impl Default for QueryCacheConfig {
+    fn default() -> Self {
+        Self {
+            cache_in_local_storage: false,
+            accounts_ttl: default_accounts_ttl(),
+            max_entries: default_max_entries(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum CacheLifetime {
+    /// Result can't change once all the requested objects are final.
+    Permanent,
+    /// Result expires after the specified number of milliseconds.
+    Ttl(u64),
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    expire_at: Option<u64>,
+    result: Value,
+}
+
+pub(crate) struct CachedQuery {
+    key: String,
+    collection: String,
+    lifetime: CacheLifetime,
+    requested_ids: Option<usize>,
+}
+
+pub(crate) struct QueryCache {
+    config: QueryCacheConfig,
+    env: Arc<ClientEnv>,
+    local_storage_path: Option<String>,
+    storage: RwLock<Option<Arc<dyn KeyValueStorage>>>,
+    /// Cached entry keys in the order of their creation. Loaded from the storage on
+    /// the first write.
+    index: Mutex<Option<VecDeque<String>>>,
+}
+
+/// Returns the number of requested ids if the filter is a lookup by `id` only.
+fn requested_ids_count(filter: &Option<Value>) -> Option<usize> {
+    let filter = filter.as_ref()?.as_object()?;
+    if filter.len() != 1 {
+        return None;
+    }
+    let id_filter = filter.get("id")?.as_object()?;
+    if id_filter.len() != 1 {
+        return None;
+    }
+    if let Some(id) = id_filter.get("eq") {
+        return id.as_str().map(|_| 1);
+    }
+    let ids = id_filter.get("in")?.as_array()?;
+    if ids.iter().all(|x| x.is_string()) {
+        Some(ids.len())
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if the object can't change anymore. Objects without `status`
+/// in the query result are treated as not final.
+fn is_final(collection: &str, object: &Value) -> bool {
+    let final_status = match collection {
+        BLOCKS_COLLECTION => BLOCK_FINALIZED,
+        TRANSACTIONS_COLLECTION => TRANSACTION_FINALIZED,
+        MESSAGES_COLLECTION => MESSAGE_FINALIZED,
+        _ => return false,
+    };
+    if object["status"].as_u64() != Some(final_status) {
+        return false;
+    }
+    // Destination transaction is executed after the message is finalized
+    collection != MESSAGES_COLLECTION
+        || !object
+            .get("dst_transaction")
+            .map(Value::is_null)
+            .unwrap_or_default()
+}
+
+fn normalize_result(result: &str) -> String {
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl QueryCache {
+    pub fn new(
+        config: QueryCacheConfig,
+        env: Arc<ClientEnv>,
+        local_storage_path: Option<String>,
+    ) -> Self {
+        Self {
+            config,
+            env,
+            local_storage_path,
+            storage: Default::default(),
+            index: Default::default(),
+        }
+    }
+
+    /// Returns the cache rule for the query or `None` if the query must not be cached.
+    pub(crate) fn lifetime(&self, params: &ParamsOfQueryCollection) -> Option<CacheLifetime> {
+        match params.collection.as_str() {
+            BLOCKS_COLLECTION | TRANSACTIONS_COLLECTION | MESSAGES_COLLECTION => {
+                requested_ids_count(&params.filter).map(|_| CacheLifetime::Permanent)
+            }
+            ACCOUNTS_COLLECTION if self.config.accounts_ttl > 0 => {
+                Some(CacheLifetime::Ttl(self.config.accounts_ttl as u64))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn prepare(&self, params: &ParamsOfQueryCollection) -> Option<CachedQuery> {
+        let lifetime = self.lifetime(params)?;
+        // `Value` object keys are sorted so the same filters produce the same key
+        let normalized = json!({
+            "collection": params.collection,
+            "filter": params.filter,
+            "result": normalize_result(&params.result),
+            "order": params.order,
+            "limit": params.limit,
+        })
+        .to_string();
+        Some(CachedQuery {
+            key: format!(
+                "query_{}",
+                hex::encode(crate::crypto::internal::sha256(normalized.as_bytes()))
+            ),
+            collection: params.collection.clone(),
+            lifetime,
+            requested_ids: requested_ids_count(&params.filter),
+        })
+    }
+
+    async fn storage(&self) -> ClientResult<Arc<dyn KeyValueStorage>> {
+        if let Some(storage) = self.storage.read().await.as_ref() {
+            return Ok(storage.clone());
+        }
+        let mut write_guard = self.storage.write().await;
+        if let Some(storage) = write_guard.as_ref() {
+            return Ok(storage.clone());
+        }
+        let storage = if self.config.cache_in_local_storage {
+            Arc::new(
+                crate::client::LocalStorage::new(
+                    self.local_storage_path.clone(),
+                    STORAGE_NAME.to_string(),
+                )
+                .await?,
+            ) as Arc<dyn KeyValueStorage>
+        } else {
+            Arc::new(InMemoryKeyValueStorage::new()) as Arc<dyn KeyValueStorage>
+        };
+        *write_guard = Some(storage.clone());
+        Ok(storage)
+    }
+
+    /// Returns cached result. Expired results are returned only if `allow_expired` is `true`.
+    pub(crate) async fn get(
+        &self,
+        query: &CachedQuery,
+        allow_expired: bool,
+    ) -> ClientResult<Option<Value>> {
+        let entry = match self.storage().await?.get_str(&query.key).await? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let entry: CacheEntry = match serde_json::from_str(&entry) {
+            Ok(entry) => entry,
+            Err(err) => {
+                log::warn!("Invalid query cache entry {}: {}", query.key, err);
+                return Ok(None);
+            }
+        };
+        match entry.expire_at {
+            Some(expire_at) if !allow_expired && expire_at <= self.env.now_ms() => Ok(None),
+            _ => Ok(Some(entry.result)),
+        }
+    }
+
+    pub(crate) async fn put(&self, query: &CachedQuery, result: &Value) -> ClientResult<()> {
+        let expire_at = match query.lifetime {
+            CacheLifetime::Permanent => {
+                // Missing objects can appear later and not final objects can change,
+                // so only complete lookups of the final objects are permanent
+                let objects = match result.as_array() {
+                    Some(objects) => objects,
+                    None => return Ok(()),
+                };
+                if Some(objects.len()) != query.requested_ids
+                    || !objects.iter().all(|x| is_final(&query.collection, x))
+                {
+                    return Ok(());
+                }
+                None
+            }
+            CacheLifetime::Ttl(ttl) => Some(self.env.now_ms() + ttl),
+        };
+        let entry = CacheEntry {
+            expire_at,
+            result: result.clone(),
+        };
+        let entry = serde_json::to_string(&entry)
+            .map_err(|err| crate::client::Error::internal_error(err))?;
+        let storage = self.storage().await?;
+        storage.put_str(&query.key, &entry).await?;
+        self.add_to_index(&storage, &query.key).await
+    }
+
+    /// Records the key of the new entry and evicts the oldest entries exceeding
+    /// the `max_entries` limit.
+    async fn add_to_index(
+        &self,
+        storage: &Arc<dyn KeyValueStorage>,
+        key: &str,
+    ) -> ClientResult<()> {
+        let mut index_guard = self.index.lock().await;
+        if index_guard.is_none() {
+            let index = match storage.get_str(INDEX_KEY).await? {
+                Some(index) => serde_json::from_str(&index).unwrap_or_else(|err| {
+                    log::warn!("Invalid query cache index: {}", err);
+                    VecDeque::new()
+                }),
+                None => VecDeque::new(),
+            };
+            *index_guard = Some(index);
+        }
+        let index = index_guard.get_or_insert_with(VecDeque::new);
+        if index.iter().any(|x| x == key) {
+            return Ok(());
+        }
+        index.push_back(key.to_string());
+        while index.len() > self.config.max_entries as usize {
+            if let Some(evicted) = index.pop_front() {
+                storage.remove(&evicted).await?;
+            }
+        }
+        let index = serde_json::to_string(index)
+            .map_err(|err| crate::client::Error::internal_error(err))?;
+        storage.put_str(INDEX_KEY, &index).await
+    }
+}
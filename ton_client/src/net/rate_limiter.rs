@@ -0,0 +1,287 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Deserializer};
+use tokio::sync::Mutex;
+
+use crate::client::{ClientContext, ClientEnv};
+use crate::error::ClientResult;
+
+pub fn default_requests_per_second() -> u32 {
+    50
+}
+
+pub fn default_burst_size() -> u32 {
+    100
+}
+
+pub fn default_retries_per_second() -> u32 {
+    10
+}
+
+fn deserialize_requests_per_second<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u32, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or(default_requests_per_second()))
+}
+
+fn deserialize_burst_size<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or(default_burst_size()))
+}
+
+fn deserialize_retries_per_second<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u32, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or(default_retries_per_second()))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ApiType)]
+pub struct RateLimitConfig {
+    /// Maximum rate of HTTP queries sent to a single endpoint.
+    ///
+    /// Queries exceeding this rate are delayed on the client side.
+    /// `0` disables the client side rate limiting. Default is 50.
+    #[serde(
+        default = "default_requests_per_second",
+        deserialize_with = "deserialize_requests_per_second"
+    )]
+    pub requests_per_second: u32,
+
+    /// Maximum number of HTTP queries that can be sent to a single endpoint
+    /// at once without waiting. Default is 100.
+    #[serde(
+        default = "default_burst_size",
+        deserialize_with = "deserialize_burst_size"
+    )]
+    pub burst_size: u32,
+
+    /// Retry budget shared by all queries.
+    ///
+    /// Maximum rate of retries performed after network errors and
+    /// `429 Too Many Requests` responses. When the budget is exhausted the query
+    /// fails immediately instead of retrying. `0` disables retries. Default is 10.
+    #[serde(
+        default = "default_retries_per_second",
+        deserialize_with = "deserialize_retries_per_second"
+    )]
+    pub retries_per_second: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: default_requests_per_second(),
+            burst_size: default_burst_size(),
+            retries_per_second: default_retries_per_second(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug)]
+pub struct ResultOfGetRateLimitStats {
+    /// Number of HTTP queries sent.
+    pub queries: u64,
+
+    /// Number of HTTP queries delayed by the client side rate limiter.
+    pub throttled_queries: u64,
+
+    /// Total time in milliseconds the queries were delayed by the client side rate limiter.
+    pub throttled_time: u64,
+
+    /// Number of `429 Too Many Requests` responses received from the endpoints.
+    pub rate_limited_responses: u64,
+
+    /// Number of performed query retries.
+    pub retries: u64,
+
+    /// Number of retries rejected because the retry budget was exhausted.
+    pub rejected_retries: u64,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    updated_at: u64,
+    blocked_until: u64,
+}
+
+impl TokenBucket {
+    fn new(capacity: u32, now: u64) -> Self {
+        Self {
+            tokens: capacity as f64,
+            updated_at: now,
+            blocked_until: 0,
+        }
+    }
+
+    fn refill(&mut self, rate: u32, capacity: u32, now: u64) {
+        let elapsed = now.saturating_sub(self.updated_at);
+        self.tokens = (self.tokens + elapsed as f64 * rate as f64 / 1000.0).min(capacity as f64);
+        self.updated_at = now;
+    }
+
+    /// Takes a token if it is available, otherwise returns the time to wait in ms.
+    /// Zero `rate` means that the rate is not limited.
+    fn try_take(&mut self, rate: u32, capacity: u32, now: u64) -> Result<(), u64> {
+        if now < self.blocked_until {
+            return Err(self.blocked_until - now);
+        }
+        if rate == 0 {
+            return Ok(());
+        }
+        self.refill(rate, capacity, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - self.tokens) * 1000.0 / rate as f64).ceil() as u64)
+        }
+    }
+}
+
+#[derive(Default)]
+struct Stats {
+    queries: AtomicU64,
+    throttled_queries: AtomicU64,
+    throttled_time: AtomicU64,
+    rate_limited_responses: AtomicU64,
+    retries: AtomicU64,
+    rejected_retries: AtomicU64,
+}
+
+pub(crate) struct RateLimiter {
+    config: Option<RateLimitConfig>,
+    env: Arc<ClientEnv>,
+    endpoints: Mutex<HashMap<String, TokenBucket>>,
+    retry_budget: Mutex<Option<TokenBucket>>,
+    stats: Stats,
+}
+
+impl RateLimiter {
+    pub fn new(config: Option<RateLimitConfig>, env: Arc<ClientEnv>) -> Self {
+        Self {
+            config,
+            env,
+            endpoints: Default::default(),
+            retry_budget: Default::default(),
+            stats: Default::default(),
+        }
+    }
+
+    /// Returns the endpoint queries rate and the burst size.
+    fn endpoint_limits(&self) -> (u32, u32) {
+        match &self.config {
+            Some(config) => (config.requests_per_second, config.burst_size.max(1)),
+            None => (0, 0),
+        }
+    }
+
+    /// Waits until the query to the endpoint is allowed by the rate limit
+    /// and by the `Retry-After` timeout received from this endpoint.
+    pub async fn acquire(&self, endpoint: &str) {
+        self.stats.queries.fetch_add(1, Ordering::Relaxed);
+        let (rate, capacity) = self.endpoint_limits();
+        let mut throttled = false;
+        loop {
+            let wait = {
+                let now = self.env.now_ms();
+                let mut endpoints = self.endpoints.lock().await;
+                let bucket = endpoints
+                    .entry(endpoint.to_string())
+                    .or_insert_with(|| TokenBucket::new(capacity, now));
+                match bucket.try_take(rate, capacity, now) {
+                    Ok(()) => return,
+                    Err(wait) => wait,
+                }
+            };
+            if !throttled {
+                throttled = true;
+                self.stats.throttled_queries.fetch_add(1, Ordering::Relaxed);
+            }
+            self.stats.throttled_time.fetch_add(wait, Ordering::Relaxed);
+            let _ = self.env.set_timer(wait).await;
+        }
+    }
+
+    /// Suspends queries to the endpoint after the `429 Too Many Requests` response.
+    ///
+    /// The tokens of the endpoint bucket are kept, so the queries are not throttled
+    /// by the rate limit right after the suspension ends.
+    pub async fn block_endpoint(&self, endpoint: &str, timeout: u64) {
+        self.stats
+            .rate_limited_responses
+            .fetch_add(1, Ordering::Relaxed);
+        let (_, capacity) = self.endpoint_limits();
+        let now = self.env.now_ms();
+        let mut endpoints = self.endpoints.lock().await;
+        let bucket = endpoints
+            .entry(endpoint.to_string())
+            .or_insert_with(|| TokenBucket::new(capacity, now));
+        bucket.blocked_until = bucket.blocked_until.max(now + timeout);
+    }
+
+    /// Takes a retry from the global retry budget. Returns `false` if the budget is exhausted.
+    pub async fn try_retry(&self) -> bool {
+        let allowed = match &self.config {
+            None => true,
+            Some(config) if config.retries_per_second == 0 => false,
+            Some(config) => {
+                let now = self.env.now_ms();
+                let mut budget = self.retry_budget.lock().await;
+                budget
+                    .get_or_insert_with(|| TokenBucket::new(config.retries_per_second, now))
+                    .try_take(config.retries_per_second, config.retries_per_second, now)
+                    .is_ok()
+            }
+        };
+        if allowed {
+            self.stats.retries.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.stats.rejected_retries.fetch_add(1, Ordering::Relaxed);
+        }
+        allowed
+    }
+
+    pub fn stats(&self) -> ResultOfGetRateLimitStats {
+        ResultOfGetRateLimitStats {
+            queries: self.stats.queries.load(Ordering::Relaxed),
+            throttled_queries: self.stats.throttled_queries.load(Ordering::Relaxed),
+            throttled_time: self.stats.throttled_time.load(Ordering::Relaxed),
+            rate_limited_responses: self.stats.rate_limited_responses.load(Ordering::Relaxed),
+            retries: self.stats.retries.load(Ordering::Relaxed),
+            rejected_retries: self.stats.rejected_retries.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Returns `Retry-After` header value in ms. Only the delay in seconds form is supported.
+pub(crate) fn retry_after(headers: &HashMap<String, String>) -> Option<u64> {
+    headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("retry-after"))
+        .and_then(|(_, value)| value.trim().parse::<u64>().ok())
+        .map(|secs| secs * 1000)
+}
+
+/// Returns statistics of the HTTP queries rate limiting and retries.
+///
+/// Statistics are collected since the client context creation.
+#[api_function]
+pub async fn get_rate_limit_stats(
+    context: Arc<ClientContext>,
+) -> ClientResult<ResultOfGetRateLimitStats> {
+    Ok(context.get_server_link()?.rate_limit_stats())
+}
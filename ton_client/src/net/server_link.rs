@@ -14,6 +14,8 @@
 use crate::client::{ClientEnv, FetchMethod};
 use crate::error::{AddNetworkUrl, ClientError, ClientResult};
 use crate::net::endpoint::Endpoint;
+use crate::net::query_cache::QueryCache;
+use crate::net::rate_limiter::{retry_after, RateLimiter, ResultOfGetRateLimitStats};
 use crate::net::ton_gql::GraphQLQuery;
 use crate::net::types::NetworkQueriesProtocol;
 use crate::net::websocket_link::WebsocketLink;
@@ -369,6 +371,8 @@ pub(crate) struct ServerLink {
     pub(crate) client_env: Arc<ClientEnv>,
     websocket_link: Arc<WebsocketLink>,
     state: Arc<NetworkState>,
+    query_cache: Option<QueryCache>,
+    rate_limiter: RateLimiter,
 }
 
 fn strip_endpoint(endpoint: &str) -> &str {
@@ -396,7 +400,11 @@ fn replace_endpoints(endpoints: Vec<String>) -> Vec<String> {
 }
 
 impl ServerLink {
-    pub fn new(config: NetworkConfig, client_env: Arc<ClientEnv>) -> ClientResult<Self> {
+    pub fn new(
+        config: NetworkConfig,
+        client_env: Arc<ClientEnv>,
+        local_storage_path: Option<String>,
+    ) -> ClientResult<Self> {
         let endpoint_addresses = config
             .endpoints
             .clone()
@@ -413,10 +421,16 @@ impl ServerLink {
             endpoint_addresses,
         ));
 
+        let query_cache = config.query_cache.clone().map(|cache_config| {
+            QueryCache::new(cache_config, client_env.clone(), local_storage_path)
+        });
+
         Ok(ServerLink {
             config: config.clone(),
             client_env: client_env.clone(),
             state: state.clone(),
+            query_cache,
+            rate_limiter: RateLimiter::new(config.rate_limit.clone(), client_env.clone()),
             websocket_link: Arc::new(WebsocketLink::new(client_env, state, config)),
         })
     }
@@ -433,6 +447,10 @@ impl ServerLink {
         self.state.clone()
     }
 
+    pub fn rate_limit_stats(&self) -> ResultOfGetRateLimitStats {
+        self.rate_limiter.stats()
+    }
+
     // Returns Stream with updates database fields by provided filter
     pub async fn subscribe_collection(
         &self,
@@ -561,6 +579,7 @@ impl ServerLink {
                 current_endpoint = Some(self.state.get_query_endpoint().await?.clone());
                 current_endpoint.as_ref().unwrap()
             };
+            self.rate_limiter.acquire(&endpoint.query_url).await;
             let result = self
                 .client_env
                 .fetch(
@@ -574,6 +593,16 @@ impl ServerLink {
 
             let result = match result {
                 Err(err) => Err(err),
+                Ok(response) if response.status == 429 => {
+                    // The endpoint is overloaded, so the resume timeout keeps growing
+                    // for the responses without `Retry-After`
+                    let retry_after = retry_after(&response.headers);
+                    let backoff = max(self.state.next_resume_timeout(), MIN_RESUME_TIMEOUT);
+                    self.rate_limiter
+                        .block_endpoint(&endpoint.query_url, retry_after.unwrap_or(backoff as u64))
+                        .await;
+                    Err(Error::too_many_requests(&response, retry_after))
+                }
                 Ok(response) => {
                     self.state.reset_resume_timeout();
                     if response.status == 401 {
@@ -591,14 +620,23 @@ impl ServerLink {
             };
 
             if let Err(err) = &result {
-                if crate::client::Error::is_network_error(err) {
+                if err.code == ErrorCode::TooManyRequests as u32 {
+                    // Next `acquire` waits for the `Retry-After` timeout
+                    if self.state.can_retry_network_error(start)
+                        && self.rate_limiter.try_retry().await
+                    {
+                        continue;
+                    }
+                } else if crate::client::Error::is_network_error(err) {
                     let multiple_endpoints = self.state.has_multiple_endpoints();
                     if multiple_endpoints {
                         self.state.internal_suspend().await;
                         self.websocket_link.suspend().await;
                         self.websocket_link.resume().await;
                     }
-                    if self.state.can_retry_network_error(start) {
+                    if self.state.can_retry_network_error(start)
+                        && self.rate_limiter.try_retry().await
+                    {
                         if !multiple_endpoints {
                             let _ = self
                                 .client_env
@@ -710,6 +748,46 @@ impl ServerLink {
             .remove(0))
     }
 
+    /// Queries collection using the query cache if it is configured.
+    ///
+    /// Is used only for the queries requested by the application with `net.query_collection`,
+    /// the library's own queries always fetch the actual data.
+    pub(crate) async fn query_collection_cached(
+        &self,
+        params: ParamsOfQueryCollection,
+    ) -> ClientResult<Value> {
+        let (cache, cached_query) = match &self.query_cache {
+            Some(cache) => match cache.prepare(&params) {
+                Some(cached_query) => (cache, cached_query),
+                None => return self.query_collection(params, None).await,
+            },
+            None => return self.query_collection(params, None).await,
+        };
+
+        match cache.get(&cached_query, false).await {
+            Ok(Some(result)) => return Ok(result),
+            Ok(None) => {}
+            Err(err) => log::warn!("Query cache read failed: {}", err),
+        }
+
+        match self.query_collection(params, None).await {
+            Ok(result) => {
+                if let Err(err) = cache.put(&cached_query, &result).await {
+                    log::warn!("Query cache write failed: {}", err);
+                }
+                Ok(result)
+            }
+            Err(err) if crate::client::Error::is_network_error(&err) => {
+                // Network is unreachable so the expired result is better than nothing
+                match cache.get(&cached_query, true).await {
+                    Ok(Some(result)) => Ok(result),
+                    _ => Err(err),
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     pub async fn wait_for_collection(
         &self,
         params: ParamsOfWaitForCollection,
@@ -748,6 +826,34 @@ impl ServerLink {
             .remove(0))
     }
 
+    // Checks the GraphQL schema of the endpoint for the specified subscription.
+    // Endpoints with disabled schema introspection are treated as not supporting it.
+    pub async fn is_subscription_supported(
+        &self,
+        name: &str,
+        endpoint: &Endpoint,
+    ) -> ClientResult<bool> {
+        let query = GraphQLQuery {
+            query: r#"query { __type(name: "Subscription") { fields { name } } }"#.to_string(),
+            variables: None,
+            is_batch: false,
+            timeout: None,
+        };
+        match self.query(&query, Some(endpoint)).await {
+            Ok(result) => Ok(result["data"]["__type"]["fields"]
+                .as_array()
+                .map(|fields| fields.iter().any(|field| field["name"] == name))
+                .unwrap_or_default()),
+            Err(err)
+                if err.code == ErrorCode::GraphqlError as u32
+                    && !crate::client::Error::is_network_error(&err) =>
+            {
+                Ok(false)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     // Sends message to node
     pub async fn send_message(
         &self,
@@ -329,6 +329,59 @@ async fn all_accounts() {
     assert!(accounts.result.len() > 0);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn typed_collections() {
+    let client = TestClient::new();
+    let context = client.context();
+
+    let transactions: Vec<ton_sdk::Transaction> = query_collection_typed(
+        context.clone(),
+        ParamsOfQueryCollectionTyped {
+            filter: Some(json!({ "in_msg": { "ne": null } })),
+            limit: Some(3),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert!(transactions.len() > 0);
+
+    let in_msg = transactions[0].in_msg.clone().unwrap();
+    let messages: Vec<ton_sdk::Message> = query_collection_typed(
+        context.clone(),
+        ParamsOfQueryCollectionTyped {
+            filter: Some(json!({ "id": { "eq": in_msg.to_string() } })),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].id, in_msg);
+
+    let blocks: Vec<ton_sdk::Block> = query_collection_typed(
+        context.clone(),
+        ParamsOfQueryCollectionTyped {
+            limit: Some(1),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(blocks.len(), 1);
+
+    let accounts: Vec<ton_sdk::Account> = query_collection_typed(
+        context,
+        ParamsOfQueryCollectionTyped {
+            limit: Some(1),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(accounts.len(), 1);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn aggregates() {
     let client = TestClient::new();
@@ -605,6 +658,8 @@ async fn subscribe_for_transactions_with_addresses() {
             ParamsOfProcessMessage {
                 message_encode_params: deploy_params,
                 send_events: false,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             TestClient::default_callback,
         )
@@ -643,6 +698,8 @@ async fn subscribe_for_transactions_with_addresses() {
                     ..Default::default()
                 },
                 send_events: false,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             TestClient::default_callback,
         )
@@ -858,6 +915,158 @@ async fn query_block_id(client: &Arc<ClientContext>) -> ClientResult<String> {
     )
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn query_cache() {
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                query_cache: Some(QueryCacheConfig::default()),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+
+    let block = |id: &str, status: u32| {
+        json!({ "data": { "blocks": [{ "id": id, "status": status }] } }).to_string()
+    };
+    let now = client.env.now_ms();
+    NetworkMock::build()
+        .url("a")
+        .info(now, 1000)
+        .ok(&block("1", 2))
+        .ok(&block("2", 1))
+        .ok(&block("2", 2))
+        .ok(&block("3", 2))
+        .reset_client(&client)
+        .await;
+
+    let query_block = |id: &str| {
+        crate::net::query_collection(
+            client.clone(),
+            ParamsOfQueryCollection {
+                collection: "blocks".to_string(),
+                filter: Some(json!({ "id": { "eq": id } })),
+                result: "id status".to_string(),
+                ..Default::default()
+            },
+        )
+    };
+
+    // Second query of the finalized block must be served from the cache,
+    // so the next mocked response is left for the query with the other id
+    assert_eq!(query_block("1").await.unwrap().result[0]["id"], "1");
+    assert_eq!(query_block("1").await.unwrap().result[0]["id"], "1");
+
+    // Proposed block can change, so it is requested again
+    assert_eq!(query_block("2").await.unwrap().result[0]["status"], 1);
+    assert_eq!(query_block("2").await.unwrap().result[0]["status"], 2);
+    assert_eq!(query_block("3").await.unwrap().result[0]["id"], "3");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn query_cache_is_bypassed_by_internal_queries() {
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                query_cache: Some(QueryCacheConfig::default()),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+
+    let account = |balance: &str| {
+        json!({ "data": { "accounts": [{ "id": "0:1", "balance": balance }] } }).to_string()
+    };
+    let now = client.env.now_ms();
+    NetworkMock::build()
+        .url("a")
+        .info(now, 1000)
+        .ok(&account("1"))
+        .ok(&account("2"))
+        .reset_client(&client)
+        .await;
+
+    let params = ParamsOfQueryCollection {
+        collection: "accounts".to_string(),
+        filter: Some(json!({ "id": { "eq": "0:1" } })),
+        result: "id balance".to_string(),
+        ..Default::default()
+    };
+
+    // Application query caches the account
+    let result = crate::net::query_collection(client.clone(), params.clone())
+        .await
+        .unwrap();
+    assert_eq!(result.result[0]["balance"], "1");
+
+    // Library's own query fetches the actual account
+    let result = crate::net::fetch_collection(client.clone(), params.clone())
+        .await
+        .unwrap();
+    assert_eq!(result.result[0]["balance"], "2");
+
+    let result = crate::net::query_collection(client.clone(), params)
+        .await
+        .unwrap();
+    assert_eq!(result.result[0]["balance"], "1");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn query_cache_max_entries() {
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                query_cache: Some(QueryCacheConfig {
+                    max_entries: 1,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+
+    let block = |id: &str| {
+        json!({ "data": { "blocks": [{ "id": id, "status": 2 }] } }).to_string()
+    };
+    let now = client.env.now_ms();
+    NetworkMock::build()
+        .url("a")
+        .info(now, 1000)
+        .ok(&block("1"))
+        .ok(&block("2"))
+        .ok(&block("1"))
+        .reset_client(&client)
+        .await;
+
+    let query_block = |id: &str| {
+        crate::net::query_collection(
+            client.clone(),
+            ParamsOfQueryCollection {
+                collection: "blocks".to_string(),
+                filter: Some(json!({ "id": { "eq": id } })),
+                result: "id status".to_string(),
+                ..Default::default()
+            },
+        )
+    };
+
+    // The first block is evicted by the second one, so it is requested again
+    // and then served from the cache
+    assert_eq!(query_block("1").await.unwrap().result[0]["id"], "1");
+    assert_eq!(query_block("2").await.unwrap().result[0]["id"], "2");
+    assert_eq!(query_block("1").await.unwrap().result[0]["id"], "1");
+    assert_eq!(query_block("1").await.unwrap().result[0]["id"], "1");
+}
+
 async fn get_query_url(client: &Arc<ClientContext>) -> String {
     let mut url = client
         .get_server_link()
@@ -939,6 +1148,119 @@ async fn retry_query_on_network_errors() {
     assert_eq!(query_block_id(&client).await.unwrap(), "4");
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn retry_query_on_too_many_requests() {
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                rate_limit: Some(RateLimitConfig {
+                    retries_per_second: 1,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+
+    let now = client.env.now_ms();
+    NetworkMock::build()
+        .url("a")
+        .info(now, 1000)
+        .status(429, "")
+        .blocks("1")
+        .status(429, "")
+        .reset_client(&client)
+        .await;
+    assert_eq!(query_block_id(&client).await.unwrap(), "1");
+
+    // Retry budget is exhausted by the previous query
+    let err = query_block_id(&client).await.unwrap_err();
+    assert_eq!(err.code, ErrorCode::TooManyRequests as u32);
+
+    let stats = get_rate_limit_stats(client.clone()).await.unwrap();
+    assert_eq!(stats.rate_limited_responses, 2);
+    assert_eq!(stats.retries, 1);
+    assert_eq!(stats.rejected_retries, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn too_many_requests_without_retry_after() {
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+
+    let now = client.env.now_ms();
+    NetworkMock::build()
+        .url("a")
+        .info(now, 1000)
+        .status(429, "")
+        .status(429, "")
+        .blocks("1")
+        .reset_client(&client)
+        .await;
+    let start = client.env.now_ms();
+    assert_eq!(query_block_id(&client).await.unwrap(), "1");
+    // Endpoint is blocked with the growing resume timeout: 500 ms and then 500 ms again
+    assert!(client.env.now_ms() - start >= 1000);
+
+    let stats = get_rate_limit_stats(client.clone()).await.unwrap();
+    assert_eq!(stats.rate_limited_responses, 2);
+    assert_eq!(stats.retries, 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn retry_after_blocks_endpoint() {
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                rate_limit: Some(RateLimitConfig {
+                    requests_per_second: 1,
+                    burst_size: 10,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+
+    let now = client.env.now_ms();
+    NetworkMock::build()
+        .url("a")
+        .info(now, 1000)
+        .status_with_headers(429, "", &[("Retry-After", "1")])
+        .blocks("1")
+        .blocks("2")
+        .blocks("3")
+        .reset_client(&client)
+        .await;
+    let start = client.env.now_ms();
+    assert_eq!(query_block_id(&client).await.unwrap(), "1");
+    assert!(client.env.now_ms() - start >= 1000);
+
+    // Burst is available right after the blocking interval
+    assert_eq!(query_block_id(&client).await.unwrap(), "2");
+    assert_eq!(query_block_id(&client).await.unwrap(), "3");
+
+    let stats = get_rate_limit_stats(client.clone()).await.unwrap();
+    assert_eq!(stats.rate_limited_responses, 1);
+    assert_eq!(stats.retries, 1);
+    assert_eq!(stats.throttled_queries, 1);
+    assert!(stats.throttled_time > 900 && stats.throttled_time <= 1000);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn retry_query_on_network_errors_ws() {
     TestClient::init_log();
@@ -1686,3 +2008,128 @@ async fn return_network_error_on_subscribe() {
 
     assert!(result.is_err());
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn subscribe_events() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(crate::tests::EVENTS, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let address = client
+        .deploy_with_giver_async(
+            ParamsOfEncodeMessage {
+                abi: abi.clone(),
+                deploy_set: DeploySet::some_with_tvc(tvc),
+                call_set: CallSet::some_with_function("constructor"),
+                signer: Signer::Keys { keys: keys.clone() },
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+
+    let start_time = (client.context().env.now_ms() / 1000) as u32 - 1;
+    client
+        .net_process_function(
+            address.clone(),
+            abi.clone(),
+            "emitValue",
+            json!({ "id": "0x1" }),
+            Signer::Keys { keys: keys.clone() },
+        )
+        .await
+        .unwrap();
+
+    let events = Arc::new(Mutex::new(Vec::<DecodedEvent>::new()));
+    let events_copy = events.clone();
+    let callback = move |result: serde_json::Value, response_type: SubscriptionResponseType| {
+        let event = match response_type {
+            SubscriptionResponseType::Ok => serde_json::from_value::<DecodedEvent>(result).unwrap(),
+            SubscriptionResponseType::Error => {
+                panic!("{}", serde_json::from_value::<ClientError>(result).unwrap())
+            }
+        };
+        let events_copy = events_copy.clone();
+        async move {
+            events_copy.lock().await.push(event);
+        }
+    };
+
+    let handle: ResultOfSubscribeCollection = client
+        .request_async_callback(
+            "net.subscribe_events",
+            ParamsOfSubscribeEvents {
+                addresses: vec![address.clone()],
+                abi: abi.clone(),
+                event_names: Some(vec!["EventThrown".to_string()]),
+                start_time: Some(start_time),
+            },
+            callback,
+        )
+        .await
+        .unwrap();
+
+    client
+        .net_process_function(
+            address.clone(),
+            abi,
+            "emitValue",
+            json!({ "id": "0x2" }),
+            Signer::Keys { keys },
+        )
+        .await
+        .unwrap();
+
+    // wait for the live event to arrive
+    for _ in 0..20 {
+        if events.lock().await.len() >= 2 {
+            break;
+        }
+        client.context().env.set_timer(500).await.unwrap();
+    }
+
+    let _: () = client
+        .request_async("net.unsubscribe", handle)
+        .await
+        .unwrap();
+
+    let events = events.lock().await;
+    assert_eq!(events.len(), 2);
+    for event in events.iter() {
+        assert_eq!(event.address, address);
+        assert_eq!(event.name, "EventThrown");
+        assert!(event.block_id.is_some());
+    }
+    assert!(events[0].transaction_id.is_some());
+    assert_ne!(
+        events[0].value.as_ref().unwrap()["id"],
+        events[1].value.as_ref().unwrap()["id"]
+    );
+    assert_ne!(events[0].message_id, events[1].message_id);
+}
+
+#[test]
+fn events_history_page_filter() {
+    let history_filter = json!({
+        "src": { "in": ["0:1"] },
+        "created_at": { "ge": 1 },
+    });
+    let filter = super::events::next_history_page_filter(
+        &history_filter,
+        &json!({ "id": "m2", "created_lt": "0x10" }),
+    );
+    assert_eq!(
+        filter,
+        json!({
+            "src": { "in": ["0:1"] },
+            "created_at": { "ge": 1 },
+            "created_lt": { "gt": "0x10" },
+            "OR": {
+                "src": { "in": ["0:1"] },
+                "created_at": { "ge": 1 },
+                "created_lt": { "eq": "0x10" },
+                "id": { "gt": "m2" },
+            },
+        })
+    );
+}
@@ -14,16 +14,17 @@
 use serde_json::Value;
 
 use crate::client::ClientContext;
-use crate::error::ClientResult;
-use crate::net::{ParamsOfQueryCollection, ServerLink, MESSAGES_COLLECTION};
+use crate::error::{ClientError, ClientResult};
+use crate::net::{ErrorCode, ParamsOfQueryCollection, ServerLink, MESSAGES_COLLECTION};
 
+use crate::abi::code_registry::CodeAbiResolver;
 use crate::abi::{decode_message_body, Abi, DecodedMessageBody, ParamsOfDecodeMessageBody};
 use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 use std::sync::Arc;
 
-const DEFAULT_WAITING_TIMEOUT: u32 = 60000;
-const DEFAULT_TRANSACTION_MAX_COUNT: u32 = 50;
+pub(crate) const DEFAULT_WAITING_TIMEOUT: u32 = 60000;
+pub(crate) const DEFAULT_TRANSACTION_MAX_COUNT: u32 = 50;
 
 fn get_string(v: &Value, name: &str) -> Option<String> {
     v[name].as_str().map(|x| x.to_string())
@@ -44,6 +45,8 @@ pub struct ParamsOfQueryTransactionTree {
 
     /// List of contract ABIs that will be used to decode message bodies.
     /// Library will try to decode each returned message body using any ABI from the registry.
+    /// Bodies which are not decoded with these ABIs are decoded with the ABIs registered
+    /// by `abi.register_code_abi`.
     pub abi_registry: Option<Vec<Abi>>,
 
     /// Timeout used to limit waiting time for the missing messages and transaction.
@@ -64,7 +67,7 @@ pub struct ParamsOfQueryTransactionTree {
     pub transaction_max_count: Option<u32>,
 }
 
-#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug)]
+#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug, PartialEq)]
 pub struct MessageNode {
     /// Message id.
     pub id: String,
@@ -91,18 +94,22 @@ pub struct MessageNode {
     /// Bounce flag.
     pub bounce: bool,
 
+    /// Bounced flag. Set for the messages produced by the bounce of the failed transaction.
+    #[serde(default)]
+    pub bounced: bool,
+
     /// Decoded body.
     ///
-    /// Library tries to decode message body using provided `params.abi_registry`.
-    /// This field will be missing if none of the provided abi can be used to decode.
+    /// Library tries to decode message body using provided `params.abi_registry`
+    /// and then the ABI registered for the message account by `abi.register_code_abi`.
+    /// This field will be missing if none of the abi can be used to decode.
     pub decoded_body: Option<DecodedMessageBody>,
 }
 
 impl MessageNode {
     fn from(
         value: &Value,
-        client: &Arc<ClientContext>,
-        abi_registry: &Option<Vec<Abi>>,
+        decoded_body: Option<DecodedMessageBody>,
         src_transactions: &HashMap<String, Option<String>>,
     ) -> ClientResult<Self> {
         let id = required_string(value, "id")?;
@@ -115,11 +122,38 @@ impl MessageNode {
             dst: get_string(value, "dst"),
             value: get_string(value, "value"),
             bounce: value["bounce"].as_bool().unwrap_or(false),
-            decoded_body: Self::try_decode_body(value, client, abi_registry),
+            bounced: value["bounced"].as_bool().unwrap_or(false),
+            decoded_body,
         })
     }
 
-    fn try_decode_body(
+    /// Decodes the bodies with the `abi_registry` ABIs and then with the ABIs registered
+    /// for the message accounts.
+    pub(crate) async fn decode_bodies(
+        messages: &[&Value],
+        client: &Arc<ClientContext>,
+        abi_registry: &Option<Vec<Abi>>,
+        code_abis: &mut CodeAbiResolver,
+    ) -> Vec<Option<DecodedMessageBody>> {
+        let decoded = messages
+            .iter()
+            .map(|message| Self::try_decode_body(message, client, abi_registry))
+            .collect::<Vec<_>>();
+        let undecoded = messages
+            .iter()
+            .zip(&decoded)
+            .filter(|(_, decoded)| decoded.is_none())
+            .map(|(message, _)| *message)
+            .collect::<Vec<_>>();
+        code_abis.resolve(client, &undecoded).await;
+        messages
+            .iter()
+            .zip(decoded)
+            .map(|(message, decoded)| decoded.or_else(|| code_abis.decode_body(client, message)))
+            .collect()
+    }
+
+    pub(crate) fn try_decode_body(
         message: &Value,
         client: &Arc<ClientContext>,
         abi_registry: &Option<Vec<Abi>>,
@@ -148,7 +182,7 @@ impl MessageNode {
     }
 }
 
-#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug)]
+#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug, PartialEq)]
 pub struct TransactionNode {
     /// Transaction id.
     pub id: String,
@@ -219,7 +253,7 @@ async fn query_next_portion(
         src_transactions.insert(msg, tr);
     }
     let mut result_fields = r#"
-        id src dst msg_type value bounce body
+        id src dst msg_type value bounce bounced body
         dst_transaction {
             id aborted compute { exit_code } total_fees out_msgs
         }"#
@@ -309,10 +343,24 @@ pub async fn query_transaction_tree(
     context: Arc<ClientContext>,
     params: ParamsOfQueryTransactionTree,
 ) -> ClientResult<ResultOfQueryTransactionTree> {
+    match query_transaction_tree_partial(context, params).await? {
+        (_, Some(timeout_error)) => Err(timeout_error),
+        (tree, None) => Ok(tree),
+    }
+}
+
+/// Retrieves the tree like `query_transaction_tree`, but if some messages have not appeared
+/// during the timeout, returns the tree retrieved so far together with the timeout error.
+pub(crate) async fn query_transaction_tree_partial(
+    context: Arc<ClientContext>,
+    params: ParamsOfQueryTransactionTree,
+) -> ClientResult<(ResultOfQueryTransactionTree, Option<ClientError>)> {
     let server_link = context.get_server_link()?;
     let mut transaction_nodes = Vec::new();
     let mut message_nodes = Vec::new();
     let mut query_queue: Vec<(Option<String>, String)> = vec![(None, params.in_msg.clone())];
+    let mut timeout_error = None;
+    let mut code_abis = CodeAbiResolver::default();
     let timeout = params.timeout.unwrap_or(DEFAULT_WAITING_TIMEOUT);
     let transaction_max_count = params
         .transaction_max_count
@@ -321,10 +369,23 @@ pub async fn query_transaction_tree(
         && (transaction_max_count == 0 || transaction_nodes.len() < transaction_max_count)
     {
         let (messages, src_transactions) =
-            query_next_portion(server_link, timeout, &mut query_queue).await?;
-        for message in messages {
-            let message_node =
-                MessageNode::from(&message, &context, &params.abi_registry, &src_transactions)?;
+            match query_next_portion(server_link, timeout, &mut query_queue).await {
+                Ok(portion) => portion,
+                Err(err) if err.code == ErrorCode::QueryTransactionTreeTimeout as u32 => {
+                    timeout_error = Some(err);
+                    break;
+                }
+                Err(err) => return Err(err),
+            };
+        let decoded_bodies = MessageNode::decode_bodies(
+            &messages.iter().collect::<Vec<_>>(),
+            &context,
+            &params.abi_registry,
+            &mut code_abis,
+        )
+        .await;
+        for (message, decoded_body) in messages.into_iter().zip(decoded_bodies) {
+            let message_node = MessageNode::from(&message, decoded_body, &src_transactions)?;
             let transaction = &message["dst_transaction"];
             if transaction.is_object() {
                 let transaction_node = TransactionNode::from(&transaction, &message_node)?;
@@ -336,8 +397,11 @@ pub async fn query_transaction_tree(
             message_nodes.push(message_node);
         }
     }
-    Ok(ResultOfQueryTransactionTree {
-        transactions: transaction_nodes,
-        messages: message_nodes,
-    })
+    Ok((
+        ResultOfQueryTransactionTree {
+            transactions: transaction_nodes,
+            messages: message_nodes,
+        },
+        timeout_error,
+    ))
 }
@@ -0,0 +1,148 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use crate::net::{
+    Error, OrderBy, ParamsOfQueryCollection, ACCOUNTS_COLLECTION, BLOCKS_COLLECTION,
+    MESSAGES_COLLECTION, TRANSACTIONS_COLLECTION,
+};
+
+/// Rust type that can be deserialized from the GraphQL collection query result.
+pub trait TypedCollection: DeserializeOwned {
+    /// Name of the collection to query.
+    const COLLECTION: &'static str;
+
+    /// Projection of the collection fields required to deserialize this type.
+    const RESULT: &'static str;
+}
+
+impl TypedCollection for ton_sdk::Block {
+    const COLLECTION: &'static str = BLOCKS_COLLECTION;
+    const RESULT: &'static str = r#"
+        id
+        gen_utime
+        after_split
+        workchain_id
+        shard
+        in_msg_descr {
+            msg_id
+            transaction_id
+        }
+    "#;
+}
+
+impl TypedCollection for ton_sdk::Message {
+    const COLLECTION: &'static str = MESSAGES_COLLECTION;
+    const RESULT: &'static str = r#"
+        id
+        body
+        msg_type
+        value
+    "#;
+}
+
+impl TypedCollection for ton_sdk::Transaction {
+    const COLLECTION: &'static str = TRANSACTIONS_COLLECTION;
+    const RESULT: &'static str = r#"
+        id
+        status
+        now
+        in_msg
+        out_msgs
+        out_messages {
+            id
+            body
+            msg_type
+            value
+        }
+        aborted
+        compute {
+            skipped_reason
+            exit_code
+            exit_arg
+            success
+            gas_fees
+            gas_used
+        }
+        storage {
+            status_change
+            storage_fees_collected
+        }
+        action {
+            success
+            valid
+            no_funds
+            result_code
+            total_fwd_fees
+            total_action_fees
+        }
+        total_fees
+    "#;
+}
+
+impl TypedCollection for ton_sdk::Account {
+    const COLLECTION: &'static str = ACCOUNTS_COLLECTION;
+    const RESULT: &'static str = r#"
+        id
+        acc_type
+        balance
+        last_paid
+        code_hash
+        data_hash
+        boc
+    "#;
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct ParamsOfQueryCollectionTyped {
+    /// Collection filter
+    pub filter: Option<Value>,
+    /// Sorting order
+    pub order: Option<Vec<OrderBy>>,
+    /// Number of documents to return
+    pub limit: Option<u32>,
+}
+
+pub(crate) fn deserialize_typed<T: TypedCollection>(value: Value) -> ClientResult<Vec<T>> {
+    serde_json::from_value(value).map_err(|err| {
+        Error::invalid_server_response(format!("Can not parse {}: {}", T::COLLECTION, err))
+    })
+}
+
+/// Queries collection and deserializes the result into the typed Rust structures.
+///
+/// Collection name and the `result` projection are taken from the `TypedCollection`
+/// implementation, so the caller specifies only the filter, order and limit.
+pub async fn query_collection_typed<T: TypedCollection>(
+    context: Arc<ClientContext>,
+    params: ParamsOfQueryCollectionTyped,
+) -> ClientResult<Vec<T>> {
+    let result = crate::net::query_collection(
+        context,
+        ParamsOfQueryCollection {
+            collection: T::COLLECTION.to_string(),
+            filter: params.filter,
+            result: T::RESULT.to_string(),
+            order: params.order,
+            limit: params.limit,
+        },
+    )
+    .await?;
+    deserialize_typed(Value::Array(result.result))
+}
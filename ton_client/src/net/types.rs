@@ -11,6 +11,7 @@
 * limitations under the License.
 */
 
+use crate::net::{QueryCacheConfig, RateLimitConfig};
 use serde::{Deserialize, Deserializer};
 
 pub const MESSAGES_COLLECTION: &str = "messages";
@@ -30,6 +31,18 @@ pub fn default_message_processing_timeout() -> u32 {
     40000
 }
 
+fn deserialize_message_outbox<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<bool, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+fn deserialize_persistent_message_monitor<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<bool, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
 pub fn default_wait_for_timeout() -> u32 {
     40000
 }
@@ -221,6 +234,31 @@ pub struct NetworkConfig {
     )]
     pub message_processing_timeout: u32,
 
+    /// Store sent messages in the persistent outbox until their processing is finished.
+    ///
+    /// If this value is `true` then `send_message` and `send_messages` save the message
+    /// BOC, expiration time, shard block id and user data into the local storage
+    /// before sending. After the restart the application can finish processing of these
+    /// messages with `processing.resume_outbox`.
+    ///
+    /// Default is `false`.
+    #[serde(default, deserialize_with = "deserialize_message_outbox")]
+    pub message_outbox: bool,
+
+    /// Persist message monitor queues in the local storage.
+    ///
+    /// If this value is `true` then the message monitor saves its queues after each change
+    /// and restores them on the client creation, so `fetch_next_monitor_results` continues
+    /// to deliver results for the messages registered with `monitor_messages`
+    /// before the restart.
+    ///
+    /// Resending of the expired messages is not persisted, so `send_messages` rejects
+    /// the messages with `encode_params` when the monitor queue is specified.
+    ///
+    /// Default is `false`.
+    #[serde(default, deserialize_with = "deserialize_persistent_message_monitor")]
+    pub persistent_message_monitor: bool,
+
     /// Maximum timeout that is used for query response.
     ///
     /// Must be specified in milliseconds. Default is 40000 (40 sec).
@@ -319,6 +357,29 @@ pub struct NetworkConfig {
 
     /// Access key to GraphQL API (Project secret)
     pub access_key: Option<String>,
+
+    /// Query result cache configuration.
+    ///
+    /// If this parameter is specified then `net.query_collection` results which
+    /// can be safely reused are cached: finalized blocks, transactions and messages
+    /// requested by `id` (the query `result` must include `status`) and accounts
+    /// for the configured lifetime.
+    /// Cached results are also returned when the network is not reachable.
+    /// Queries performed by the library itself (processing, iterators, proofs, etc.)
+    /// are not cached.
+    ///
+    /// Default is `None` (caching is disabled).
+    pub query_cache: Option<QueryCacheConfig>,
+
+    /// HTTP queries rate limiting configuration.
+    ///
+    /// If this parameter is specified then the library limits the rate of HTTP queries
+    /// sent to each endpoint and the rate of query retries.
+    /// `Retry-After` header of the `429 Too Many Requests` responses is honoured
+    /// regardless of this parameter.
+    ///
+    /// Default is `None` (rate limiting is disabled).
+    pub rate_limit: Option<RateLimitConfig>,
 }
 
 impl NetworkConfig {
@@ -347,6 +408,8 @@ impl Default for NetworkConfig {
             reconnect_timeout: default_reconnect_timeout(),
             message_retries_count: default_message_retries_count(),
             message_processing_timeout: default_message_processing_timeout(),
+            message_outbox: false,
+            persistent_message_monitor: false,
             wait_for_timeout: default_wait_for_timeout(),
             out_of_sync_threshold: None,
             sending_endpoint_count: default_sending_endpoint_count(),
@@ -358,6 +421,8 @@ impl Default for NetworkConfig {
             next_remp_status_timeout: default_next_remp_status_timeout(),
             signature_id: None,
             access_key: None,
+            query_cache: None,
+            rate_limit: None,
         }
     }
 }
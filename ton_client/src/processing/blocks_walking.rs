@@ -14,30 +14,27 @@
 use super::Error;
 use crate::client::ClientContext;
 use crate::error::ClientResult;
-use crate::net::{OrderBy, ParamsOfQueryCollection, ParamsOfWaitForCollection, SortDirection, BLOCKS_COLLECTION, Endpoint};
+use crate::net::{OrderBy, ParamsOfQueryCollection, ParamsOfWaitForCollection, SortDirection, TypedCollection, BLOCKS_COLLECTION, Endpoint, ServerLink};
 use std::sync::Arc;
 use ton_block::MsgAddressInt;
 use ton_block::MASTERCHAIN_ID;
 
-pub const BLOCK_FIELDS: &str = r#"
-    id
-    gen_utime
-    after_split
-    workchain_id
-    shard
-    in_msg_descr {
-        msg_id
-        transaction_id
-    }
-"#;
+pub const BLOCK_FIELDS: &str = <ton_sdk::Block as TypedCollection>::RESULT;
 
 pub(crate) async fn find_last_shard_block(
     context: &Arc<ClientContext>,
     address: &MsgAddressInt,
     endpoint: Option<Endpoint>,
+) -> ClientResult<ton_sdk::BlockId> {
+    find_last_shard_block_with_link(context.get_server_link()?, address, endpoint).await
+}
+
+pub(crate) async fn find_last_shard_block_with_link(
+    server_link: &ServerLink,
+    address: &MsgAddressInt,
+    endpoint: Option<Endpoint>,
 ) -> ClientResult<ton_sdk::BlockId> {
     let workchain = address.get_workchain_id();
-    let server_link = context.get_server_link()?;
 
     // if account resides in masterchain, then starting point is last masterchain block
     // generated before message was sent
@@ -151,53 +148,90 @@ pub async fn wait_next_block(
     address: &MsgAddressInt,
     timeout: Option<u32>,
 ) -> ClientResult<ton_sdk::Block> {
-    let client = context.get_server_link()?;
+    wait_next_block_with_link(context.get_server_link()?, current, address, timeout).await
+}
 
+/// Waits for the next block of the account shard.
+///
+/// Follows shard splits and merges: after the split the child block which contains
+/// the account is selected, after the merge the merged block is returned.
+/// If the next block doesn't contain the account and there is no sibling block created
+/// by the same split (the account shard chain is lost), the `AccountShardLost` error is
+/// returned so the caller can re-route the walking.
+pub(crate) async fn wait_next_block_with_link(
+    client: &ServerLink,
+    current: &str,
+    address: &MsgAddressInt,
+    timeout: Option<u32>,
+) -> ClientResult<ton_sdk::Block> {
     let block = client
         .wait_for_collection(ParamsOfWaitForCollection {
             collection: BLOCKS_COLLECTION.to_string(),
-            filter: Some(json!({
-                "prev_ref": {
-                    "root_hash": { "eq": current.to_string() }
-                },
-                "OR": {
-                    "prev_alt_ref": {
-                        "root_hash": { "eq": current.to_string() }
-                    }
-                }
-            })),
+            filter: Some(next_block_filter(current, None)),
             result: BLOCK_FIELDS.to_string(),
             timeout,
         }, None)
         .await?;
     debug!(
         "{}: block received {:#}",
-        context.env.now_ms() / 1000,
+        client.state().env().now_ms() / 1000,
         block
     );
 
-    if block["after_split"] == true && !check_shard_match(block.clone(), address)? {
-        client
-            .wait_for_collection(ParamsOfWaitForCollection {
-                collection: BLOCKS_COLLECTION.to_string(),
-                filter: Some(json!({
-                    "id": { "ne": block["id"]},
-                    "prev_ref": {
-                        "root_hash": { "eq": current.to_string() }
-                    }
-                })),
-                result: BLOCK_FIELDS.to_string(),
-                timeout,
-            }, None)
-            .await
-            .and_then(|val| {
-                serde_json::from_value(val)
-                    .map_err(|err| Error::invalid_data(format!("Can not parse block: {}", err)))
-            })
-    } else {
-        serde_json::from_value(block)
-            .map_err(|err| Error::invalid_data(format!("Can not parse block: {}", err)))
+    if check_shard_match(block.clone(), address)? {
+        return parse_block(block);
+    }
+
+    // Only a split produces a sibling block which can contain the account
+    if block["after_split"] != true {
+        return Err(Error::account_shard_lost(address, current));
+    }
+
+    // After the split the account can reside in the other child shard
+    let sibling = client
+        .wait_for_collection(ParamsOfWaitForCollection {
+            collection: BLOCKS_COLLECTION.to_string(),
+            filter: Some(next_block_filter(current, block["id"].as_str())),
+            result: BLOCK_FIELDS.to_string(),
+            timeout,
+        }, None)
+        .await?;
+    debug!(
+        "{}: sibling block received {:#}",
+        client.state().env().now_ms() / 1000,
+        sibling
+    );
+
+    if !check_shard_match(sibling.clone(), address)? {
+        return Err(Error::account_shard_lost(address, current));
+    }
+    parse_block(sibling)
+}
+
+/// Filter for the blocks following the `current` block: the next block of the same shard,
+/// both children after the split and the merged block.
+fn next_block_filter(current: &str, except_id: Option<&str>) -> serde_json::Value {
+    let mut prev_ref = json!({
+        "prev_ref": {
+            "root_hash": { "eq": current.to_string() }
+        }
+    });
+    let mut prev_alt_ref = json!({
+        "prev_alt_ref": {
+            "root_hash": { "eq": current.to_string() }
+        }
+    });
+    if let Some(id) = except_id {
+        prev_ref["id"] = json!({ "ne": id });
+        prev_alt_ref["id"] = json!({ "ne": id });
     }
+    prev_ref["OR"] = prev_alt_ref;
+    prev_ref
+}
+
+fn parse_block(block: serde_json::Value) -> ClientResult<ton_sdk::Block> {
+    serde_json::from_value(block)
+        .map_err(|err| Error::invalid_data(format!("Can not parse block: {}", err)))
 }
 
 fn check_shard_match(
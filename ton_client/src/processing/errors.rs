@@ -15,6 +15,7 @@
 use crate::error::{ClientError, format_time};
 use serde_json::Value;
 use ton_block::MsgAddressInt;
+use ton_sdk::TransactionFees;
 
 #[derive(ApiType)]
 pub enum ErrorCode {
@@ -34,6 +35,9 @@ pub enum ErrorCode {
     MessageRejected = 514,
     InvalidRempStatus = 515,
     NextRempStatusTimeout = 516,
+    DryRunFailed = 517,
+    AccountShardLost = 518,
+    ResendingNotPersisted = 519,
 }
 
 pub struct Error;
@@ -233,4 +237,52 @@ impl Error {
     pub fn next_remp_status_timeout() -> ClientError {
         error(ErrorCode::NextRempStatusTimeout, format!("Next REMP status awaiting timeout"))
     }
+
+    pub fn resending_not_persisted() -> ClientError {
+        error(
+            ErrorCode::ResendingNotPersisted,
+            "Messages with `encode_params` can not be monitored by the persistent message monitor: \
+            resending parameters are not persisted. Send the encoded messages instead."
+                .into(),
+        )
+    }
+
+    pub fn account_shard_lost(address: &MsgAddressInt, block_id: &str) -> ClientError {
+        error_with_data(
+            ErrorCode::AccountShardLost,
+            format!(
+                "No block of the account {} shard follows the block {}",
+                address, block_id
+            ),
+            json!({
+                "account_address": address.to_string(),
+                "shard_block_id": block_id,
+            }),
+        )
+    }
+
+    pub fn dry_run_failed(
+        message_id: &str,
+        address: &MsgAddressInt,
+        local_error: ClientError,
+        fees: Option<&TransactionFees>,
+    ) -> ClientError {
+        let mut error = Self::processing_error(
+            ErrorCode::DryRunFailed,
+            format!(
+                "Message was not sent because its local execution failed: {}",
+                local_error.message
+            ),
+            message_id,
+            None,
+        );
+
+        error.data["account_address"] = address.to_string().into();
+        error.data["local_error"] = serde_json::to_value(&local_error).unwrap_or_default();
+        if let Some(fees) = fees {
+            error.data["fees"] = serde_json::to_value(fees).unwrap_or_default();
+        }
+
+        error
+    }
 }
@@ -9,7 +9,7 @@ use crate::processing::blocks_walking::wait_next_block;
 use crate::processing::internal::{can_retry_network_error, resolve_error};
 use crate::processing::parsing::{decode_output, parse_transaction_boc};
 use crate::processing::{
-    Error, ParamsOfWaitForTransaction, ProcessingEvent, ResultOfProcessMessage,
+    Error, ErrorCode, ParamsOfWaitForTransaction, ProcessingEvent, ResultOfProcessMessage,
 };
 use crate::tvm::check_transaction::{calc_transaction_fees, extract_error};
 use serde_json::Value;
@@ -45,6 +45,7 @@ pub async fn fetch_next_shard_block<F: futures::Future<Output = ()> + Send>(
         // Fetch next block
         match wait_next_block(context, block_id.into(), &address, Some(timeout)).await {
             Ok(block) => return Ok(block),
+            Err(err) if err.code == ErrorCode::AccountShardLost as u32 => return Err(err),
             Err(err) => {
                 let is_retryable_error = crate::client::Error::is_network_error(&err) ||
                     err.code == crate::net::ErrorCode::WaitForTimeout as u32;
@@ -257,6 +258,8 @@ pub async fn fetch_transaction_result(
         out_messages,
         decoded: abi_decoded,
         fees,
+        dry_run: None,
+        transaction_tree: None,
     })
 }
 
@@ -1,8 +1,11 @@
 use crate::error::ClientResult;
+use crate::processing::outbox::Outbox;
+use crate::processing::send_messages::resend_expired_messages;
 use crate::ClientContext;
 use std::sync::Arc;
 use ton_client_processing::{
-    MessageMonitoringParams, MessageMonitoringResult, MonitorFetchWaitMode, MonitoringQueueInfo,
+    MessageMonitoringParams, MessageMonitoringResult, MessageMonitoringStatus,
+    MonitorFetchWaitMode, MonitoringQueueInfo,
 };
 
 #[derive(Deserialize, Default, ApiType)]
@@ -77,14 +80,42 @@ pub async fn fetch_next_monitor_results(
     context: Arc<ClientContext>,
     params: ParamsOfFetchNextMonitorResults,
 ) -> ClientResult<ResultOfFetchNextMonitorResults> {
-    let results = context
-        .message_monitor
-        .fetch_next_monitor_results(
-            &params.queue,
-            params.wait_mode.unwrap_or(MonitorFetchWaitMode::NoWait),
-        )
-        .await?;
-    Ok(ResultOfFetchNextMonitorResults { results })
+    let wait_mode = params.wait_mode.unwrap_or(MonitorFetchWaitMode::NoWait);
+    loop {
+        let results = context
+            .message_monitor
+            .fetch_next_monitor_results(&params.queue, wait_mode)
+            .await?;
+        if Outbox::is_enabled(&context) {
+            // Results are already removed from the monitor queue,
+            // so they are returned even if the outbox can not be updated
+            for result in &results {
+                if let Err(err) = context.outbox.remove(&context, &result.hash).await {
+                    log::warn!("Can not remove message from the outbox: {}", err);
+                }
+            }
+        }
+        let now = context.env.now_ms();
+        for result in &results {
+            match result.status {
+                MessageMonitoringStatus::Finalized => context
+                    .processing_stats
+                    .transaction_received(&result.hash, now),
+                MessageMonitoringStatus::Timeout => context.processing_stats.expired(&result.hash),
+                _ => {}
+            }
+        }
+        let fetched = results.len();
+        // Expired messages sent with `encode_params` are sent again,
+        // so their results are not returned to the application
+        let results = resend_expired_messages(&context, results).await;
+        if !results.is_empty()
+            || fetched == 0
+            || matches!(wait_mode, MonitorFetchWaitMode::NoWait)
+        {
+            return Ok(ResultOfFetchNextMonitorResults { results });
+        }
+    }
 }
 
 #[derive(Deserialize, ApiType, Default)]
@@ -116,5 +147,6 @@ pub fn cancel_monitor(
     params: ParamsOfCancelMonitor,
 ) -> ClientResult<()> {
     context.message_monitor.cancel_monitor(&params.queue)?;
+    context.resending_messages.cancel_queue(&params.queue);
     Ok(())
 }
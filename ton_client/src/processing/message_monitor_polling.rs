@@ -0,0 +1,335 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::boc::cache::Bocs;
+use crate::boc::internal::deserialize_object_from_cell;
+use crate::error::{ClientError, ClientResult};
+use crate::net::subscriptions::SubscriptionAction;
+use crate::net::{
+    NetworkContext, ParamsOfQueryCollection, ParamsOfWaitForCollection, ServerLink,
+    TRANSACTIONS_COLLECTION,
+};
+use crate::processing::blocks_walking::{
+    find_last_shard_block_with_link, wait_next_block_with_link,
+};
+use crate::processing::internal::find_transactions;
+use crate::processing::{Error, ErrorCode};
+use futures::FutureExt;
+use rand::RngCore;
+use serde_json::Value;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use ton_block::MsgAddressInt;
+use ton_client_processing::{
+    MessageMonitoringParams, MessageMonitoringResult, MessageMonitoringStatus,
+    MessageMonitoringTransaction, MessageMonitoringTransactionCompute, MonitoredMessage,
+};
+
+const TRANSACTION_FIELDS: &str = "id in_msg aborted compute { exit_code }";
+
+struct PolledMessage {
+    hash: String,
+    address: MsgAddressInt,
+    wait_until: u32,
+    /// Last walked shard block. Missing until the walking is started.
+    block_id: Option<String>,
+}
+
+impl PolledMessage {
+    fn new(bocs: &Bocs, params: MessageMonitoringParams) -> ClientResult<Self> {
+        let (hash, address) = match params.message {
+            MonitoredMessage::HashAddress { hash, address } => {
+                (hash, crate::encoding::account_decode(&address)?)
+            }
+            MonitoredMessage::Boc { boc } => {
+                let (_, cell) = bocs.deserialize_cell(&boc, "message")?;
+                let hash = cell.repr_hash().as_hex_string();
+                let message = deserialize_object_from_cell::<ton_block::Message>(cell, "message")?;
+                let address = message
+                    .dst()
+                    .ok_or_else(|| Error::message_has_not_destination_address())?;
+                (hash, address)
+            }
+        };
+        Ok(Self {
+            hash,
+            address,
+            wait_until: params.wait_until,
+            block_id: None,
+        })
+    }
+}
+
+fn finalized(hash: String, transaction: &Value) -> MessageMonitoringResult {
+    MessageMonitoringResult {
+        hash,
+        status: MessageMonitoringStatus::Finalized,
+        transaction: Some(MessageMonitoringTransaction {
+            hash: transaction["id"].as_str().map(|x| x.to_string()),
+            aborted: transaction["aborted"].as_bool().unwrap_or_default(),
+            compute: transaction["compute"]["exit_code"]
+                .as_i64()
+                .map(|exit_code| MessageMonitoringTransactionCompute {
+                    exit_code: exit_code as i32,
+                }),
+        }),
+        error: None,
+        user_data: None,
+    }
+}
+
+fn timeout(hash: String) -> MessageMonitoringResult {
+    MessageMonitoringResult {
+        hash,
+        status: MessageMonitoringStatus::Timeout,
+        transaction: None,
+        error: Some("Message was not processed until `wait_until` block time".to_string()),
+        user_data: None,
+    }
+}
+
+/// Starts resolving of the messages by polling the blockchain data.
+///
+/// Used instead of the `recentExtInMessageStatuses` subscription when the endpoint
+/// does not support it. The returned handle is registered as a regular subscription
+/// handle so the polling is stopped with `NetworkContext::unsubscribe`.
+pub(crate) async fn subscribe_polling<F: Future<Output = ()> + Send>(
+    net: Arc<NetworkContext>,
+    bocs: &Bocs,
+    messages: Vec<MessageMonitoringParams>,
+    callback: impl Fn(ton_client_processing::Result<Vec<MessageMonitoringResult>>) -> F
+        + Send
+        + Sync
+        + 'static,
+) -> ClientResult<u32> {
+    let mut polled = Vec::new();
+    for message in messages {
+        polled.push(PolledMessage::new(bocs, message)?);
+    }
+    let (sender, receiver) = mpsc::channel(1);
+    let handle = rand::thread_rng().next_u32();
+    net.add_subscription_handle(handle, sender).await;
+    let env = net.env.clone();
+    env.spawn(async move {
+        poll_messages(net, polled, callback, receiver).await;
+    });
+    Ok(handle)
+}
+
+async fn poll_messages<F: Future<Output = ()> + Send>(
+    net: Arc<NetworkContext>,
+    mut messages: Vec<PolledMessage>,
+    callback: impl Fn(ton_client_processing::Result<Vec<MessageMonitoringResult>>) -> F
+        + Send
+        + Sync,
+    mut receiver: mpsc::Receiver<SubscriptionAction>,
+) {
+    let mut retry_start = 0;
+    while !messages.is_empty() {
+        let polled = futures::select! {
+            polled = poll_next(&net, &mut messages).fuse() => polled,
+            // Subscription is removed with `unsubscribe`
+            _ = receiver.recv().fuse() => return,
+        };
+        let network_error = match polled {
+            Ok(polled) => {
+                if !polled.results.is_empty() {
+                    callback(Ok(polled.results)).await;
+                }
+                for err in polled.errors {
+                    callback(Err(err.into())).await;
+                }
+                polled.network_error
+            }
+            Err(err) => Some(err),
+        };
+        match network_error {
+            None => retry_start = 0,
+            Some(err) => {
+                if retry_start == 0 {
+                    retry_start = net.env.now_ms();
+                }
+                let can_retry = crate::client::Error::is_network_error(&err)
+                    && net
+                        .get_server_link()
+                        .map(|link| link.state().can_retry_network_error(retry_start))
+                        .unwrap_or_default();
+                if !can_retry {
+                    callback(Err(err.into())).await;
+                    return;
+                }
+                if let Ok(link) = net.get_server_link() {
+                    let _ = net
+                        .env
+                        .set_timer(link.state().next_resume_timeout() as u64)
+                        .await;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct PollingStep {
+    results: Vec<MessageMonitoringResult>,
+    /// Errors of the single messages. Walking of such messages is restarted
+    /// from the current shard block.
+    errors: Vec<ClientError>,
+    /// Network error of the single messages. Such messages are polled again after the delay.
+    network_error: Option<ClientError>,
+}
+
+impl PollingStep {
+    fn message_failed(&mut self, message: &mut PolledMessage, err: ClientError) {
+        if crate::client::Error::is_network_error(&err) {
+            self.network_error = Some(err);
+            return;
+        }
+        // The skipped blocks are covered by the transactions lookup on restart
+        message.block_id = None;
+        if err.code != ErrorCode::AccountShardLost as u32 {
+            self.errors.push(err);
+        }
+    }
+}
+
+/// Performs one polling step: starts walking for the new messages and
+/// walks one block further for the others. Returns resolved results and
+/// removes resolved messages from the list. Errors of the single messages
+/// don't stop the polling of the other messages.
+async fn poll_next(
+    net: &NetworkContext,
+    messages: &mut Vec<PolledMessage>,
+) -> ClientResult<PollingStep> {
+    let server_link = net.get_server_link()?;
+    let mut results = Vec::new();
+
+    // Starting blocks are found before the transactions lookup, so the transactions
+    // missed by the lookup will be found in the next blocks
+    let mut started = Vec::new();
+    for message in messages.iter_mut().filter(|x| x.block_id.is_none()) {
+        let block_id =
+            find_last_shard_block_with_link(server_link, &message.address, None).await?;
+        message.block_id = Some(block_id.to_string());
+        started.push(message.hash.clone());
+    }
+    if !started.is_empty() {
+        for transaction in query_transactions(server_link, &started).await? {
+            if let Some(hash) = transaction["in_msg"].as_str() {
+                results.push(finalized(hash.to_string(), &transaction));
+            }
+        }
+        messages.retain(|x| !results.iter().any(|result| result.hash == x.hash));
+    }
+
+    let wait_timeout = server_link.config().wait_for_timeout;
+    let blocks = futures::future::join_all(messages.iter().map(|message| {
+        next_block(
+            server_link,
+            message.block_id.as_deref().unwrap_or_default(),
+            &message.address,
+            wait_timeout,
+        )
+    }))
+    .await;
+
+    let mut step = PollingStep::default();
+    let mut resolved = Vec::new();
+    for (message, block) in messages.iter_mut().zip(blocks) {
+        let block = match block {
+            Ok(Some(block)) => block,
+            Ok(None) => continue,
+            Err(err) => {
+                step.message_failed(message, err);
+                continue;
+            }
+        };
+        let block_id = block.id.to_string();
+        let transaction_ids = match find_transactions(&block, &message.hash, &block_id) {
+            Ok(transaction_ids) => transaction_ids,
+            Err(err) => {
+                step.message_failed(message, err);
+                continue;
+            }
+        };
+        if let Some(transaction_id) = transaction_ids.first() {
+            match wait_transaction(server_link, transaction_id, wait_timeout).await {
+                Ok(transaction) => resolved.push(finalized(message.hash.clone(), &transaction)),
+                Err(err) => step.message_failed(message, err),
+            }
+        } else if block.gen_utime > message.wait_until {
+            resolved.push(timeout(message.hash.clone()));
+        } else {
+            message.block_id = Some(block_id);
+        }
+    }
+    messages.retain(|x| !resolved.iter().any(|result| result.hash == x.hash));
+    results.append(&mut resolved);
+    step.results = results;
+    Ok(step)
+}
+
+/// Waits for the next shard block. Returns `None` if there is no new block yet.
+async fn next_block(
+    server_link: &ServerLink,
+    current: &str,
+    address: &MsgAddressInt,
+    timeout: u32,
+) -> ClientResult<Option<ton_sdk::Block>> {
+    match wait_next_block_with_link(server_link, current, address, Some(timeout)).await {
+        Ok(block) => Ok(Some(block)),
+        Err(err) if err.code == crate::net::ErrorCode::WaitForTimeout as u32 => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+async fn query_transactions(
+    server_link: &ServerLink,
+    message_hashes: &[String],
+) -> ClientResult<Vec<Value>> {
+    let result = server_link
+        .query_collection(
+            ParamsOfQueryCollection {
+                collection: TRANSACTIONS_COLLECTION.to_string(),
+                filter: Some(json!({
+                    "in_msg": { "in": message_hashes },
+                })),
+                result: TRANSACTION_FIELDS.to_string(),
+                ..Default::default()
+            },
+            None,
+        )
+        .await?;
+    Ok(result.as_array().cloned().unwrap_or_default())
+}
+
+async fn wait_transaction(
+    server_link: &ServerLink,
+    transaction_id: &str,
+    timeout: u32,
+) -> ClientResult<Value> {
+    server_link
+        .wait_for_collection(
+            ParamsOfWaitForCollection {
+                collection: TRANSACTIONS_COLLECTION.to_string(),
+                filter: Some(json!({
+                    "id": { "eq": transaction_id },
+                })),
+                result: TRANSACTION_FIELDS.to_string(),
+                timeout: Some(timeout),
+            },
+            None,
+        )
+        .await
+}
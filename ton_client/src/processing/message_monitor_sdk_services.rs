@@ -1,26 +1,61 @@
 use crate::boc::cache::Bocs;
+use crate::client::storage::KeyValueStorage;
 use crate::error::{ClientError, ClientResult};
 use crate::net::{NetworkContext, ResultOfSubscription};
+use crate::processing::message_monitor_polling::subscribe_polling;
 use async_trait::async_trait;
 use serde_json::Value;
 use std::future::Future;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use tokio::sync::RwLock;
 use ton_client_processing::{
-    MessageMonitorSdkServices, MessageMonitoringParams, MessageMonitoringResult,
+    MessageMonitorSdkServices, MessageMonitorStorage, MessageMonitoringParams, MessageMonitoringResult,
     MessageMonitoringStatus, MessageMonitoringTransaction, MessageMonitoringTransactionCompute,
     MonitoredMessage, NetSubscription,
 };
 use ton_types::Cell;
 
+const EXT_IN_MESSAGE_STATUSES_SUBSCRIPTION: &str = "recentExtInMessageStatuses";
+
 pub(crate) struct SdkServices {
     net: Arc<NetworkContext>,
     bocs: Arc<Bocs>,
+    // Cached result of the capabilities check with the URL of the checked endpoint
+    ext_in_message_statuses_supported: RwLock<Option<(String, bool)>>,
 }
 
 impl SdkServices {
     pub fn new(net: Arc<NetworkContext>, bocs: Arc<Bocs>) -> Self {
-        Self { net, bocs }
+        Self {
+            net,
+            bocs,
+            ext_in_message_statuses_supported: Default::default(),
+        }
+    }
+
+    /// Checks the current query endpoint for the `recentExtInMessageStatuses` subscription.
+    /// The result is checked again when the query endpoint changes.
+    pub(crate) async fn is_ext_in_message_statuses_supported(&self) -> ClientResult<bool> {
+        let server_link = self.net.get_server_link()?;
+        let endpoint = server_link.get_query_endpoint().await?;
+        if let Some((url, supported)) = &*self.ext_in_message_statuses_supported.read().await {
+            if *url == endpoint.query_url {
+                return Ok(*supported);
+            }
+        }
+        let supported = server_link
+            .is_subscription_supported(EXT_IN_MESSAGE_STATUSES_SUBSCRIPTION, &endpoint)
+            .await?;
+        if !supported {
+            log::debug!(
+                "Message monitor uses polling: endpoint has no {} subscription",
+                EXT_IN_MESSAGE_STATUSES_SUBSCRIPTION
+            );
+        }
+        *self.ext_in_message_statuses_supported.write().await =
+            Some((endpoint.query_url.clone(), supported));
+        Ok(supported)
     }
 
     fn subscription(messages: Vec<MessageMonitoringParams>) -> (String, Option<Value>) {
@@ -94,6 +129,11 @@ impl MessageMonitorSdkServices for SdkServices {
             + Sync
             + 'static,
     ) -> ton_client_processing::Result<NetSubscription> {
+        if !self.is_ext_in_message_statuses_supported().await? {
+            let subscription =
+                subscribe_polling(self.net.clone(), &self.bocs, messages, callback).await?;
+            return Ok(NetSubscription(subscription as usize));
+        }
         // We have to wrap callback into Arc because it will move out of closure scope
         let callback = Arc::new(callback);
         let (query, vars) = Self::subscription(messages);
@@ -167,6 +207,58 @@ impl MessageMonitorSdkServices for SdkServices {
     }
 }
 
+const MONITOR_STORAGE_NAME: &str = "message_monitor";
+const MONITOR_QUEUES_KEY: &str = "queues";
+
+/// Message monitor queues storage based on the client local storage.
+pub(crate) struct MonitorStorage {
+    local_storage_path: Option<String>,
+    storage: RwLock<Option<Arc<dyn KeyValueStorage>>>,
+}
+
+impl MonitorStorage {
+    pub fn new(local_storage_path: Option<String>) -> Self {
+        Self {
+            local_storage_path,
+            storage: Default::default(),
+        }
+    }
+
+    async fn storage(&self) -> ClientResult<Arc<dyn KeyValueStorage>> {
+        if let Some(storage) = self.storage.read().await.as_ref() {
+            return Ok(storage.clone());
+        }
+        let mut write_guard = self.storage.write().await;
+        if let Some(storage) = write_guard.as_ref() {
+            return Ok(storage.clone());
+        }
+        let storage = Arc::new(
+            crate::client::LocalStorage::new(
+                self.local_storage_path.clone(),
+                MONITOR_STORAGE_NAME.to_string(),
+            )
+            .await?,
+        ) as Arc<dyn KeyValueStorage>;
+        *write_guard = Some(storage.clone());
+        Ok(storage)
+    }
+}
+
+#[async_trait]
+impl MessageMonitorStorage for MonitorStorage {
+    async fn load_queues(&self) -> ton_client_processing::Result<Option<String>> {
+        Ok(self.storage().await?.get_str(MONITOR_QUEUES_KEY).await?)
+    }
+
+    async fn save_queues(&self, snapshot: String) -> ton_client_processing::Result<()> {
+        Ok(self
+            .storage()
+            .await?
+            .put_str(MONITOR_QUEUES_KEY, &snapshot)
+            .await?)
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct GraphQLMessageMonitoringParams {
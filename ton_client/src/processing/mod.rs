@@ -20,12 +20,18 @@ mod errors;
 mod fetching;
 mod internal;
 mod message_monitor;
+mod message_monitor_polling;
 mod message_monitor_sdk_services;
+pub(crate) mod outbox;
 pub(crate) mod parsing;
 pub(crate) mod process_message;
 mod remp;
+mod remp_statuses;
 pub(crate) mod send_message;
 mod send_messages;
+pub(crate) mod sequencer;
+pub(crate) mod stats;
+mod transaction_tree;
 mod types;
 pub(crate) mod wait_for_transaction;
 
@@ -36,9 +42,28 @@ pub use message_monitor::{
     ParamsOfCancelMonitor, ParamsOfFetchNextMonitorResults, ParamsOfGetMonitorInfo,
     ParamsOfMonitorMessages, ResultOfFetchNextMonitorResults,
 };
-pub(crate) use message_monitor_sdk_services::SdkServices;
-pub use process_message::{process_message, ParamsOfProcessMessage};
+pub(crate) use message_monitor_sdk_services::{MonitorStorage, SdkServices};
+pub(crate) use outbox::Outbox;
+pub use outbox::{
+    get_outbox, resume_outbox, OutboxMessage, OutboxMessageResult, ParamsOfResumeOutbox,
+    ResultOfGetOutbox, ResultOfResumeOutbox, DEFAULT_OUTBOX_QUEUE,
+};
+pub use process_message::{process_message, DryRunOptions, ParamsOfProcessMessage};
 pub use send_message::{send_message, ParamsOfSendMessage, ResultOfSendMessage};
+pub use remp_statuses::{
+    subscribe_remp_statuses, ParamsOfSubscribeRempStatuses, RempStatusUpdate,
+};
+pub(crate) use send_messages::ResendingMessages;
+pub(crate) use sequencer::Sequencers;
+pub use sequencer::{
+    get_sequencer_info, process_sequenced_message, ParamsOfGetSequencerInfo,
+    ParamsOfProcessSequencedMessage, ResultOfGetSequencerInfo,
+};
+pub(crate) use stats::ProcessingStats;
+pub use stats::{
+    get_stats, EndpointFailures, LatencyStats, ParamsOfGetStats, RempStatusStats,
+    ResultOfGetStats,
+};
 pub use send_messages::{
     send_messages, send_messages_api, MessageSendingParams, ParamsOfSendMessages,
     ResultOfSendMessages,
@@ -48,5 +73,8 @@ pub use ton_client_processing::{
     MessageMonitoringTransaction, MessageMonitoringTransactionCompute, MonitorFetchWaitMode,
     MonitoredMessage, MonitoringQueueInfo,
 };
-pub use types::{DecodedOutput, ProcessingEvent, ProcessingResponseType, ResultOfProcessMessage};
+pub use types::{
+    DecodedOutput, DryRunResult, ProcessingEvent, ProcessingResponseType, ResultOfProcessMessage,
+};
+pub use transaction_tree::{ResultOfTransactionTree, TransactionTreeOptions};
 pub use wait_for_transaction::{wait_for_transaction, ParamsOfWaitForTransaction};
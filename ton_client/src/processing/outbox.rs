@@ -0,0 +1,348 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::sync::{Mutex, RwLock};
+use ton_client_processing::{MessageMonitoringParams, MonitoredMessage};
+
+use crate::abi::Abi;
+use crate::client::storage::KeyValueStorage;
+use crate::client::ClientContext;
+use crate::error::{ClientError, ClientResult};
+use crate::processing::internal::get_message_expiration_time;
+use crate::processing::{
+    wait_for_transaction, ErrorCode, ParamsOfWaitForTransaction, ResultOfProcessMessage,
+};
+
+const STORAGE_NAME: &str = "outbox";
+const INDEX_KEY: &str = "index";
+
+/// Name of the monitoring queue used by `resume_outbox` by default.
+pub const DEFAULT_OUTBOX_QUEUE: &str = "outbox";
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, Clone)]
+pub struct OutboxMessage {
+    /// Message hash.
+    pub message_id: String,
+
+    /// Message BOC.
+    pub message: String,
+
+    /// Message ABI used to decode the transaction result.
+    pub abi: Option<Abi>,
+
+    /// Message expiration time.
+    ///
+    /// Missing if the message has no `expire` header.
+    /// Must be specified as a UNIX timestamp in seconds.
+    pub expire: Option<u32>,
+
+    /// Time when the message was recorded into the outbox.
+    ///
+    /// Must be specified as a UNIX timestamp in seconds.
+    pub created_at: u32,
+
+    /// The last generated block id of the destination account shard before
+    /// the message was sent.
+    ///
+    /// Missing for the messages sent with `send_messages`.
+    pub shard_block_id: Option<String>,
+
+    /// The list of endpoints to which the message was sent.
+    pub sending_endpoints: Option<Vec<String>>,
+
+    /// User defined data associated with this message.
+    pub user_data: Option<Value>,
+}
+
+impl OutboxMessage {
+    pub(crate) fn new(
+        context: &Arc<ClientContext>,
+        message: &str,
+        abi: Option<&Abi>,
+        user_data: Option<Value>,
+    ) -> ClientResult<Self> {
+        let (hash, message) = context.bocs.resolve_boc_with_hash(message, "message")?;
+        let expire = get_message_expiration_time(context.clone(), abi, &message)?
+            .map(|expire| (expire / 1000) as u32);
+        Ok(Self {
+            message_id: hash.as_hex_string(),
+            message,
+            abi: abi.cloned(),
+            expire,
+            created_at: (context.env.now_ms() / 1000) as u32,
+            shard_block_id: None,
+            sending_endpoints: None,
+            user_data,
+        })
+    }
+
+    fn wait_until(&self, context: &ClientContext) -> u32 {
+        self.expire.unwrap_or(
+            self.created_at + context.config.network.message_processing_timeout / 1000,
+        )
+    }
+}
+
+/// Durable storage of the messages which processing is not finished yet.
+#[derive(Default)]
+pub(crate) struct Outbox {
+    storage: RwLock<Option<Arc<dyn KeyValueStorage>>>,
+    index_lock: Mutex<()>,
+}
+
+fn message_key(message_id: &str) -> String {
+    format!("message_{}", message_id)
+}
+
+impl Outbox {
+    pub fn is_enabled(context: &ClientContext) -> bool {
+        context.config.network.message_outbox
+    }
+
+    async fn storage(&self, context: &ClientContext) -> ClientResult<Arc<dyn KeyValueStorage>> {
+        if let Some(storage) = self.storage.read().await.as_ref() {
+            return Ok(storage.clone());
+        }
+        let mut write_guard = self.storage.write().await;
+        if let Some(storage) = write_guard.as_ref() {
+            return Ok(storage.clone());
+        }
+        let storage = Arc::new(
+            crate::client::LocalStorage::new(
+                context.config.local_storage_path.clone(),
+                STORAGE_NAME.to_string(),
+            )
+            .await?,
+        ) as Arc<dyn KeyValueStorage>;
+        *write_guard = Some(storage.clone());
+        Ok(storage)
+    }
+
+    async fn read_index(storage: &Arc<dyn KeyValueStorage>) -> ClientResult<Vec<String>> {
+        match storage.get_str(INDEX_KEY).await? {
+            Some(index) => serde_json::from_str(&index).map_err(|err| {
+                crate::client::Error::internal_error(format!("Invalid outbox index: {}", err))
+            }),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn write_index(storage: &Arc<dyn KeyValueStorage>, index: &[String]) -> ClientResult<()> {
+        let index = serde_json::to_string(index).map_err(crate::client::Error::internal_error)?;
+        storage.put_str(INDEX_KEY, &index).await
+    }
+
+    pub async fn put(&self, context: &ClientContext, message: &OutboxMessage) -> ClientResult<()> {
+        let storage = self.storage(context).await?;
+        let value = serde_json::to_string(message).map_err(crate::client::Error::internal_error)?;
+        let _guard = self.index_lock.lock().await;
+        storage
+            .put_str(&message_key(&message.message_id), &value)
+            .await?;
+        let mut index = Self::read_index(&storage).await?;
+        if !index.contains(&message.message_id) {
+            index.push(message.message_id.clone());
+            Self::write_index(&storage, &index).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn remove(&self, context: &ClientContext, message_id: &str) -> ClientResult<()> {
+        let storage = self.storage(context).await?;
+        let _guard = self.index_lock.lock().await;
+        let mut index = Self::read_index(&storage).await?;
+        if let Some(pos) = index.iter().position(|id| id == message_id) {
+            index.remove(pos);
+            Self::write_index(&storage, &index).await?;
+        }
+        storage.remove(&message_key(message_id)).await
+    }
+
+    pub async fn messages(&self, context: &ClientContext) -> ClientResult<Vec<OutboxMessage>> {
+        let storage = self.storage(context).await?;
+        let _guard = self.index_lock.lock().await;
+        let mut messages = Vec::new();
+        for message_id in Self::read_index(&storage).await? {
+            if let Some(value) = storage.get_str(&message_key(&message_id)).await? {
+                match serde_json::from_str(&value) {
+                    Ok(message) => messages.push(message),
+                    Err(err) => log::warn!("Invalid outbox message {}: {}", message_id, err),
+                }
+            }
+        }
+        Ok(messages)
+    }
+}
+
+/// Removes the message from the outbox if its processing result is definite.
+///
+/// The result is definite if the transaction is found, the message is expired or its
+/// rejection is confirmed. Other errors (e.g. `TransactionWaitTimeout`) leave the message
+/// in the outbox, so `resume_outbox` can check it again.
+pub(crate) async fn complete_outbox_message<T>(
+    context: &Arc<ClientContext>,
+    message: &str,
+    result: &ClientResult<T>,
+) {
+    if !Outbox::is_enabled(context) {
+        return;
+    }
+    let definite = match result {
+        Ok(_) => true,
+        Err(err) => {
+            err.code == ErrorCode::MessageExpired as u32
+                || err.code == ErrorCode::MessageRejected as u32
+        }
+    };
+    if !definite {
+        return;
+    }
+    let removed = match context.bocs.resolve_boc_with_hash(message, "message") {
+        Ok((hash, _)) => context.outbox.remove(context, &hash.as_hex_string()).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = removed {
+        log::warn!("Can not remove message from the outbox: {}", err);
+    }
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ResultOfGetOutbox {
+    /// Messages which processing is not finished yet.
+    pub messages: Vec<OutboxMessage>,
+}
+
+/// Returns messages stored in the outbox.
+///
+/// Messages are stored in the outbox before sending if the `network.message_outbox`
+/// config parameter is `true` and are removed from the outbox when their processing
+/// result is definite: the transaction is found, the message is expired or rejected.
+#[api_function]
+pub async fn get_outbox(context: Arc<ClientContext>) -> ClientResult<ResultOfGetOutbox> {
+    Ok(ResultOfGetOutbox {
+        messages: context.outbox.messages(&context).await?,
+    })
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfResumeOutbox {
+    /// Message monitor queue that will receive the outbox messages.
+    ///
+    /// If this parameter is specified then all the outbox messages are passed to
+    /// the message monitor and the application receives their results
+    /// with `fetch_next_monitor_results`.
+    /// Otherwise the library waits for the transactions of the messages
+    /// that have `shard_block_id` and passes the rest of the messages to the
+    /// `outbox` monitor queue.
+    pub monitor_queue: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct OutboxMessageResult {
+    /// Outbox message.
+    pub message: OutboxMessage,
+
+    /// Message processing result if the transaction was found.
+    pub result: Option<ResultOfProcessMessage>,
+
+    /// Message processing error.
+    pub error: Option<ClientError>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ResultOfResumeOutbox {
+    /// Results of the messages waited by the library.
+    pub results: Vec<OutboxMessageResult>,
+
+    /// Messages passed to the message monitor.
+    pub monitored: Vec<OutboxMessage>,
+}
+
+/// Resumes processing of the messages stored in the outbox.
+///
+/// Application should call this function on startup to finish processing of the messages
+/// sent before the restart. Each message is either waited with `wait_for_transaction`
+/// or passed to the message monitor, see `monitor_queue` parameter.
+///
+/// Messages with the definite result are removed from the outbox. Messages passed to
+/// the message monitor are removed from the outbox when their results are fetched
+/// with `fetch_next_monitor_results`.
+#[api_function]
+pub async fn resume_outbox(
+    context: Arc<ClientContext>,
+    params: ParamsOfResumeOutbox,
+) -> ClientResult<ResultOfResumeOutbox> {
+    let mut waiting = Vec::new();
+    let mut monitored = Vec::new();
+    for message in context.outbox.messages(&context).await? {
+        if params.monitor_queue.is_none() && message.shard_block_id.is_some() {
+            waiting.push(message);
+        } else {
+            monitored.push(message);
+        }
+    }
+
+    if !monitored.is_empty() {
+        let queue = params
+            .monitor_queue
+            .unwrap_or_else(|| DEFAULT_OUTBOX_QUEUE.to_string());
+        context.message_monitor.monitor_messages(
+            &queue,
+            monitored
+                .iter()
+                .map(|message| MessageMonitoringParams {
+                    message: MonitoredMessage::Boc {
+                        boc: message.message.clone(),
+                    },
+                    wait_until: message.wait_until(&context),
+                    user_data: message.user_data.clone(),
+                })
+                .collect(),
+        )?;
+    }
+
+    let futures = waiting.into_iter().map(|message| {
+        let context = context.clone();
+        async move {
+            let result = wait_for_transaction(
+                context,
+                ParamsOfWaitForTransaction {
+                    abi: message.abi.clone(),
+                    message: message.message.clone(),
+                    shard_block_id: message.shard_block_id.clone().unwrap_or_default(),
+                    send_events: false,
+                    sending_endpoints: message.sending_endpoints.clone(),
+                },
+                |_| futures::future::ready(()),
+            )
+            .await;
+            let (result, error) = match result {
+                Ok(result) => (Some(result), None),
+                Err(err) => (None, Some(err)),
+            };
+            OutboxMessageResult {
+                message,
+                result,
+                error,
+            }
+        }
+    });
+
+    Ok(ResultOfResumeOutbox {
+        results: futures::future::join_all(futures).await,
+        monitored,
+    })
+}
@@ -1,12 +1,19 @@
-use crate::abi::ParamsOfEncodeMessage;
+use crate::abi::{Abi, ParamsOfEncodeMessage, ResultOfEncodeMessage};
+use crate::boc::internal::deserialize_object_from_base64;
 use crate::client::ClientContext;
-use crate::error::{AddNetworkUrl, ClientResult};
+use crate::error::{AddNetworkUrl, ClientError, ClientResult};
+use crate::processing::fetching::fetch_account;
 use crate::processing::internal::can_retry_expired_message;
+use crate::processing::transaction_tree::{
+    wait_transaction_tree, ResultOfTransactionTree, TransactionTreeOptions,
+};
 use crate::processing::{
-    send_message, wait_for_transaction, ErrorCode, ParamsOfSendMessage, ParamsOfWaitForTransaction,
-    ProcessingEvent, ResultOfProcessMessage, ResultOfSendMessage,
+    send_message, wait_for_transaction, DryRunResult, Error, ErrorCode, ParamsOfSendMessage,
+    ParamsOfWaitForTransaction, ProcessingEvent, ResultOfProcessMessage, ResultOfSendMessage,
 };
-use crate::tvm::StdContractError;
+use crate::tvm::check_transaction::extract_error;
+use crate::tvm::{AccountForExecutor, ParamsOfRunExecutor, StdContractError};
+use std::convert::TryFrom;
 use std::sync::Arc;
 
 #[derive(Serialize, Deserialize, ApiType, Default, Debug)]
@@ -18,6 +25,116 @@ pub struct ParamsOfProcessMessage {
     /// Default is `false`.
     #[serde(default)]
     pub send_events: bool,
+
+    /// Dry-run options.
+    ///
+    /// If specified, the message is executed locally on the current account state
+    /// before sending. If the local execution fails, the message is not sent and
+    /// the `DryRunFailed` error with the local error and estimated fees is returned.
+    pub dry_run: Option<DryRunOptions>,
+
+    /// Transaction tree waiting options.
+    ///
+    /// If specified, after the message transaction is found the library waits until
+    /// all the transactions of the tree produced by the message are executed and returns
+    /// the tree in `ResultOfProcessMessage.transaction_tree`. If the waiting fails,
+    /// the message result is still returned and the tree contains the `error`.
+    pub wait_transaction_tree: Option<TransactionTreeOptions>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, Clone)]
+pub struct DryRunOptions {
+    /// Flag for returning the local execution result in `ResultOfProcessMessage.dry_run`,
+    /// so it can be compared with the real one.
+    /// Default is `false`.
+    #[serde(default)]
+    pub return_result: bool,
+}
+
+/// Executes the message locally on the current state of the destination account.
+async fn dry_run(
+    context: &Arc<ClientContext>,
+    message: &ResultOfEncodeMessage,
+    abi: &Abi,
+) -> ClientResult<DryRunResult> {
+    let address = crate::encoding::account_decode(&message.address)?;
+    let account = match fetch_account(context.clone(), &address, "boc balance(format: DEC)").await
+    {
+        Ok(account) => account,
+        Err(err) if err.code == crate::tvm::ErrorCode::AccountMissing as u32 => {
+            return Err(Error::dry_run_failed(&message.message_id, &address, err, None));
+        }
+        Err(err) => return Err(err),
+    };
+    let balance = account["balance"]
+        .as_str()
+        .and_then(|balance| balance.parse::<u64>().ok())
+        .unwrap_or_default();
+
+    let result = crate::tvm::run_executor_internal(
+        context.clone(),
+        ParamsOfRunExecutor {
+            message: message.message.clone(),
+            account: AccountForExecutor::Account {
+                boc: account["boc"].as_str().unwrap_or_default().to_string(),
+                unlimited_balance: None,
+            },
+            abi: Some(abi.clone()),
+            skip_transaction_check: Some(true),
+            ..Default::default()
+        },
+        true,
+    )
+    .await
+    .map_err(|err| Error::dry_run_failed(&message.message_id, &address, err, None))?;
+
+    if result.transaction["aborted"].as_bool().unwrap_or_default() {
+        let transaction = deserialize_object_from_base64::<ton_block::Transaction>(
+            result.transaction["boc"].as_str().unwrap_or_default(),
+            "transaction",
+        )?;
+        let transaction = ton_sdk::Transaction::try_from(&transaction.object)
+            .map_err(|err| crate::tvm::Error::can_not_read_transaction(err))?;
+        let address_copy = address.clone();
+        let local_error = match extract_error(
+            &transaction,
+            || async move { Ok((address_copy, balance)) },
+            true,
+        )
+        .await
+        {
+            Err(err) => err,
+            Ok(_) => crate::tvm::Error::transaction_aborted(),
+        };
+        return Err(Error::dry_run_failed(
+            &message.message_id,
+            &address,
+            local_error,
+            Some(&result.fees),
+        ));
+    }
+
+    Ok(DryRunResult {
+        transaction: result.transaction,
+        out_messages: result.out_messages,
+        decoded: result.decoded,
+        fees: result.fees,
+    })
+}
+
+/// Checks that the message processing failed because of the message expiration
+/// and can be retried with a new message.
+pub(crate) fn can_retry_expired_message_error(
+    context: &Arc<ClientContext>,
+    err: &ClientError,
+    try_index: u8,
+) -> bool {
+    let local_exit_code = &err.data["local_error"]["data"]["exit_code"];
+    err.code == ErrorCode::MessageExpired as u32
+        && (err.data["local_error"].is_null()
+            || local_exit_code == StdContractError::ReplayProtection as i32
+            || local_exit_code == StdContractError::ExtMessageExpired as i32)
+        && can_retry_expired_message(context, try_index)
 }
 
 pub async fn process_message<F: futures::Future<Output = ()> + Send>(
@@ -35,6 +152,12 @@ pub async fn process_message<F: futures::Future<Output = ()> + Send>(
         let message = crate::abi::encode_message(context.clone(), encode_params)
             .await?;
 
+        // Execute locally, the message is not sent if the execution fails
+        let dry_run_result = match &params.dry_run {
+            Some(_) => Some(dry_run(&context, &message, &abi).await?),
+            None => None,
+        };
+
         // Send
         let ResultOfSendMessage {
             shard_block_id,
@@ -45,6 +168,7 @@ pub async fn process_message<F: futures::Future<Output = ()> + Send>(
                 message: message.message.clone(),
                 abi: Some(abi.clone()),
                 send_events: params.send_events,
+                user_data: None,
             },
             &callback,
         )
@@ -68,18 +192,25 @@ pub async fn process_message<F: futures::Future<Output = ()> + Send>(
         .await;
 
         match wait_for {
-            Ok(output) => {
+            Ok(mut output) => {
                 // Waiting is complete, return output
+                if params.dry_run.as_ref().map_or(false, |x| x.return_result) {
+                    output.dry_run = dry_run_result;
+                }
+                if let Some(options) = &params.wait_transaction_tree {
+                    // The message is already processed, so the tree error is returned
+                    // with the output to prevent the message resending
+                    let tree = wait_transaction_tree(&context, &message.message_id, &abi, options)
+                        .await
+                        .add_network_url_from_context(&context)
+                        .await
+                        .unwrap_or_else(ResultOfTransactionTree::failed);
+                    output.transaction_tree = Some(tree);
+                }
                 return Ok(output);
             }
             Err(err) => {
-                let local_exit_code = &err.data["local_error"]["data"]["exit_code"];
-                let can_retry = err.code == ErrorCode::MessageExpired as u32
-                    && (err.data["local_error"].is_null()
-                        || local_exit_code == StdContractError::ReplayProtection as i32
-                        || local_exit_code == StdContractError::ExtMessageExpired as i32)
-                    && can_retry_expired_message(&context, try_index);
-                if !can_retry {
+                if !can_retry_expired_message_error(&context, &err, try_index) {
                     // Waiting error is unrecoverable, return it
                     return Err(err);
                 }
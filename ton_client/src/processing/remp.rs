@@ -35,6 +35,17 @@ pub enum RempStatus {
 }
 
 impl RempStatus {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RempStatus::RejectedByFullnode(_) => "RejectedByFullnode",
+            RempStatus::SentToValidators(_) => "SentToValidators",
+            RempStatus::IncludedIntoBlock(_) => "IncludedIntoBlock",
+            RempStatus::IncludedIntoAcceptedBlock(_) => "IncludedIntoAcceptedBlock",
+            RempStatus::Finalized(_) => "Finalized",
+            RempStatus::Other(_) => "Other",
+        }
+    }
+
     pub fn into_event(self, message_dst: String) -> ProcessingEvent {
         match self {
             RempStatus::SentToValidators(data) => {
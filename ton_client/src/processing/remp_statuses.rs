@@ -0,0 +1,251 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::FutureExt;
+use rand::RngCore;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use crate::net::subscriptions::ParamsOfSubscribe;
+use crate::net::{ResultOfSubscribeCollection, ResultOfSubscription};
+use crate::processing::remp::{RempStatus, RempStatusData};
+use crate::processing::Error;
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfSubscribeRempStatuses {
+    /// Ids (hashes) of the messages which REMP statuses must be delivered.
+    ///
+    /// The messages can be sent by any client, not only by this one.
+    pub message_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Debug, PartialEq, Clone)]
+#[serde(tag = "type")]
+pub enum RempStatusUpdate {
+    /// The message has been delivered to the thread's validators.
+    SentToValidators {
+        message_id: String,
+        timestamp: u64,
+        json: Value,
+    },
+    /// The message has been included into a block candidate by the thread's collator.
+    IncludedIntoBlock {
+        message_id: String,
+        timestamp: u64,
+        json: Value,
+    },
+    /// The block candidate with the message has been accepted by the thread's validators.
+    IncludedIntoAcceptedBlock {
+        message_id: String,
+        timestamp: u64,
+        json: Value,
+    },
+    /// Some other minor REMP status.
+    Other {
+        message_id: String,
+        timestamp: u64,
+        json: Value,
+    },
+    /// Final status: the message has been rejected and will not be processed.
+    RejectedByFullnode {
+        message_id: String,
+        timestamp: u64,
+        /// Rejection reason reported by the node.
+        error: String,
+        json: Value,
+    },
+    /// Final status: the message has been processed and its transaction
+    /// is included into the finalized block.
+    Finalized {
+        message_id: String,
+        timestamp: u64,
+        /// Id of the block containing the message transaction.
+        block_id: Option<String>,
+        json: Value,
+    },
+}
+
+impl RempStatusUpdate {
+    pub fn message_id(&self) -> &str {
+        match self {
+            RempStatusUpdate::SentToValidators { message_id, .. }
+            | RempStatusUpdate::IncludedIntoBlock { message_id, .. }
+            | RempStatusUpdate::IncludedIntoAcceptedBlock { message_id, .. }
+            | RempStatusUpdate::Other { message_id, .. }
+            | RempStatusUpdate::RejectedByFullnode { message_id, .. }
+            | RempStatusUpdate::Finalized { message_id, .. } => message_id,
+        }
+    }
+
+    /// Returns `true` if no more statuses are expected for the message.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            RempStatusUpdate::RejectedByFullnode { .. } | RempStatusUpdate::Finalized { .. }
+        )
+    }
+}
+
+impl From<RempStatus> for RempStatusUpdate {
+    fn from(status: RempStatus) -> Self {
+        match status {
+            RempStatus::SentToValidators(RempStatusData { message_id, timestamp, json }) => {
+                RempStatusUpdate::SentToValidators { message_id, timestamp, json }
+            }
+            RempStatus::IncludedIntoBlock(RempStatusData { message_id, timestamp, json }) => {
+                RempStatusUpdate::IncludedIntoBlock { message_id, timestamp, json }
+            }
+            RempStatus::IncludedIntoAcceptedBlock(RempStatusData { message_id, timestamp, json }) => {
+                RempStatusUpdate::IncludedIntoAcceptedBlock { message_id, timestamp, json }
+            }
+            RempStatus::Other(RempStatusData { message_id, timestamp, json }) => {
+                RempStatusUpdate::Other { message_id, timestamp, json }
+            }
+            RempStatus::RejectedByFullnode(RempStatusData { message_id, timestamp, json }) => {
+                RempStatusUpdate::RejectedByFullnode {
+                    message_id,
+                    timestamp,
+                    error: json["error"].as_str().unwrap_or("unknown error").to_string(),
+                    json,
+                }
+            }
+            RempStatus::Finalized(RempStatusData { message_id, timestamp, json }) => {
+                RempStatusUpdate::Finalized {
+                    message_id,
+                    timestamp,
+                    block_id: json["block_id"].as_str().map(|x| x.to_string()),
+                    json,
+                }
+            }
+        }
+    }
+}
+
+fn parse_status(result: ResultOfSubscription) -> ClientResult<Option<RempStatusUpdate>> {
+    let receipt = &result.result["rempReceipts"];
+    if receipt.is_null() {
+        return Ok(None);
+    }
+    let status: RempStatus = serde_json::from_value(receipt.clone()).map_err(|err| {
+        Error::invalid_remp_status(format!("can not parse REMP status message: {}", err))
+    })?;
+    Ok(Some(status.into()))
+}
+
+/// Creates a subscription for the REMP statuses of the messages.
+///
+/// Unlike `process_message` and `wait_for_transaction`, which report REMP statuses
+/// of the message being processed, this function tracks any messages by their ids,
+/// including the messages sent by other clients.
+///
+/// Each status is delivered as `RempStatusUpdate`. `Finalized` and `RejectedByFullnode`
+/// are the final statuses: no more updates are delivered for the message after them.
+/// Subscription errors are delivered to the callback and do not stop the subscription.
+///
+/// The subscription must be closed with `net.unsubscribe` unless all the messages
+/// got their final statuses.
+pub async fn subscribe_remp_statuses<F: Future<Output = ()> + Send>(
+    context: Arc<ClientContext>,
+    params: ParamsOfSubscribeRempStatuses,
+    callback: impl Fn(ClientResult<RempStatusUpdate>) -> F + Send + Sync + 'static,
+) -> ClientResult<ResultOfSubscribeCollection> {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+
+    // GraphQL subscription can contain only one root field,
+    // so each message gets its own subscription.
+    let mut subscriptions = HashMap::new();
+    for message_id in params.message_ids {
+        if subscriptions.contains_key(&message_id) {
+            continue;
+        }
+        let sender = sender.clone();
+        let subscription = crate::net::subscribe(
+            context.clone(),
+            ParamsOfSubscribe {
+                subscription: r#"
+                    subscription rempReceipts($messageId: String!) {
+                        rempReceipts(messageId: $messageId) {
+                            messageId kind timestamp json
+                        }
+                    }
+                    "#
+                .to_string(),
+                variables: Some(json!({ "messageId": message_id })),
+            },
+            move |result: ClientResult<ResultOfSubscription>| {
+                let _ = sender.send(result);
+                futures::future::ready(())
+            },
+        )
+        .await;
+        match subscription {
+            Ok(subscription) => {
+                subscriptions.insert(message_id, subscription);
+            }
+            Err(err) => {
+                for (_, subscription) in subscriptions {
+                    let _ = crate::net::unsubscribe(context.clone(), subscription).await;
+                }
+                return Err(err);
+            }
+        }
+    }
+    drop(sender);
+
+    let (action_sender, mut action_receiver) = mpsc::channel(1);
+    let handle = rand::thread_rng().next_u32();
+    context.net.add_subscription_handle(handle, action_sender).await;
+
+    let env = context.env.clone();
+    env.spawn(async move {
+        while !subscriptions.is_empty() {
+            let result = futures::select! {
+                result = receiver.recv().fuse() => result,
+                // Subscription is removed with `unsubscribe`
+                _ = action_receiver.recv().fuse() => None,
+            };
+            let result = match result {
+                Some(result) => result,
+                None => break,
+            };
+            match result.and_then(parse_status) {
+                // Statuses received after the final one are skipped
+                Ok(Some(status)) if !subscriptions.contains_key(status.message_id()) => {}
+                Ok(Some(status)) => {
+                    if status.is_final() {
+                        if let Some(subscription) = subscriptions.remove(status.message_id()) {
+                            let _ = crate::net::unsubscribe(context.clone(), subscription).await;
+                        }
+                    }
+                    callback(Ok(status)).await;
+                }
+                Ok(None) => {}
+                Err(err) => callback(Err(err)).await,
+            }
+        }
+        for (_, subscription) in subscriptions {
+            let _ = crate::net::unsubscribe(context.clone(), subscription).await;
+        }
+        // All messages got final statuses (or there were no messages at all),
+        // so the handle is not kept until `unsubscribe`
+        context.net.extract_subscription_handle(&handle).await;
+    });
+
+    Ok(ResultOfSubscribeCollection { handle })
+}
@@ -20,8 +20,10 @@ use crate::encoding::{base64_decode, hex_decode};
 use crate::error::{AddNetworkUrl, ClientResult};
 use crate::net::EndpointStat;
 use crate::processing::internal::get_message_expiration_time;
+use crate::processing::outbox::{Outbox, OutboxMessage};
 use crate::processing::types::ProcessingEvent;
 use crate::processing::Error;
+use serde_json::Value;
 use std::sync::Arc;
 use ton_block::{Message, MsgAddressInt};
 
@@ -48,6 +50,12 @@ pub struct ParamsOfSendMessage {
     /// Default is `false`.
     #[serde(default)]
     pub send_events: bool,
+
+    /// User defined data associated with this message.
+    ///
+    /// This value is stored in the outbox together with the message
+    /// if the `network.message_outbox` config parameter is `true`.
+    pub user_data: Option<Value>,
 }
 
 #[derive(Serialize, Deserialize, ApiType, Default, PartialEq, Debug)]
@@ -170,6 +178,7 @@ impl SendingMessage {
                 futures.push(Box::pin(async move {
                     let result = message.send_to_address(context.clone(), address).await;
                     if result.is_err() {
+                        context.processing_stats.endpoint_failed(address);
                         context
                             .get_server_link()?
                             .update_stat(&[address.to_owned()], EndpointStat::MessageUndelivered)
@@ -233,7 +242,35 @@ pub async fn send_message<F: futures::Future<Output = ()> + Send>(
     };
 
     let shard_block_id = message.prepare_to_send(&context, &callback).await?;
+    let mut outbox_message = None;
+    if Outbox::is_enabled(&context) {
+        let mut record =
+            OutboxMessage::new(&context, &params.message, params.abi.as_ref(), params.user_data)?;
+        record.shard_block_id = Some(shard_block_id.clone());
+        context.outbox.put(&context, &record).await?;
+        outbox_message = Some(record);
+    }
     let result = message.send(&context).await;
+    match &result {
+        Ok(_) => context
+            .processing_stats
+            .message_sent(&message.id, context.env.now_ms()),
+        Err(_) => context.processing_stats.send_failed(),
+    }
+    // The message is already sent (or definitely not sent), so outbox failures
+    // must not hide the sending result from the application
+    if let Some(mut record) = outbox_message {
+        let updated = match &result {
+            Ok(sending_endpoints) => {
+                record.sending_endpoints = Some(sending_endpoints.clone());
+                context.outbox.put(&context, &record).await
+            }
+            Err(_) => context.outbox.remove(&context, &record.message_id).await,
+        };
+        if let Err(err) = updated {
+            log::warn!("Can not update message in the outbox: {}", err);
+        }
+    }
     if let Some(callback) = &callback {
         callback(match &result {
             Ok(_) => ProcessingEvent::DidSend {
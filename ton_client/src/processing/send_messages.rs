@@ -12,24 +12,51 @@
  *
  */
 
+use crate::abi::ParamsOfEncodeMessage;
 use crate::client::ClientContext;
 use crate::error::{AddNetworkUrl, ClientResult};
+use crate::processing::internal::{can_retry_expired_message, get_message_expiration_time};
+use crate::processing::outbox::{Outbox, OutboxMessage};
+use crate::processing::Error;
 use serde_json::Value;
-use std::sync::Arc;
-use ton_client_processing::{MessageMonitoringParams, MonitoredMessage};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use ton_client_processing::{
+    MessageMonitoringParams, MessageMonitoringResult, MessageMonitoringStatus, MonitoredMessage,
+};
 
 #[derive(Serialize, Deserialize, ApiType, Default, Debug, Clone)]
 pub struct MessageSendingParams {
     /// BOC of the message, that must be sent to the blockchain.
+    ///
+    /// Must be empty if `encode_params` is specified.
+    #[serde(default)]
     pub boc: String,
 
     /// Expiration time of the message.
     /// Must be specified as a UNIX timestamp in seconds.
+    ///
+    /// If the message is specified with `encode_params` and the contract's ABI
+    /// includes `expire` header, the header value is used instead.
     pub wait_until: u32,
 
     /// User defined data associated with this message.
     /// Helps to identify this message when user received `MessageMonitoringResult`.
     pub user_data: Option<Value>,
+
+    /// Message encode parameters.
+    ///
+    /// If specified, the library encodes the message itself. When such a message
+    /// is expired without being processed, the library encodes it again with the new
+    /// `expire` header, signs it and sends it again. The retry configuration is the same
+    /// as for `process_message`.
+    ///
+    /// Retries are performed only if `monitor_queue` is specified. The application
+    /// receives the monitoring result of the last sent message only, with the same `user_data`.
+    ///
+    /// Retry parameters are kept in memory only, so such messages can not be sent with
+    /// `monitor_queue` when `persistent_message_monitor` is enabled.
+    pub encode_params: Option<ParamsOfEncodeMessage>,
 }
 
 #[derive(Serialize, Deserialize, ApiType, Default, Debug, Clone)]
@@ -48,16 +75,73 @@ pub struct ResultOfSendMessages {
     pub messages: Vec<MessageMonitoringParams>,
 }
 
-#[api_function]
-/// Sends specified messages to the blockchain.
-pub async fn send_messages(
-    context: Arc<ClientContext>,
-    params: ParamsOfSendMessages,
-) -> ClientResult<ResultOfSendMessages> {
+struct ResendingMessage {
+    encode_params: ParamsOfEncodeMessage,
+    try_index: u8,
+    queue: String,
+}
+
+/// Messages sent with `encode_params` that will be sent again on expiration.
+#[derive(Default)]
+pub(crate) struct ResendingMessages {
+    pub(super) messages: Mutex<HashMap<String, ResendingMessage>>,
+}
+
+impl ResendingMessages {
+    /// Forgets the messages of the cancelled monitoring queue so they are not sent again.
+    pub(crate) fn cancel_queue(&self, queue: &str) {
+        self.messages
+            .lock()
+            .unwrap()
+            .retain(|_, message| message.queue != queue);
+    }
+}
+
+struct EncodedMessage {
+    boc: String,
+    hash: String,
+    expire: Option<u32>,
+}
+
+async fn encode_message(
+    context: &Arc<ClientContext>,
+    encode_params: &ParamsOfEncodeMessage,
+    try_index: u8,
+) -> ClientResult<EncodedMessage> {
+    let mut encode_params = encode_params.clone();
+    encode_params.processing_try_index = Some(try_index);
+    let abi = encode_params.abi.clone();
+    let message = crate::abi::encode_message(context.clone(), encode_params).await?;
+    let expire = get_message_expiration_time(context.clone(), Some(&abi), &message.message)?
+        .map(|expire| (expire / 1000) as u32);
+    Ok(EncodedMessage {
+        boc: message.message,
+        hash: message.message_id,
+        expire,
+    })
+}
+
+async fn send_and_monitor(
+    context: &Arc<ClientContext>,
+    messages: Vec<MessageSendingParams>,
+    monitor_queue: Option<&str>,
+) -> ClientResult<Vec<MessageMonitoringParams>> {
     let server_link = context.get_server_link()?;
     let endpoint = server_link.state().get_query_endpoint().await?;
-    let messages = params
-        .messages
+    let mut outbox_messages = Vec::new();
+    if Outbox::is_enabled(context) {
+        for message in &messages {
+            let record =
+                OutboxMessage::new(context, &message.boc, None, message.user_data.clone())?;
+            let record = OutboxMessage {
+                expire: Some(message.wait_until),
+                ..record
+            };
+            context.outbox.put(context, &record).await?;
+            outbox_messages.push(record.message_id);
+        }
+    }
+    let bocs = messages
         .iter()
         .map(|x| {
             context
@@ -66,13 +150,29 @@ pub async fn send_messages(
                 .unwrap()
         })
         .collect();
-    server_link
-        .send_messages(messages, Some(&endpoint))
+    let sent = server_link
+        .send_messages(bocs, Some(&endpoint))
         .await
-        .add_endpoint_from_context(&context, &endpoint)
-        .await?;
-    let messages = params
-        .messages
+        .add_endpoint_from_context(context, &endpoint)
+        .await;
+    if let Err(err) = sent {
+        context.processing_stats.send_failed();
+        for message_id in outbox_messages {
+            if let Err(remove_err) = context.outbox.remove(context, &message_id).await {
+                log::warn!("Can not remove message from the outbox: {}", remove_err);
+            }
+        }
+        return Err(err);
+    }
+    let now = context.env.now_ms();
+    for message in &messages {
+        if let Ok((hash, _)) = context.bocs.resolve_boc_with_hash(&message.boc, "message") {
+            context
+                .processing_stats
+                .message_sent(&hash.as_hex_string(), now);
+        }
+    }
+    let messages = messages
         .into_iter()
         .map(|x| MessageMonitoringParams {
             message: MonitoredMessage::Boc { boc: x.boc },
@@ -80,10 +180,145 @@ pub async fn send_messages(
             user_data: x.user_data,
         })
         .collect::<Vec<_>>();
-    if let Some(queue) = params.monitor_queue {
+    if let Some(queue) = monitor_queue {
         context
             .message_monitor
-            .monitor_messages(&queue, messages.clone())?;
+            .monitor_messages(queue, messages.clone())?;
+    }
+    Ok(messages)
+}
+
+#[api_function]
+/// Sends specified messages to the blockchain.
+pub async fn send_messages(
+    context: Arc<ClientContext>,
+    params: ParamsOfSendMessages,
+) -> ClientResult<ResultOfSendMessages> {
+    // Resending state would be lost on restart while the monitor queue is restored
+    if params.monitor_queue.is_some()
+        && context.config.network.persistent_message_monitor
+        && params.messages.iter().any(|x| x.encode_params.is_some())
+    {
+        return Err(Error::resending_not_persisted());
+    }
+    let mut messages = Vec::new();
+    let mut resending = Vec::new();
+    for message in params.messages {
+        let encode_params = match message.encode_params {
+            Some(encode_params) => encode_params,
+            None => {
+                messages.push(message);
+                continue;
+            }
+        };
+        let encoded = encode_message(&context, &encode_params, 0).await?;
+        if let (Some(queue), Some(_)) = (&params.monitor_queue, encoded.expire) {
+            resending.push((
+                encoded.hash,
+                ResendingMessage {
+                    encode_params,
+                    try_index: 0,
+                    queue: queue.clone(),
+                },
+            ));
+        }
+        messages.push(MessageSendingParams {
+            boc: encoded.boc,
+            wait_until: encoded.expire.unwrap_or(message.wait_until),
+            user_data: message.user_data,
+            encode_params: None,
+        });
+    }
+
+    let hashes = resending
+        .iter()
+        .map(|(hash, _)| hash.clone())
+        .collect::<Vec<_>>();
+    context
+        .resending_messages
+        .messages
+        .lock()
+        .unwrap()
+        .extend(resending);
+    match send_and_monitor(&context, messages, params.monitor_queue.as_deref()).await {
+        Ok(messages) => Ok(ResultOfSendMessages { messages }),
+        Err(err) => {
+            let mut resending = context.resending_messages.messages.lock().unwrap();
+            for hash in hashes {
+                resending.remove(&hash);
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Sends again the expired messages which were sent with `encode_params`.
+/// Returns the monitoring results that must be passed to the application.
+pub(crate) async fn resend_expired_messages(
+    context: &Arc<ClientContext>,
+    results: Vec<MessageMonitoringResult>,
+) -> Vec<MessageMonitoringResult> {
+    let mut passed = Vec::new();
+    for mut result in results {
+        let resending = context
+            .resending_messages
+            .messages
+            .lock()
+            .unwrap()
+            .remove(&result.hash);
+        let resending = match resending {
+            Some(resending)
+                if result.status == MessageMonitoringStatus::Timeout
+                    && can_retry_expired_message(context, resending.try_index) =>
+            {
+                resending
+            }
+            _ => {
+                passed.push(result);
+                continue;
+            }
+        };
+        if let Err(err) = resend_message(context, resending, result.user_data.clone()).await {
+            result.error = Some(format!(
+                "{}. Message can not be sent again: {}",
+                result.error.unwrap_or_default(),
+                err.message
+            ));
+            passed.push(result);
+        }
+    }
+    passed
+}
+
+async fn resend_message(
+    context: &Arc<ClientContext>,
+    resending: ResendingMessage,
+    user_data: Option<Value>,
+) -> ClientResult<()> {
+    let try_index = resending.try_index.checked_add(1).unwrap_or(resending.try_index);
+    let encoded = encode_message(context, &resending.encode_params, try_index).await?;
+    let message = MessageSendingParams {
+        boc: encoded.boc,
+        wait_until: encoded.expire.unwrap_or_default(),
+        user_data,
+        encode_params: None,
+    };
+    let queue = resending.queue.clone();
+    context.resending_messages.messages.lock().unwrap().insert(
+        encoded.hash.clone(),
+        ResendingMessage {
+            try_index,
+            ..resending
+        },
+    );
+    if let Err(err) = send_and_monitor(context, vec![message], Some(&queue)).await {
+        context
+            .resending_messages
+            .messages
+            .lock()
+            .unwrap()
+            .remove(&encoded.hash);
+        return Err(err);
     }
-    Ok(ResultOfSendMessages { messages })
+    Ok(())
 }
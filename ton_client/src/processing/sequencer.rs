@@ -0,0 +1,292 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::abi::{CallSet, FunctionHeader, ParamsOfEncodeMessage, Signer};
+use crate::client::ClientContext;
+use crate::error::{AddNetworkUrl, ClientResult};
+use crate::processing::process_message::can_retry_expired_message_error;
+use crate::processing::{
+    send_message, wait_for_transaction, Error, ParamsOfSendMessage, ParamsOfWaitForTransaction,
+    ProcessingEvent, ResultOfProcessMessage, ResultOfSendMessage,
+};
+
+/// Time in ms after which the sequencer of the account without queued messages is removed.
+pub(crate) const IDLE_SEQUENCER_TIMEOUT: u64 = 60_000;
+
+#[derive(Default)]
+pub(crate) struct AccountSequencer {
+    /// Serializes creation and sending of the account messages.
+    /// Contains the `time` header of the last created message.
+    last_time: Mutex<u64>,
+    queued: AtomicU32,
+    processed: AtomicU64,
+    failed: AtomicU64,
+    retries: AtomicU64,
+    /// Time of the last queue change.
+    used_at: AtomicU64,
+}
+
+impl AccountSequencer {
+    fn is_idle(self: &Arc<Self>, now: u64) -> bool {
+        // The sequencer held by somebody can get new messages
+        Arc::strong_count(self) == 1
+            && self.queued.load(Ordering::Relaxed) == 0
+            && now.saturating_sub(self.used_at.load(Ordering::Relaxed)) >= IDLE_SEQUENCER_TIMEOUT
+    }
+}
+
+/// Per-account message sequencers.
+///
+/// Sequencers of the accounts which have no messages for `IDLE_SEQUENCER_TIMEOUT`
+/// are removed when the sequencer for a new account is created.
+#[derive(Default)]
+pub(crate) struct Sequencers {
+    accounts: std::sync::Mutex<HashMap<String, Arc<AccountSequencer>>>,
+}
+
+impl Sequencers {
+    pub(crate) fn get(&self, address: &str, now: u64) -> Arc<AccountSequencer> {
+        let mut accounts = self.accounts.lock().unwrap();
+        if let Some(sequencer) = accounts.get(address) {
+            sequencer.used_at.store(now, Ordering::Relaxed);
+            return sequencer.clone();
+        }
+        accounts.retain(|_, sequencer| !sequencer.is_idle(now));
+        let sequencer = Arc::new(AccountSequencer {
+            used_at: AtomicU64::new(now),
+            ..Default::default()
+        });
+        accounts.insert(address.to_string(), sequencer.clone());
+        sequencer
+    }
+
+    pub(crate) fn find(&self, address: &str) -> Option<Arc<AccountSequencer>> {
+        self.accounts.lock().unwrap().get(address).cloned()
+    }
+}
+
+async fn resolve_account_address(
+    context: &Arc<ClientContext>,
+    encode_params: &ParamsOfEncodeMessage,
+) -> ClientResult<String> {
+    let address = match &encode_params.address {
+        Some(address) => address.clone(),
+        // Deploy message address is calculated by the encoder
+        None => {
+            crate::abi::encode_message(context.clone(), encode_params.clone())
+                .await?
+                .address
+        }
+    };
+    Ok(crate::encoding::account_decode(&address)?.to_string())
+}
+
+/// Returns unique `time` header value that is greater than the previous one.
+fn next_time(context: &ClientContext, last_time: &mut u64) -> u64 {
+    let time = context.env.now_ms().max(*last_time + 1);
+    *last_time = time;
+    time
+}
+
+fn with_time(encode_params: &ParamsOfEncodeMessage, time: u64) -> ParamsOfEncodeMessage {
+    let mut encode_params = encode_params.clone();
+    let call_set = encode_params
+        .call_set
+        .get_or_insert_with(|| CallSet::default());
+    let header = call_set.header.get_or_insert_with(|| FunctionHeader::default());
+    header.time = Some(time);
+    encode_params
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug)]
+pub struct ParamsOfProcessSequencedMessage {
+    /// Message encode parameters.
+    ///
+    /// `time` header is always set by the sequencer, so the value from the
+    /// `call_set.header` is ignored. Signer must be able to sign the message,
+    /// so the `External` signer is not allowed.
+    pub message_encode_params: ParamsOfEncodeMessage,
+
+    /// Flag for requesting events sending.
+    /// Default is `false`.
+    #[serde(default)]
+    pub send_events: bool,
+}
+
+/// Processes message in the queue of the destination account.
+///
+/// Messages to the same account are created and sent one by one in the order of the calls:
+/// the next message is created only after the previous one is sent. Waiting for the
+/// transactions doesn't block the queue, so the messages are processed concurrently.
+/// Each message gets the `time` header greater than the `time` of the previous message,
+/// so the contract replay protection does not reject messages created within
+/// the same millisecond.
+///
+/// If the message expires, the sequencer creates it again with the new `time` and
+/// `expire` headers and signs it with the specified signer, like `process_message` does.
+pub async fn process_sequenced_message<F: futures::Future<Output = ()> + Send>(
+    context: Arc<ClientContext>,
+    params: ParamsOfProcessSequencedMessage,
+    callback: impl Fn(ProcessingEvent) -> F + Send + Sync + 'static,
+) -> ClientResult<ResultOfProcessMessage> {
+    if let Signer::External { .. } = params.message_encode_params.signer {
+        return Err(Error::external_signer_must_not_be_used());
+    }
+    let address = resolve_account_address(&context, &params.message_encode_params).await?;
+    let sequencer = context.sequencers.get(&address, context.env.now_ms());
+
+    sequencer.queued.fetch_add(1, Ordering::Relaxed);
+    let result = process_in_sequence(&context, &params, &callback, &sequencer).await;
+    sequencer.used_at.store(context.env.now_ms(), Ordering::Relaxed);
+    sequencer.queued.fetch_sub(1, Ordering::Relaxed);
+
+    match &result {
+        Ok(_) => sequencer.processed.fetch_add(1, Ordering::Relaxed),
+        Err(_) => sequencer.failed.fetch_add(1, Ordering::Relaxed),
+    };
+    result
+}
+
+async fn process_in_sequence<F: futures::Future<Output = ()> + Send>(
+    context: &Arc<ClientContext>,
+    params: &ParamsOfProcessSequencedMessage,
+    callback: &(impl Fn(ProcessingEvent) -> F + Send + Sync + 'static),
+    sequencer: &AccountSequencer,
+) -> ClientResult<ResultOfProcessMessage> {
+    let abi = params.message_encode_params.abi.clone();
+    let mut try_index = 0;
+    loop {
+        // The queue is locked only until the message is sent,
+        // so the next message can't be sent before the previous one
+        let mut last_time = sequencer.last_time.lock().await;
+        // Each try gets the new `time` so the message is unique for the replay protection
+        let mut encode_params =
+            with_time(&params.message_encode_params, next_time(context, &mut last_time));
+        encode_params.processing_try_index = Some(try_index);
+        let message = crate::abi::encode_message(context.clone(), encode_params).await?;
+
+        let ResultOfSendMessage {
+            shard_block_id,
+            sending_endpoints,
+        } = send_message(
+            context.clone(),
+            ParamsOfSendMessage {
+                message: message.message.clone(),
+                abi: Some(abi.clone()),
+                send_events: params.send_events,
+                user_data: None,
+            },
+            callback,
+        )
+        .await
+        .add_network_url_from_context(context)
+        .await?;
+        drop(last_time);
+
+        let wait_for = wait_for_transaction(
+            context.clone(),
+            ParamsOfWaitForTransaction {
+                message: message.message.clone(),
+                send_events: params.send_events,
+                abi: Some(abi.clone()),
+                shard_block_id,
+                sending_endpoints: Some(sending_endpoints),
+            },
+            callback,
+        )
+        .await
+        .add_network_url_from_context(context)
+        .await;
+
+        match wait_for {
+            Ok(output) => return Ok(output),
+            Err(err) => {
+                if !can_retry_expired_message_error(context, &err, try_index) {
+                    return Err(err);
+                }
+                if params.send_events {
+                    callback(ProcessingEvent::MessageExpired {
+                        message_id: message.message_id,
+                        message_dst: message.address,
+                        message: message.message,
+                        error: err,
+                    })
+                    .await;
+                }
+                sequencer.retries.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        try_index = try_index.checked_add(1).unwrap_or(try_index);
+    }
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfGetSequencerInfo {
+    /// Account address.
+    pub address: String,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone, Debug, PartialEq)]
+pub struct ResultOfGetSequencerInfo {
+    /// Number of the messages in the account queue including the messages being processed.
+    pub queued: u32,
+
+    /// Number of successfully processed messages.
+    pub processed: u64,
+
+    /// Number of messages failed to process.
+    pub failed: u64,
+
+    /// Number of the message retries performed after the message expiration.
+    pub retries: u64,
+
+    /// `time` header of the last created message.
+    ///
+    /// Missing if the account queue is sending a message right now
+    /// or no messages were sent yet.
+    pub last_time: Option<u64>,
+}
+
+/// Returns the state of the account message queue used by `process_sequenced_message`.
+///
+/// The queue state is reset after the queue has no messages for a minute.
+#[api_function]
+pub async fn get_sequencer_info(
+    context: Arc<ClientContext>,
+    params: ParamsOfGetSequencerInfo,
+) -> ClientResult<ResultOfGetSequencerInfo> {
+    let address = crate::encoding::account_decode(&params.address)?.to_string();
+    let sequencer = match context.sequencers.find(&address) {
+        Some(sequencer) => sequencer,
+        None => return Ok(ResultOfGetSequencerInfo::default()),
+    };
+    let last_time = sequencer
+        .last_time
+        .try_lock()
+        .ok()
+        .map(|time| *time)
+        .filter(|time| *time != 0);
+    Ok(ResultOfGetSequencerInfo {
+        queued: sequencer.queued.load(Ordering::Relaxed),
+        processed: sequencer.processed.load(Ordering::Relaxed),
+        failed: sequencer.failed.load(Ordering::Relaxed),
+        retries: sequencer.retries.load(Ordering::Relaxed),
+        last_time,
+    })
+}
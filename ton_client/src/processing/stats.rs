@@ -0,0 +1,319 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::client::ClientContext;
+use crate::error::ClientResult;
+use crate::processing::ErrorCode;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+/// Sending times are kept for the messages which results are not received yet.
+/// Outdated times are dropped when there are too many such messages.
+const MAX_PENDING_MESSAGES: usize = 10000;
+const PENDING_MESSAGE_TTL_MS: u64 = 10 * 60 * 1000;
+
+const METRICS_PREFIX: &str = "ever_sdk_processing";
+
+#[derive(Default)]
+struct Latency {
+    count: u64,
+    total_ms: u64,
+    max_ms: u64,
+}
+
+impl Latency {
+    fn add(&mut self, ms: u64) {
+        self.count += 1;
+        self.total_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+    }
+
+    fn stats(&self) -> LatencyStats {
+        LatencyStats {
+            count: self.count,
+            average_ms: if self.count > 0 { self.total_ms / self.count } else { 0 },
+            max_ms: self.max_ms,
+        }
+    }
+}
+
+#[derive(Default)]
+struct StatsState {
+    messages_sent: u64,
+    send_failures: u64,
+    transactions_received: u64,
+    expirations: u64,
+    wait_timeouts: u64,
+    processing_failures: u64,
+    time_to_transaction: Latency,
+    remp_statuses: BTreeMap<String, Latency>,
+    endpoint_failures: BTreeMap<String, u64>,
+    sending_times: HashMap<String, u64>,
+}
+
+impl StatsState {
+    fn time_since_sending(&self, message_id: &str, now: u64) -> Option<u64> {
+        self.sending_times
+            .get(message_id)
+            .map(|time| now.saturating_sub(*time))
+    }
+}
+
+/// Processing metrics collected by the client context.
+#[derive(Default)]
+pub(crate) struct ProcessingStats {
+    state: Mutex<StatsState>,
+}
+
+impl ProcessingStats {
+    pub fn message_sent(&self, message_id: &str, now: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.messages_sent += 1;
+        if state.sending_times.len() >= MAX_PENDING_MESSAGES {
+            state
+                .sending_times
+                .retain(|_, time| *time + PENDING_MESSAGE_TTL_MS > now);
+        }
+        state.sending_times.insert(message_id.to_string(), now);
+    }
+
+    pub fn send_failed(&self) {
+        self.state.lock().unwrap().send_failures += 1;
+    }
+
+    pub fn endpoint_failed(&self, endpoint: &str) {
+        *self
+            .state
+            .lock()
+            .unwrap()
+            .endpoint_failures
+            .entry(endpoint.to_string())
+            .or_default() += 1;
+    }
+
+    pub fn remp_status(&self, message_id: &str, kind: &str, now: u64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(latency) = state.time_since_sending(message_id, now) {
+            state
+                .remp_statuses
+                .entry(kind.to_string())
+                .or_default()
+                .add(latency);
+        }
+    }
+
+    pub fn transaction_received(&self, message_id: &str, now: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.transactions_received += 1;
+        if let Some(latency) = state.time_since_sending(message_id, now) {
+            state.time_to_transaction.add(latency);
+        }
+        state.sending_times.remove(message_id);
+    }
+
+    pub fn expired(&self, message_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.expirations += 1;
+        state.sending_times.remove(message_id);
+    }
+
+    pub fn wait_timeout(&self, message_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.wait_timeouts += 1;
+        state.sending_times.remove(message_id);
+    }
+
+    pub fn failed(&self, message_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.processing_failures += 1;
+        state.sending_times.remove(message_id);
+    }
+
+    /// Registers the result of the message waiting.
+    pub fn processing_finished<T>(&self, message_id: &str, result: &ClientResult<T>, now: u64) {
+        match result {
+            Ok(_) => self.transaction_received(message_id, now),
+            Err(err) if err.code == ErrorCode::MessageExpired as u32 => self.expired(message_id),
+            // The message can still be processed, so the timeout is not an expiration
+            Err(err) if err.code == ErrorCode::TransactionWaitTimeout as u32 => {
+                self.wait_timeout(message_id)
+            }
+            Err(_) => self.failed(message_id),
+        }
+    }
+
+    pub fn get(&self) -> ResultOfGetStats {
+        let state = self.state.lock().unwrap();
+        ResultOfGetStats {
+            messages_sent: state.messages_sent,
+            send_failures: state.send_failures,
+            transactions_received: state.transactions_received,
+            expirations: state.expirations,
+            wait_timeouts: state.wait_timeouts,
+            processing_failures: state.processing_failures,
+            time_to_transaction: state.time_to_transaction.stats(),
+            remp_statuses: state
+                .remp_statuses
+                .iter()
+                .map(|(kind, latency)| RempStatusStats {
+                    kind: kind.clone(),
+                    latency: latency.stats(),
+                })
+                .collect(),
+            endpoint_failures: state
+                .endpoint_failures
+                .iter()
+                .map(|(endpoint, failures)| EndpointFailures {
+                    endpoint: endpoint.clone(),
+                    failures: *failures,
+                })
+                .collect(),
+            prometheus: None,
+        }
+    }
+
+    /// Returns metrics in the Prometheus text exposition format.
+    pub fn prometheus(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut text = String::new();
+        let counters = [
+            ("messages_sent_total", state.messages_sent),
+            ("send_failures_total", state.send_failures),
+            ("transactions_received_total", state.transactions_received),
+            ("expirations_total", state.expirations),
+            ("wait_timeouts_total", state.wait_timeouts),
+            ("processing_failures_total", state.processing_failures),
+        ];
+        for (name, value) in counters.iter() {
+            let _ = writeln!(text, "# TYPE {}_{} counter", METRICS_PREFIX, name);
+            let _ = writeln!(text, "{}_{} {}", METRICS_PREFIX, name, value);
+        }
+
+        let _ = writeln!(text, "# TYPE {}_time_to_transaction_ms summary", METRICS_PREFIX);
+        write_latency(&mut text, "time_to_transaction_ms", "", &state.time_to_transaction);
+
+        let _ = writeln!(text, "# TYPE {}_remp_status_latency_ms summary", METRICS_PREFIX);
+        for (kind, latency) in &state.remp_statuses {
+            let labels = format!("{{kind=\"{}\"}}", kind);
+            write_latency(&mut text, "remp_status_latency_ms", &labels, latency);
+        }
+
+        let _ = writeln!(text, "# TYPE {}_endpoint_failures_total counter", METRICS_PREFIX);
+        for (endpoint, failures) in &state.endpoint_failures {
+            let _ = writeln!(
+                text,
+                "{}_endpoint_failures_total{{endpoint=\"{}\"}} {}",
+                METRICS_PREFIX,
+                endpoint.replace('\\', "\\\\").replace('"', "\\\""),
+                failures
+            );
+        }
+        text
+    }
+}
+
+fn write_latency(text: &mut String, name: &str, labels: &str, latency: &Latency) {
+    let _ = writeln!(text, "{}_{}_sum{} {}", METRICS_PREFIX, name, labels, latency.total_ms);
+    let _ = writeln!(text, "{}_{}_count{} {}", METRICS_PREFIX, name, labels, latency.count);
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct LatencyStats {
+    /// Number of measurements.
+    pub count: u64,
+
+    /// Average latency in milliseconds.
+    pub average_ms: u64,
+
+    /// Maximum latency in milliseconds.
+    pub max_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct RempStatusStats {
+    /// REMP status kind, e.g. `SentToValidators`.
+    pub kind: String,
+
+    /// Time between the message sending and receiving of the status.
+    pub latency: LatencyStats,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct EndpointFailures {
+    /// Endpoint URL.
+    pub endpoint: String,
+
+    /// Number of the message deliveries failed on this endpoint.
+    pub failures: u64,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Clone)]
+pub struct ParamsOfGetStats {
+    /// Return metrics in the Prometheus text format in the `prometheus` field.
+    /// Default is `false`.
+    #[serde(default)]
+    pub prometheus: bool,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfGetStats {
+    /// Number of successfully sent messages.
+    pub messages_sent: u64,
+
+    /// Number of messages failed to send.
+    pub send_failures: u64,
+
+    /// Number of received transactions of the sent messages.
+    pub transactions_received: u64,
+
+    /// Number of expired messages.
+    pub expirations: u64,
+
+    /// Number of messages which transactions were not found within the waiting timeout.
+    /// Such messages can still be processed later.
+    pub wait_timeouts: u64,
+
+    /// Number of messages failed to process because of other errors.
+    pub processing_failures: u64,
+
+    /// Time between the message sending and receiving of its transaction.
+    pub time_to_transaction: LatencyStats,
+
+    /// Time between the message sending and receiving of REMP statuses.
+    pub remp_statuses: Vec<RempStatusStats>,
+
+    /// Failed message deliveries by endpoints.
+    pub endpoint_failures: Vec<EndpointFailures>,
+
+    /// Metrics in the Prometheus text exposition format.
+    ///
+    /// Returned only if `prometheus` is requested.
+    pub prometheus: Option<String>,
+}
+
+/// Returns processing metrics collected by the client context.
+///
+/// Metrics are collected by `send_message`, `wait_for_transaction`, `process_message`,
+/// `send_messages` and the message monitor regardless of the `send_events` flag.
+#[api_function]
+pub fn get_stats(
+    context: Arc<ClientContext>,
+    params: ParamsOfGetStats,
+) -> ClientResult<ResultOfGetStats> {
+    let mut result = context.processing_stats.get();
+    if params.prometheus {
+        result.prometheus = Some(context.processing_stats.prometheus());
+    }
+    Ok(result)
+}
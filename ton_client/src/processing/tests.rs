@@ -6,19 +6,39 @@ use crate::boc::internal::deserialize_object_from_cell;
 use crate::boc::tvc::resolve_state_init_cell;
 use crate::json_interface::modules::ProcessingModule;
 use crate::net::{ParamsOfQuery, ResultOfQuery};
+use crate::processing::blocks_walking::wait_next_block;
+use crate::processing::outbox::{complete_outbox_message, OutboxMessage};
+use crate::processing::sequencer::IDLE_SEQUENCER_TIMEOUT;
+use crate::processing::transaction_tree::wait_transaction_tree;
 use crate::processing::types::DecodedOutput;
 use crate::processing::{
-    ErrorCode, ParamsOfProcessMessage, ParamsOfSendMessage, ParamsOfWaitForTransaction,
-    ProcessingEvent, ProcessingResponseType,
+    cancel_monitor, fetch_next_monitor_results, get_outbox, get_sequencer_info, monitor_messages,
+    process_sequenced_message, resume_outbox, send_messages, DryRunOptions, ErrorCode,
+    MessageSendingParams, ParamsOfCancelMonitor, ParamsOfFetchNextMonitorResults,
+    ParamsOfGetSequencerInfo, ParamsOfMonitorMessages, ParamsOfProcessMessage,
+    ParamsOfProcessSequencedMessage,
+    ParamsOfResumeOutbox, ParamsOfSendMessage, ParamsOfSendMessages, ParamsOfWaitForTransaction,
+    ParamsOfGetStats, ParamsOfSubscribeRempStatuses, ProcessingEvent, ProcessingResponseType,
+    RempStatusUpdate, ResultOfGetStats, SdkServices, Sequencers, TransactionTreeOptions,
+    subscribe_remp_statuses,
 };
 use crate::tests::GIVER_V2;
 use crate::tests::{TestClient, EVENTS_OLD, HELLO};
 use crate::tvm::ErrorCode as TvmErrorCode;
 use crate::tvm::{AccountForExecutor, ParamsOfRunExecutor, ResultOfRunExecutor};
 use crate::utils::conversion::abi_uint;
+use crate::client::NetworkMock;
+use crate::error::{ClientError, ClientResult};
+use crate::net::NetworkConfig;
+use crate::{ClientConfig, ClientContext};
 use api_info::ApiModule;
 use ever_struct::scheme::TVC;
+use serde_json::Value;
+use std::sync::Arc;
 use ton_block::{Serializable, StateInit};
+use ton_client_processing::{
+    MessageMonitoringParams, MessageMonitoringStatus, MonitorFetchWaitMode, MonitoredMessage,
+};
 
 fn processing_event_name(e: Option<&ProcessingEvent>) -> &str {
     if let Some(e) = e {
@@ -162,6 +182,7 @@ async fn test_wait_message() {
                 message: encoded.message.clone(),
                 send_events: true,
                 abi: Some(abi.clone()),
+                user_data: None,
             },
             callback.clone(),
         )
@@ -193,6 +214,119 @@ async fn test_wait_message() {
     assert_events(&events.lock().await, remp_enabled(&client).await);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_outbox() {
+    let storage_path =
+        std::env::temp_dir().join(format!("tonclient-{}", rand::random::<u32>()));
+    let client = TestClient::new_with_config(json!({
+        "network": {
+            "endpoints": TestClient::endpoints(),
+            "message_outbox": true,
+        },
+        "local_storage_path": storage_path.to_string_lossy(),
+    }));
+    let context = client.context();
+    let (abi, tvc) = TestClient::package(EVENTS_OLD, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let encoded = client
+        .encode_message(ParamsOfEncodeMessage {
+            abi: abi.clone(),
+            deploy_set: DeploySet::some_with_tvc(tvc),
+            call_set: CallSet::some_with_function_and_input(
+                "constructor",
+                json!({}),
+            ),
+            signer: Signer::Keys { keys },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    client
+        .get_tokens_from_giver_async(&encoded.address, None)
+        .await;
+
+    // Message is recorded before sending, so it stays in the outbox
+    // as if the application was restarted before waiting
+    let sent = crate::processing::send_message(
+        context.clone(),
+        ParamsOfSendMessage {
+            message: encoded.message.clone(),
+            abi: Some(abi.clone()),
+            send_events: false,
+            user_data: Some(json!({ "order": 1 })),
+        },
+        |_| futures::future::ready(()),
+    )
+    .await
+    .unwrap();
+
+    let outbox = get_outbox(context.clone()).await.unwrap();
+    assert_eq!(outbox.messages.len(), 1);
+    assert_eq!(outbox.messages[0].message_id, encoded.message_id);
+    assert_eq!(outbox.messages[0].shard_block_id, Some(sent.shard_block_id));
+    assert_eq!(outbox.messages[0].user_data, Some(json!({ "order": 1 })));
+
+    let resumed = resume_outbox(context.clone(), ParamsOfResumeOutbox::default())
+        .await
+        .unwrap();
+    assert_eq!(resumed.results.len(), 1);
+    assert!(resumed.results[0].result.is_some());
+    assert!(resumed.monitored.is_empty());
+
+    let outbox = get_outbox(context).await.unwrap();
+    assert!(outbox.messages.is_empty());
+    let _ = std::fs::remove_dir_all(storage_path);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_outbox_keeps_undefined_results() {
+    let storage_path =
+        std::env::temp_dir().join(format!("tonclient-{}", rand::random::<u32>()));
+    let client = TestClient::new_with_config(json!({
+        "network": {
+            "endpoints": TestClient::endpoints(),
+            "message_outbox": true,
+        },
+        "local_storage_path": storage_path.to_string_lossy(),
+    }));
+    let context = client.context();
+    let (abi, tvc) = TestClient::package(EVENTS_OLD, Some(2));
+
+    let encoded = client
+        .encode_message(ParamsOfEncodeMessage {
+            abi: abi.clone(),
+            deploy_set: DeploySet::some_with_tvc(tvc),
+            call_set: CallSet::some_with_function_and_input("constructor", json!({})),
+            signer: Signer::Keys {
+                keys: client.generate_sign_keys(),
+            },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let record = OutboxMessage::new(&context, &encoded.message, Some(&abi), None).unwrap();
+    context.outbox.put(&context, &record).await.unwrap();
+
+    let result: ClientResult<()> = Err(ClientError::with_code_message(
+        ErrorCode::TransactionWaitTimeout as u32,
+        "Transaction wait timeout".to_string(),
+    ));
+    complete_outbox_message(&context, &encoded.message, &result).await;
+    let outbox = get_outbox(context.clone()).await.unwrap();
+    assert_eq!(outbox.messages.len(), 1);
+    assert_eq!(outbox.messages[0].message_id, encoded.message_id);
+
+    let result: ClientResult<()> = Err(ClientError::with_code_message(
+        ErrorCode::MessageExpired as u32,
+        "Message expired".to_string(),
+    ));
+    complete_outbox_message(&context, &encoded.message, &result).await;
+    let outbox = get_outbox(context).await.unwrap();
+    assert!(outbox.messages.is_empty());
+    let _ = std::fs::remove_dir_all(storage_path);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_process_message() {
     TestClient::init_log();
@@ -238,6 +372,8 @@ async fn test_process_message() {
             ParamsOfProcessMessage {
                 message_encode_params: encode_params,
                 send_events: true,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             callback,
         )
@@ -283,6 +419,8 @@ async fn test_process_message() {
                     ..Default::default()
                 },
                 send_events: true,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             callback,
         )
@@ -315,6 +453,371 @@ async fn test_process_message() {
     assert_events(&events.lock().await, remp_enabled);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_process_message_dry_run() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(EVENTS_OLD, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let address = client
+        .deploy_with_giver_async(
+            ParamsOfEncodeMessage {
+                abi: abi.clone(),
+                deploy_set: DeploySet::some_with_tvc(tvc),
+                call_set: CallSet::some_with_function_and_input("constructor", json!({})),
+                signer: Signer::Keys { keys: keys.clone() },
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+
+    let call_params = |keys| ParamsOfEncodeMessage {
+        abi: abi.clone(),
+        address: Some(address.clone()),
+        call_set: CallSet::some_with_function_and_input("returnValue", json!({ "id": "0x1" })),
+        signer: Signer::Keys { keys },
+        ..Default::default()
+    };
+
+    let output = client
+        .net_process_message(
+            ParamsOfProcessMessage {
+                message_encode_params: call_params(keys.clone()),
+                send_events: false,
+                dry_run: Some(DryRunOptions {
+                    return_result: true,
+                }),
+                wait_transaction_tree: None,
+            },
+            TestClient::default_callback,
+        )
+        .await
+        .unwrap();
+    let dry_run = output.dry_run.unwrap();
+    assert_eq!(dry_run.decoded, output.decoded);
+    assert_eq!(dry_run.out_messages.len(), output.out_messages.len());
+    assert!(dry_run.fees.total_account_fees > 0);
+
+    // Message signed with the wrong keys is rejected by the contract, so it is not sent
+    let err = client
+        .net_process_message(
+            ParamsOfProcessMessage {
+                message_encode_params: call_params(client.generate_sign_keys()),
+                send_events: false,
+                dry_run: Some(DryRunOptions::default()),
+                wait_transaction_tree: None,
+            },
+            TestClient::default_callback,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.code, ErrorCode::DryRunFailed as u32);
+    assert!(!err.data["local_error"].is_null());
+    assert_eq!(err.data["account_address"], address);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_process_message_transaction_tree() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(GIVER_V2, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let address = client
+        .deploy_with_giver_async(
+            ParamsOfEncodeMessage {
+                abi: abi.clone(),
+                deploy_set: DeploySet::some_with_tvc(tvc),
+                call_set: CallSet::some_with_function("constructor"),
+                signer: Signer::Keys { keys: keys.clone() },
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+
+    // Transfer to the missing account is aborted and bounced back
+    let output = client
+        .net_process_message(
+            ParamsOfProcessMessage {
+                message_encode_params: ParamsOfEncodeMessage {
+                    abi: abi.clone(),
+                    address: Some(address.clone()),
+                    call_set: CallSet::some_with_function_and_input(
+                        "sendTransaction",
+                        json!({
+                            "dest": "0:0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+                            "value": 100_000_000,
+                            "bounce": true,
+                        }),
+                    ),
+                    signer: Signer::Keys { keys },
+                    ..Default::default()
+                },
+                send_events: false,
+                dry_run: None,
+                wait_transaction_tree: Some(TransactionTreeOptions {
+                    transaction_max_count: Some(0),
+                    ..Default::default()
+                }),
+            },
+            TestClient::default_callback,
+        )
+        .await
+        .unwrap();
+
+    let tree = output.transaction_tree.unwrap();
+    assert!(tree.complete);
+    assert_eq!(tree.transactions.len(), 3);
+    assert_eq!(tree.transactions[0].id, output.transaction["id"].as_str().unwrap());
+    assert_eq!(tree.aborted_transactions.len(), 1);
+    assert_eq!(tree.bounced_messages.len(), 1);
+    assert!(tree.messages[0].decoded_body.is_some());
+    assert!(u128::from_str_radix(tree.total_fees.trim_start_matches("0x"), 16).unwrap() > 0);
+    assert_eq!(tree.error, None);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_wait_transaction_tree_timeout() {
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+
+    NetworkMock::build()
+        .url("a")
+        .info(client.env.now_ms(), 1000)
+        .ok(&json!({
+            "data": {
+                "messages": [{
+                    "id": "m1",
+                    "dst": format!("0:{}", "3".repeat(64)),
+                    "msg_type": 1,
+                    "bounce": false,
+                    "bounced": false,
+                    "src_transaction": null,
+                    "dst_transaction": {
+                        "id": "t1",
+                        "aborted": false,
+                        "compute": { "exit_code": 0 },
+                        "total_fees": "0x10",
+                        "out_msgs": ["m2"],
+                    },
+                }]
+            }
+        })
+        .to_string())
+        // Out message of the root transaction doesn't appear
+        .repeat(10)
+        .ok(&json!({ "data": { "messages": [] } }).to_string())
+        .reset_client(&client)
+        .await;
+
+    let tree = wait_transaction_tree(
+        &client,
+        "m1",
+        &TestClient::abi(HELLO, Some(2)),
+        &TransactionTreeOptions {
+            timeout: Some(1500),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    assert!(!tree.complete);
+    assert_eq!(tree.error, None);
+    assert_eq!(tree.messages.len(), 1);
+    assert_eq!(tree.transactions.len(), 1);
+    assert_eq!(tree.transactions[0].id, "t1");
+    assert_eq!(tree.transactions[0].out_msgs, vec!["m2".to_string()]);
+    assert_eq!(tree.total_fees, "0x10");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_stats() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(EVENTS_OLD, Some(2));
+    let keys = client.generate_sign_keys();
+
+    client
+        .deploy_with_giver_async(
+            ParamsOfEncodeMessage {
+                abi,
+                deploy_set: DeploySet::some_with_tvc(tvc),
+                call_set: CallSet::some_with_function_and_input("constructor", json!({})),
+                signer: Signer::Keys { keys },
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+
+    let stats: ResultOfGetStats = client
+        .request_async("processing.get_stats", ParamsOfGetStats { prometheus: true })
+        .await
+        .unwrap();
+    // Giver and deploy messages
+    assert!(stats.messages_sent >= 2);
+    assert!(stats.transactions_received >= 2);
+    assert_eq!(stats.time_to_transaction.count, stats.transactions_received);
+    assert!(stats.time_to_transaction.max_ms >= stats.time_to_transaction.average_ms);
+    let prometheus = stats.prometheus.unwrap();
+    assert!(prometheus.contains(&format!(
+        "ever_sdk_processing_messages_sent_total {}",
+        stats.messages_sent
+    )));
+    assert!(prometheus.contains("ever_sdk_processing_time_to_transaction_ms_count"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_processing_metrics_address_released() {
+    let address = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .to_string();
+    let context = ClientContext::new(ClientConfig {
+        processing_metrics_address: Some(address.clone()),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(std::net::TcpListener::bind(&address).is_err());
+
+    drop(context);
+    let mut released = false;
+    for _ in 0..50 {
+        if std::net::TcpListener::bind(&address).is_ok() {
+            released = true;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(released);
+}
+
+#[test]
+fn test_stats_processing_results() {
+    let stats = crate::processing::stats::ProcessingStats::default();
+    let error = |code: ErrorCode| -> ClientResult<()> {
+        Err(ClientError::with_code_message(code as u32, String::new()))
+    };
+    stats.processing_finished("m1", &error(ErrorCode::MessageExpired), 0);
+    stats.processing_finished("m2", &error(ErrorCode::TransactionWaitTimeout), 0);
+    stats.processing_finished("m3", &error(ErrorCode::MessageRejected), 0);
+
+    let result = stats.get();
+    assert_eq!(result.expirations, 1);
+    assert_eq!(result.wait_timeouts, 1);
+    assert_eq!(result.processing_failures, 1);
+    assert!(stats
+        .prometheus()
+        .contains("ever_sdk_processing_wait_timeouts_total 1"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_process_sequenced_message() {
+    let client = TestClient::new();
+    let (abi, tvc) = TestClient::package(EVENTS_OLD, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let deploy_params = ParamsOfEncodeMessage {
+        abi: abi.clone(),
+        deploy_set: DeploySet::some_with_tvc(tvc),
+        call_set: Some(CallSet {
+            function_name: "constructor".into(),
+            header: Some(FunctionHeader {
+                pubkey: Some(keys.public.clone()),
+                ..Default::default()
+            }),
+            input: None,
+        }),
+        signer: Signer::Keys { keys: keys.clone() },
+        ..Default::default()
+    };
+    let address = client
+        .encode_message(deploy_params.clone())
+        .await
+        .unwrap()
+        .address;
+    client.get_tokens_from_giver_async(&address, None).await;
+
+    let context = client.context();
+    let no_events = |_: ProcessingEvent| futures::future::ready(());
+    process_sequenced_message(
+        context.clone(),
+        ParamsOfProcessSequencedMessage {
+            message_encode_params: deploy_params,
+            send_events: false,
+        },
+        no_events,
+    )
+    .await
+    .unwrap();
+
+    // Messages created at the same time must be processed one by one
+    let calls = (1..=3).map(|id| {
+        process_sequenced_message(
+            context.clone(),
+            ParamsOfProcessSequencedMessage {
+                message_encode_params: ParamsOfEncodeMessage {
+                    abi: abi.clone(),
+                    address: Some(address.clone()),
+                    call_set: CallSet::some_with_function_and_input(
+                        "returnValue",
+                        json!({ "id": id }),
+                    ),
+                    signer: Signer::Keys { keys: keys.clone() },
+                    ..Default::default()
+                },
+                send_events: false,
+            },
+            no_events,
+        )
+    });
+    for output in futures::future::join_all(calls).await {
+        assert_eq!(output.unwrap().out_messages.len(), 2);
+    }
+
+    let info = get_sequencer_info(
+        context.clone(),
+        ParamsOfGetSequencerInfo {
+            address: address.clone(),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(info.queued, 0);
+    assert_eq!(info.processed, 4);
+    assert_eq!(info.failed, 0);
+    assert!(info.last_time.is_some());
+}
+
+#[test]
+fn test_sequencers_eviction() {
+    let sequencers = Sequencers::default();
+    drop(sequencers.get("1", 1000));
+    let used = sequencers.get("2", 1000);
+
+    // Idle sequencer is removed when the new one is created
+    drop(sequencers.get("3", 1000 + IDLE_SEQUENCER_TIMEOUT));
+    assert!(sequencers.find("1").is_none());
+    assert!(sequencers.find("2").is_some());
+    assert!(sequencers.find("3").is_some());
+
+    // Released sequencer is removed, recently used one is kept
+    drop(used);
+    drop(sequencers.get("4", 1000 + IDLE_SEQUENCER_TIMEOUT));
+    assert!(sequencers.find("2").is_none());
+    assert!(sequencers.find("3").is_some());
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_error_resolving() {
     // skip on Evernode SE since it behaves different to real node
@@ -382,6 +885,8 @@ async fn test_error_resolving() {
             ParamsOfProcessMessage {
                 message_encode_params: deploy_params.clone(),
                 send_events: false,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             TestClient::default_callback,
         )
@@ -409,6 +914,8 @@ async fn test_error_resolving() {
             ParamsOfProcessMessage {
                 message_encode_params: deploy_params.clone(),
                 send_events: false,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             TestClient::default_callback,
         )
@@ -442,6 +949,8 @@ async fn test_error_resolving() {
             ParamsOfProcessMessage {
                 message_encode_params: run_params.clone(),
                 send_events: false,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             TestClient::default_callback,
         )
@@ -465,6 +974,8 @@ async fn test_error_resolving() {
             ParamsOfProcessMessage {
                 message_encode_params: deploy_params.clone(),
                 send_events: false,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             TestClient::default_callback,
         )
@@ -478,6 +989,8 @@ async fn test_error_resolving() {
             ParamsOfProcessMessage {
                 message_encode_params: run_params.clone(),
                 send_events: false,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             TestClient::default_callback,
         )
@@ -550,6 +1063,8 @@ async fn test_retries() {
                             ..Default::default()
                         },
                         send_events: false,
+                        dry_run: None,
+                        wait_transaction_tree: None,
                     },
                     TestClient::default_callback,
                 )
@@ -561,6 +1076,184 @@ async fn test_retries() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_send_messages_with_encode_params() {
+    let client = TestClient::new_with_config(json!({
+        "network": {
+            "endpoints": TestClient::endpoints(),
+            "message_retries_count": 10,
+            "out_of_sync_threshold": 2500,
+        },
+        "abi": {
+            "message_expiration_timeout": 5000,
+        }
+    }));
+    let context = client.context();
+    let (abi, tvc) = TestClient::package(HELLO, Some(2));
+    let keys = client.generate_sign_keys();
+
+    let address = client
+        .deploy_with_giver_async(
+            ParamsOfEncodeMessage {
+                abi: abi.clone(),
+                deploy_set: DeploySet::some_with_tvc(tvc),
+                call_set: CallSet::some_with_function("constructor"),
+                signer: Signer::Keys { keys: keys.clone() },
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+
+    let messages = (0..5)
+        .map(|index| MessageSendingParams {
+            encode_params: Some(ParamsOfEncodeMessage {
+                abi: abi.clone(),
+                address: Some(address.clone()),
+                call_set: CallSet::some_with_function("touch"),
+                signer: Signer::Keys { keys: keys.clone() },
+                ..Default::default()
+            }),
+            user_data: Some(json!({ "index": index })),
+            ..Default::default()
+        })
+        .collect();
+    let sent = send_messages(
+        context.clone(),
+        ParamsOfSendMessages {
+            messages,
+            monitor_queue: Some("resign".to_string()),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(sent.messages.len(), 5);
+    assert!(sent.messages.iter().all(|x| x.wait_until > 0));
+
+    let mut results = fetch_next_monitor_results(
+        context.clone(),
+        ParamsOfFetchNextMonitorResults {
+            queue: "resign".to_string(),
+            wait_mode: Some(MonitorFetchWaitMode::All),
+        },
+    )
+    .await
+    .unwrap()
+    .results;
+    results.sort_by_key(|x| x.user_data.as_ref().unwrap()["index"].as_u64());
+    assert_eq!(results.len(), 5);
+    for (index, result) in results.iter().enumerate() {
+        assert_eq!(result.status, MessageMonitoringStatus::Finalized);
+        assert_eq!(result.user_data, Some(json!({ "index": index })));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_send_messages_encode_params_persistent_monitor() {
+    let storage_path =
+        std::env::temp_dir().join(format!("tonclient-{}", rand::random::<u32>()));
+    let context = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                persistent_message_monitor: true,
+                ..Default::default()
+            },
+            local_storage_path: Some(storage_path.to_string_lossy().to_string()),
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+    let (abi, _) = TestClient::package(HELLO, Some(2));
+    let keys = crate::crypto::generate_random_sign_keys(context.clone()).unwrap();
+
+    // Resending state is not persisted, so it can not be combined with the monitor queue
+    let err = send_messages(
+        context.clone(),
+        ParamsOfSendMessages {
+            messages: vec![MessageSendingParams {
+                encode_params: Some(ParamsOfEncodeMessage {
+                    abi,
+                    address: Some(format!("0:{}", "1".repeat(64))),
+                    call_set: CallSet::some_with_function("touch"),
+                    signer: Signer::Keys { keys },
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            monitor_queue: Some("1".to_string()),
+        },
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code, ErrorCode::ResendingNotPersisted as u32);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_cancel_monitor_forgets_resending_messages() {
+    let context = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+    let (abi, _) = TestClient::package(HELLO, Some(2));
+    let keys = crate::crypto::generate_random_sign_keys(context.clone()).unwrap();
+
+    NetworkMock::build()
+        .url("a")
+        .info(context.env.now_ms(), 1000)
+        .repeat(2)
+        .ok(&json!({ "data": { "postRequests": null } }).to_string())
+        .reset_client(&context)
+        .await;
+
+    for queue in ["1", "2"] {
+        send_messages(
+            context.clone(),
+            ParamsOfSendMessages {
+                messages: vec![MessageSendingParams {
+                    encode_params: Some(ParamsOfEncodeMessage {
+                        abi: abi.clone(),
+                        address: Some(format!("0:{}", "1".repeat(64))),
+                        call_set: CallSet::some_with_function("touch"),
+                        signer: Signer::Keys { keys: keys.clone() },
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }],
+                monitor_queue: Some(queue.to_string()),
+            },
+        )
+        .await
+        .unwrap();
+    }
+    let resending_count = || context.resending_messages.messages.lock().unwrap().len();
+    assert_eq!(resending_count(), 2);
+
+    cancel_monitor(
+        context.clone(),
+        ParamsOfCancelMonitor {
+            queue: "1".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(resending_count(), 1);
+
+    cancel_monitor(
+        context.clone(),
+        ParamsOfCancelMonitor {
+            queue: "2".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(resending_count(), 0);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_fees() {
     let client = TestClient::new();
@@ -622,6 +1315,8 @@ async fn test_fees() {
             ParamsOfProcessMessage {
                 message_encode_params: params,
                 send_events: false,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             TestClient::default_callback,
         )
@@ -711,6 +1406,8 @@ async fn test_deploy_from_tvc_v1() {
             ParamsOfProcessMessage {
                 message_encode_params: encode_params,
                 send_events: false,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             |_: ProcessingEvent, _: ProcessingResponseType| async {},
         )
@@ -733,6 +1430,8 @@ async fn test_deploy_from_tvc_v1() {
                     ..Default::default()
                 },
                 send_events: false,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             move |_: ProcessingEvent, _: ProcessingResponseType| async {},
         )
@@ -796,6 +1495,8 @@ fn test_process_message_sync() {
             ParamsOfProcessMessage {
                 message_encode_params: encode_params,
                 send_events: true,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
         )
         .unwrap();
@@ -826,6 +1527,8 @@ fn test_process_message_sync() {
                     ..Default::default()
                 },
                 send_events: true,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
         )
         .unwrap();
@@ -853,3 +1556,453 @@ fn test_process_message_sync() {
         })
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_monitor_messages_polling() {
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+
+    let hash = "1".repeat(64);
+    let now = client.env.now_ms();
+    NetworkMock::build()
+        .url("a")
+        .info(now, 1000)
+        // Endpoint schema has no `recentExtInMessageStatuses` subscription
+        .ok(&json!({
+            "data": {
+                "__type": { "fields": [{ "name": "blocks" }] }
+            }
+        })
+        .to_string())
+        .blocks("1")
+        .ok(&json!({
+            "data": {
+                "transactions": [{
+                    "id": "2",
+                    "in_msg": hash,
+                    "aborted": false,
+                    "compute": { "exit_code": 0 },
+                }]
+            }
+        })
+        .to_string())
+        .reset_client(&client)
+        .await;
+
+    monitor_messages(
+        client.clone(),
+        ParamsOfMonitorMessages {
+            queue: "1".to_string(),
+            messages: vec![MessageMonitoringParams {
+                message: MonitoredMessage::HashAddress {
+                    hash: hash.clone(),
+                    address: format!("-1:{}", "3".repeat(64)),
+                },
+                wait_until: (now / 1000) as u32 + 60,
+                user_data: Some(json!(1)),
+            }],
+        },
+    )
+    .unwrap();
+    let results = fetch_next_monitor_results(
+        client.clone(),
+        ParamsOfFetchNextMonitorResults {
+            queue: "1".to_string(),
+            wait_mode: Some(MonitorFetchWaitMode::All),
+        },
+    )
+    .await
+    .unwrap()
+    .results;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].hash, hash);
+    assert_eq!(results[0].status, MessageMonitoringStatus::Finalized);
+    assert_eq!(
+        results[0].transaction.as_ref().unwrap().hash.as_deref(),
+        Some("2")
+    );
+    assert_eq!(results[0].user_data, Some(json!(1)));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_monitor_messages_polling_unsubscribe() {
+    use crate::processing::message_monitor_polling::subscribe_polling;
+
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+
+    let now = client.env.now_ms();
+    NetworkMock::build()
+        .url("a")
+        .info(now, 1000)
+        .blocks("1")
+        .ok(&json!({ "data": { "transactions": [] } }).to_string())
+        // Next block is waited much longer than the test runs
+        .delay(10000)
+        .blocks("2")
+        .reset_client(&client)
+        .await;
+
+    // Callback is dropped when the polling is finished
+    let polling = Arc::new(());
+    let polling_copy = polling.clone();
+    let handle = subscribe_polling(
+        client.net.clone(),
+        &client.bocs,
+        vec![MessageMonitoringParams {
+            message: MonitoredMessage::HashAddress {
+                hash: "1".repeat(64),
+                address: format!("-1:{}", "3".repeat(64)),
+            },
+            wait_until: (now / 1000) as u32 + 60,
+            user_data: None,
+        }],
+        move |_| {
+            let _ = &polling_copy;
+            futures::future::ready(())
+        },
+    )
+    .await
+    .unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    assert_eq!(Arc::strong_count(&polling), 2);
+    client.net.unsubscribe(handle).await.unwrap();
+    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    assert_eq!(Arc::strong_count(&polling), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_ext_in_message_statuses_support_endpoint_change() {
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+    let schema = |subscription: &str| {
+        json!({
+            "data": {
+                "__type": { "fields": [{ "name": "blocks" }, { "name": subscription }] }
+            }
+        })
+        .to_string()
+    };
+    let services = SdkServices::new(client.net.clone(), client.bocs.clone());
+
+    let now = client.env.now_ms();
+    NetworkMock::build()
+        .url("a")
+        .info(now, 1000)
+        .ok(&schema("messages"))
+        .reset_client(&client)
+        .await;
+    assert!(!services.is_ext_in_message_statuses_supported().await.unwrap());
+    // Cached result is used for the same endpoint
+    assert!(!services.is_ext_in_message_statuses_supported().await.unwrap());
+
+    crate::net::set_endpoints(
+        client.clone(),
+        crate::net::EndpointsSet {
+            endpoints: vec!["b".into()],
+        },
+    )
+    .await
+    .unwrap();
+    NetworkMock::build()
+        .url("b")
+        .info(now, 1000)
+        .ok(&schema("recentExtInMessageStatuses"))
+        .reset_client(&client)
+        .await;
+    assert!(services.is_ext_in_message_statuses_supported().await.unwrap());
+}
+
+fn mock_block(id: &str, shard: &str, after_split: bool) -> String {
+    json!({
+        "data": {
+            "blocks": [{
+                "id": id,
+                "gen_utime": 1,
+                "after_split": after_split,
+                "workchain_id": 0,
+                "shard": shard,
+                "in_msg_descr": [],
+            }]
+        }
+    })
+    .to_string()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_wait_next_block_shard_split_and_merge() {
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+    // Account resides in the left half of the workchain
+    let address = crate::encoding::account_decode(&format!("0:{}", "3".repeat(64))).unwrap();
+
+    NetworkMock::build()
+        .url("a")
+        .info(client.env.now_ms(), 1000)
+        // Split: the right child is received first
+        .ok(&mock_block("c", "c000000000000000", true))
+        .ok(&mock_block("4", "4000000000000000", true))
+        // Next block of the left shard
+        .ok(&mock_block("42", "4000000000000000", false))
+        // Merge
+        .ok(&mock_block("8", "8000000000000000", false))
+        // Chain of the account shard is lost: the next block is not a split child,
+        // so the sibling is not waited for
+        .ok(&mock_block("c1", "c000000000000000", false))
+        .delay(10000)
+        .ok(&mock_block("c2", "c000000000000000", true))
+        .reset_client(&client)
+        .await;
+
+    let block = wait_next_block(&client, "1", &address, None).await.unwrap();
+    assert_eq!(block.id.to_string(), "4");
+    assert!(block.after_split);
+
+    let block = wait_next_block(&client, "4", &address, None).await.unwrap();
+    assert_eq!(block.id.to_string(), "42");
+
+    let block = wait_next_block(&client, "42", &address, None).await.unwrap();
+    assert_eq!(block.id.to_string(), "8");
+
+    let start = client.env.now_ms();
+    let err = wait_next_block(&client, "8", &address, None)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code, ErrorCode::AccountShardLost as u32);
+    assert!(client.env.now_ms() - start < 5000);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_wait_for_transaction_account_shard_lost() {
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+    let (abi, tvc) = TestClient::package(HELLO, Some(2));
+    let keys = crate::crypto::generate_random_sign_keys(client.clone()).unwrap();
+    let message = crate::abi::encode_message(
+        client.clone(),
+        ParamsOfEncodeMessage {
+            abi: abi.clone(),
+            deploy_set: DeploySet::some_with_tvc(tvc),
+            call_set: CallSet::some_with_function("constructor"),
+            signer: Signer::Keys { keys },
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let executed = crate::tvm::run_executor(
+        client.clone(),
+        ParamsOfRunExecutor {
+            message: message.message.clone(),
+            account: AccountForExecutor::Uninit,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let transaction_id = executed.transaction["id"].as_str().unwrap().to_string();
+
+    // Both children of the shard block don't contain the account
+    let other_shard = if message.address.as_bytes()[2] < b'8' {
+        "c000000000000000"
+    } else {
+        "4000000000000000"
+    };
+    NetworkMock::build()
+        .url("a")
+        .info(client.env.now_ms(), 1000)
+        .ok(&mock_block("c1", other_shard, true))
+        .ok(&mock_block("c2", other_shard, true))
+        // Transaction is looked up by the message after re-routing
+        .ok(&json!({
+            "data": {
+                "transactions": [{ "id": transaction_id, "block_id": "b1" }]
+            }
+        })
+        .to_string())
+        .ok(&json!({
+            "data": {
+                "transactions": [{
+                    "boc": executed.transaction["boc"],
+                    "out_messages": [],
+                }]
+            }
+        })
+        .to_string())
+        .reset_client(&client)
+        .await;
+
+    let result = crate::processing::wait_for_transaction(
+        client.clone(),
+        ParamsOfWaitForTransaction {
+            abi: Some(abi),
+            message: message.message,
+            shard_block_id: "1".to_string(),
+            send_events: false,
+            sending_endpoints: None,
+        },
+        |_| async {},
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.transaction["id"], transaction_id);
+    assert!(result.out_messages.is_empty());
+}
+
+fn remp_receipt(message_id: &str, kind: &str, json: Value) -> Value {
+    json!({
+        "rempReceipts": {
+            "messageId": message_id,
+            "kind": kind,
+            "timestamp": 1,
+            "json": json.to_string(),
+        }
+    })
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_subscribe_remp_statuses() {
+    let client = Arc::new(
+        ClientContext::new(ClientConfig {
+            network: NetworkConfig {
+                endpoints: Some(vec!["a".into()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+
+    NetworkMock::build()
+        .url("a")
+        .info(client.env.now_ms(), 1000)
+        .delay(100)
+        .ws_ack()
+        .delay(100)
+        .ws_data(1, remp_receipt("m1", "SentToValidators", json!({})), None)
+        .delay(100)
+        .ws_data(2, remp_receipt("m2", "RejectedByFullnode", json!({ "error": "bad" })), None)
+        .delay(100)
+        .ws_data(1, remp_receipt("m1", "Finalized", json!({ "block_id": "b1" })), None)
+        // Statuses after the final one are not delivered
+        .delay(100)
+        .ws_data(1, remp_receipt("m1", "Other", json!({})), None)
+        .reset_client(&client)
+        .await;
+
+    let updates = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+    let updates_copy = updates.clone();
+    let callback = move |result: ClientResult<RempStatusUpdate>| {
+        let updates = updates_copy.clone();
+        async move {
+            updates.lock().await.push(result.unwrap());
+        }
+    };
+    let subscription = subscribe_remp_statuses(
+        client.clone(),
+        ParamsOfSubscribeRempStatuses {
+            message_ids: vec!["m1".to_string(), "m2".to_string()],
+        },
+        callback,
+    )
+    .await
+    .unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+    // All messages are finalized, so the handle is already released
+    assert!(!client
+        .net
+        .subscriptions
+        .lock()
+        .await
+        .contains_key(&subscription.handle));
+    crate::net::unsubscribe(client.clone(), subscription)
+        .await
+        .unwrap();
+
+    let updates = updates.lock().await;
+    assert_eq!(
+        *updates,
+        vec![
+            RempStatusUpdate::SentToValidators {
+                message_id: "m1".to_string(),
+                timestamp: 1,
+                json: json!({}),
+            },
+            RempStatusUpdate::RejectedByFullnode {
+                message_id: "m2".to_string(),
+                timestamp: 1,
+                error: "bad".to_string(),
+                json: json!({ "error": "bad" }),
+            },
+            RempStatusUpdate::Finalized {
+                message_id: "m1".to_string(),
+                timestamp: 1,
+                block_id: Some("b1".to_string()),
+                json: json!({ "block_id": "b1" }),
+            },
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_subscribe_remp_statuses_empty() {
+    let client = TestClient::new();
+    let subscription = subscribe_remp_statuses(
+        client.context(),
+        ParamsOfSubscribeRempStatuses { message_ids: vec![] },
+        |_| async {},
+    )
+    .await
+    .unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    assert!(!client
+        .context()
+        .net
+        .subscriptions
+        .lock()
+        .await
+        .contains_key(&subscription.handle));
+}
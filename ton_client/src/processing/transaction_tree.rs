@@ -0,0 +1,186 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::abi::Abi;
+use crate::client::ClientContext;
+use crate::error::{ClientError, ClientResult};
+use crate::net::transaction_tree::{
+    query_transaction_tree_partial, DEFAULT_TRANSACTION_MAX_COUNT, DEFAULT_WAITING_TIMEOUT,
+};
+use crate::net::{
+    MessageNode, ParamsOfQueryTransactionTree, ResultOfQueryTransactionTree, TransactionNode,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, Clone)]
+pub struct TransactionTreeOptions {
+    /// Additional contract ABIs used to decode message bodies of the tree.
+    ///
+    /// The ABI of the processed message is always used.
+    pub abi_registry: Option<Vec<Abi>>,
+
+    /// Maximum waiting time for the whole transaction tree in milliseconds.
+    ///
+    /// Default value is 60000 (1 min). If `timeout` is set to 0 then the library
+    /// waits infinitely until the whole transaction tree is executed.
+    pub timeout: Option<u32>,
+
+    /// Maximum transaction count to wait.
+    ///
+    /// Default value is 50. If `transaction_max_count` is set to 0 then no limitation
+    /// on transaction count is used.
+    pub transaction_max_count: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct ResultOfTransactionTree {
+    /// Messages of the tree including the processed message.
+    pub messages: Vec<MessageNode>,
+
+    /// Transactions of the tree including the root transaction.
+    pub transactions: Vec<TransactionNode>,
+
+    /// Sum of `total_fees` of all transactions of the tree. Encoded as hex string.
+    pub total_fees: String,
+
+    /// Ids of the aborted transactions.
+    pub aborted_transactions: Vec<String>,
+
+    /// Ids of the bounced messages.
+    pub bounced_messages: Vec<String>,
+
+    /// Flag indicating that all the tree transactions are executed.
+    ///
+    /// Is `false` if the waiting timeout or `transaction_max_count` is reached
+    /// before the whole tree is executed.
+    pub complete: bool,
+
+    /// Error that interrupted waiting for the tree. The tree is incomplete in this case.
+    ///
+    /// The processed message transaction is already executed, so `process_message` returns
+    /// its result with this error instead of failing.
+    pub error: Option<ClientError>,
+}
+
+fn parse_fees(fees: &str) -> u128 {
+    match fees.strip_prefix("0x") {
+        Some(hex) => u128::from_str_radix(hex, 16).unwrap_or_default(),
+        None => fees.parse().unwrap_or_default(),
+    }
+}
+
+/// Checks that every internal message of the tree has a transaction
+/// and every out message of the tree transactions is received.
+fn is_complete(tree: &ResultOfQueryTransactionTree) -> bool {
+    let message_ids = tree
+        .messages
+        .iter()
+        .map(|x| x.id.as_str())
+        .collect::<HashSet<_>>();
+    let has_pending_messages = tree.messages.iter().any(|message| {
+        let is_internal = message.dst.as_deref().map_or(false, |dst| !dst.is_empty());
+        is_internal && message.dst_transaction_id.is_none()
+    });
+    let has_missing_messages = tree
+        .transactions
+        .iter()
+        .flat_map(|x| x.out_msgs.iter())
+        .any(|id| !message_ids.contains(id.as_str()));
+    !has_pending_messages && !has_missing_messages
+}
+
+impl ResultOfTransactionTree {
+    fn new(tree: ResultOfQueryTransactionTree, complete: bool) -> Self {
+        let total_fees = tree
+            .transactions
+            .iter()
+            .map(|x| parse_fees(&x.total_fees))
+            .sum::<u128>();
+        Self {
+            total_fees: format!("0x{:x}", total_fees),
+            aborted_transactions: tree
+                .transactions
+                .iter()
+                .filter(|x| x.aborted)
+                .map(|x| x.id.clone())
+                .collect(),
+            bounced_messages: tree
+                .messages
+                .iter()
+                .filter(|x| x.bounced)
+                .map(|x| x.id.clone())
+                .collect(),
+            messages: tree.messages,
+            transactions: tree.transactions,
+            complete,
+            error: None,
+        }
+    }
+
+    pub(crate) fn failed(error: ClientError) -> Self {
+        Self {
+            total_fees: "0x0".to_string(),
+            error: Some(error),
+            ..Default::default()
+        }
+    }
+}
+
+/// Waits until all the transactions of the tree produced by the message are executed.
+///
+/// Returns the tree retrieved so far with `complete` set to `false` if the timeout
+/// is reached.
+pub(crate) async fn wait_transaction_tree(
+    context: &Arc<ClientContext>,
+    message_id: &str,
+    abi: &Abi,
+    options: &TransactionTreeOptions,
+) -> ClientResult<ResultOfTransactionTree> {
+    let mut abi_registry = vec![abi.clone()];
+    abi_registry.extend(options.abi_registry.clone().unwrap_or_default());
+    let timeout = options.timeout.unwrap_or(DEFAULT_WAITING_TIMEOUT);
+    let transaction_max_count = options
+        .transaction_max_count
+        .unwrap_or(DEFAULT_TRANSACTION_MAX_COUNT) as usize;
+    let time_limit = context.env.now_ms() + timeout as u64;
+    loop {
+        // Messages of the tree are awaited not longer than the time left
+        let query_timeout = if timeout == 0 {
+            0
+        } else {
+            time_limit.saturating_sub(context.env.now_ms()).max(1) as u32
+        };
+        let (tree, timeout_error) = query_transaction_tree_partial(
+            context.clone(),
+            ParamsOfQueryTransactionTree {
+                in_msg: message_id.to_string(),
+                abi_registry: Some(abi_registry.clone()),
+                timeout: Some(query_timeout),
+                transaction_max_count: Some(transaction_max_count as u32),
+            },
+        )
+        .await?;
+        if timeout_error.is_some() {
+            return Ok(ResultOfTransactionTree::new(tree, false));
+        }
+        let complete = is_complete(&tree);
+        let limit_reached =
+            transaction_max_count != 0 && tree.transactions.len() >= transaction_max_count;
+        let expired = timeout != 0 && context.env.now_ms() > time_limit;
+        if complete || limit_reached || expired {
+            return Ok(ResultOfTransactionTree::new(tree, complete));
+        }
+        context.env.set_timer(1000).await?;
+    }
+}
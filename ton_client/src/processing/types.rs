@@ -1,5 +1,6 @@
 use crate::abi::DecodedMessageBody;
 use crate::error::ClientError;
+use crate::processing::transaction_tree::ResultOfTransactionTree;
 use serde_json::Value;
 use ton_sdk::TransactionFees;
 
@@ -33,6 +34,35 @@ pub struct ResultOfProcessMessage {
 
     /// Transaction fees
     pub fees: TransactionFees,
+
+    /// Result of the local message execution performed before sending.
+    ///
+    /// Returned only if `dry_run.return_result` is requested in `ParamsOfProcessMessage`.
+    pub dry_run: Option<DryRunResult>,
+
+    /// Transaction tree produced by the message.
+    ///
+    /// Returned only if `wait_transaction_tree` is specified in `ParamsOfProcessMessage`.
+    pub transaction_tree: Option<ResultOfTransactionTree>,
+}
+
+#[derive(Serialize, Deserialize, ApiType, Default, Debug, PartialEq, Clone)]
+pub struct DryRunResult {
+    /// Parsed transaction produced by the local execution.
+    ///
+    /// In addition to the regular transaction fields there is a
+    /// `boc` field encoded with `base64` which contains source
+    /// transaction BOC.
+    pub transaction: Value,
+
+    /// List of output messages' BOCs. Encoded as `base64`
+    pub out_messages: Vec<String>,
+
+    /// Optional decoded message bodies according to the message ABI.
+    pub decoded: Option<DecodedOutput>,
+
+    /// Estimated transaction fees
+    pub fees: TransactionFees,
 }
 
 #[derive(Clone, num_derive::FromPrimitive, PartialEq, Debug)]
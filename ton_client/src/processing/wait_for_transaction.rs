@@ -5,7 +5,10 @@ use crate::client::ClientContext;
 use crate::error::{AddNetworkUrl, ClientResult};
 use crate::net::{EndpointStat, ResultOfSubscription};
 use crate::processing::internal::{get_message_expiration_time, resolve_error};
-use crate::processing::{fetching, internal, Error};
+use crate::processing::outbox::complete_outbox_message;
+use crate::net::{ParamsOfQueryCollection, TRANSACTIONS_COLLECTION};
+use crate::processing::blocks_walking::find_last_shard_block;
+use crate::processing::{fetching, internal, Error, ErrorCode};
 use crate::processing::{ProcessingEvent, ResultOfProcessMessage};
 use futures::{FutureExt, StreamExt};
 use std::convert::TryInto;
@@ -55,11 +58,21 @@ pub async fn wait_for_transaction<F: futures::Future<Output = ()> + Send>(
 
     let callback = Arc::new(callback);
 
-    if net.state().get_query_endpoint().await?.remp_enabled() {
-        wait_by_remp(context, params, callback).await
+    let message = params.message.clone();
+    let result = if net.state().get_query_endpoint().await?.remp_enabled() {
+        wait_by_remp(context.clone(), params, callback).await
     } else {
-        wait_by_block_walking(context, &params, callback).await
+        wait_by_block_walking(context.clone(), &params, callback).await
+    };
+    complete_outbox_message(&context, &message, &result).await;
+    if let Ok((hash, _)) = context.bocs.resolve_boc_with_hash(&message, "message") {
+        context.processing_stats.processing_finished(
+            &hash.as_hex_string(),
+            &result,
+            context.env.now_ms(),
+        );
     }
+    result
 }
 
 async fn wait_by_remp<F: futures::Future<Output = ()> + Send>(
@@ -203,6 +216,9 @@ async fn process_remp_message<F: futures::Future<Output = ()> + Send>(
     let status: RempStatus = serde_json::from_value(remp_message).map_err(|err| {
         Error::invalid_remp_status(format!("can not parse REMP status message: {}", err))
     })?;
+    context
+        .processing_stats
+        .remp_status(message_id, status.kind(), context.env.now_ms());
 
     match status {
         RempStatus::RejectedByFullnode(data) => Ok(Some(
@@ -339,7 +355,32 @@ async fn wait_by_block_walking<F: futures::Future<Output = ()> + Send>(
         )
         .await
         .add_network_url_from_context(&context)
-        .await?;
+        .await;
+        let block = match block {
+            Ok(block) => block,
+            Err(err) if err.code == ErrorCode::AccountShardLost as u32 => {
+                // The account shard chain can not be followed, so the transaction
+                // is looked up directly and the walking is restarted from the current
+                // account shard block
+                log::debug!("{}", err.message);
+                let found = find_transaction_by_message(
+                    &context,
+                    params,
+                    &message_id,
+                    &address,
+                    max_block_time,
+                )
+                .await?;
+                if let Some(result) = found {
+                    return result;
+                }
+                shard_block_id = find_last_shard_block(&context, &address, None)
+                    .await?
+                    .to_string();
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
         let transaction_ids = internal::find_transactions(&block, &message_id, &shard_block_id)?;
         let mut last_error = None;
         for transaction_id in transaction_ids {
@@ -408,6 +449,9 @@ async fn wait_by_block_walking<F: futures::Future<Output = ()> + Send>(
             .await;
             if let (Some(endpoints), Err(err)) = (&params.sending_endpoints, &resolved) {
                 if err.data["local_error"].is_null() {
+                    for endpoint in endpoints {
+                        context.processing_stats.endpoint_failed(endpoint);
+                    }
                     context
                         .get_server_link()?
                         .update_stat(endpoints, EndpointStat::MessageUndelivered)
@@ -422,3 +466,54 @@ async fn wait_by_block_walking<F: futures::Future<Output = ()> + Send>(
         shard_block_id = block.id.to_string();
     }
 }
+
+/// Looks up the message transaction directly.
+/// Used when the block walking is re-routed and the skipped blocks can contain the transaction.
+async fn find_transaction_by_message(
+    context: &Arc<ClientContext>,
+    params: &ParamsOfWaitForTransaction,
+    message_id: &str,
+    address: &MsgAddressInt,
+    max_block_time: u64,
+) -> ClientResult<Option<ClientResult<ResultOfProcessMessage>>> {
+    let transactions = context
+        .get_server_link()?
+        .query_collection(
+            ParamsOfQueryCollection {
+                collection: TRANSACTIONS_COLLECTION.to_string(),
+                filter: Some(json!({
+                    "in_msg": { "eq": message_id },
+                })),
+                result: "id block_id".to_string(),
+                limit: Some(1),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .add_network_url_from_context(context)
+        .await?;
+    let transaction = &transactions[0];
+    let (transaction_id, block_id) = match (
+        transaction["id"].as_str(),
+        transaction["block_id"].as_str(),
+    ) {
+        (Some(transaction_id), Some(block_id)) => (transaction_id, block_id),
+        _ => return Ok(None),
+    };
+    let result = fetching::fetch_transaction_result(
+        context,
+        block_id,
+        message_id,
+        &params.message,
+        Some(transaction_id),
+        &params.abi,
+        address.clone(),
+        (max_block_time / 1000) as u32,
+        (context.env.now_ms() / 1000) as u32,
+    )
+    .await
+    .add_network_url_from_context(context)
+    .await;
+    Ok(Some(result))
+}
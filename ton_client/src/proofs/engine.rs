@@ -14,7 +14,7 @@ use crate::client::storage::{InMemoryKeyValueStorage, KeyValueStorage};
 use crate::ClientContext;
 use crate::encoding::base64_decode;
 use crate::error::ClientResult;
-use crate::net::{OrderBy, ParamsOfQueryCollection, query_collection, SortDirection};
+use crate::net::{OrderBy, ParamsOfQueryCollection, fetch_collection, SortDirection};
 use crate::proofs::{BlockProof, ProofHelperEngine, resolve_initial_trusted_key_block};
 use crate::proofs::Error;
 use crate::utils::json::JsonHelper;
@@ -253,7 +253,7 @@ impl ProofHelperEngineImpl {
     }
 
     pub(crate) async fn query_zerostate_boc(&self) -> Result<Vec<u8>> {
-        let zerostates = query_collection(
+        let zerostates = fetch_collection(
             Arc::clone(&self.context),
             ParamsOfQueryCollection {
                 collection: "zerostates".to_string(),
@@ -277,7 +277,7 @@ impl ProofHelperEngineImpl {
         mut mc_seq_no: u32,
     ) -> Result<Option<String>> {
         mc_seq_no += 1;
-        let blocks = Self::preprocess_query_result(query_collection(
+        let blocks = Self::preprocess_query_result(fetch_collection(
             Arc::clone(&self.context),
             ParamsOfQueryCollection {
                 collection: "blocks".to_string(),
@@ -302,7 +302,7 @@ impl ProofHelperEngineImpl {
         if let Some(boc) = self.read_block(root_hash).await? {
             Ok(boc)
         } else {
-            let blocks = query_collection(
+            let blocks = fetch_collection(
                 Arc::clone(&self.context),
                 ParamsOfQueryCollection {
                     collection: "blocks".to_string(),
@@ -349,7 +349,7 @@ impl ProofHelperEngineImpl {
     }
 
     pub(crate) async fn query_mc_block_proof(&self, mc_seq_no: u32) -> Result<Value> {
-        let mut blocks = Self::preprocess_query_result(query_collection(
+        let mut blocks = Self::preprocess_query_result(fetch_collection(
             Arc::clone(&self.context),
             ParamsOfQueryCollection {
                 collection: "blocks".to_string(),
@@ -427,7 +427,7 @@ impl ProofHelperEngineImpl {
                 return Ok(result);
             }
 
-            let key_blocks = query_collection(
+            let key_blocks = fetch_collection(
                 Arc::clone(&self.context),
                 ParamsOfQueryCollection {
                     collection: "blocks".to_string(),
@@ -463,7 +463,7 @@ impl ProofHelperEngineImpl {
         mut proofs_sorted: &mut [(u32, Value)],
     ) -> Result<()> {
         while proofs_sorted.len() > 0 {
-            let mut blocks = Self::preprocess_query_result(query_collection(
+            let mut blocks = Self::preprocess_query_result(fetch_collection(
                 Arc::clone(&self.context),
                 ParamsOfQueryCollection {
                     collection: "blocks".to_string(),
@@ -609,7 +609,7 @@ impl ProofHelperEngineImpl {
         shard_block_seq_no: u32,
     ) -> Result<Option<u32>> {
         loop {
-            let blocks = Self::preprocess_query_result(query_collection(
+            let blocks = Self::preprocess_query_result(fetch_collection(
                 Arc::clone(&self.context),
                 ParamsOfQueryCollection {
                     collection: "blocks".to_string(),
@@ -661,7 +661,7 @@ impl ProofHelperEngineImpl {
         shard: &ShardIdent,
         seq_no_range: Range<u32>,
     ) -> Result<Vec<Vec<u8>>> {
-        let blocks = Self::preprocess_query_result(query_collection(
+        let blocks = Self::preprocess_query_result(fetch_collection(
             Arc::clone(&self.context),
             ParamsOfQueryCollection {
                 collection: "blocks".to_string(),
@@ -841,7 +841,7 @@ impl ProofHelperEngineImpl {
     }
 
     pub(crate) async fn query_transaction_data(&self, id: &str, fields: &str) -> Result<Value> {
-        let mut transactions = query_collection(
+        let mut transactions = fetch_collection(
             Arc::clone(&self.context),
             ParamsOfQueryCollection {
                 collection: "transactions".to_string(),
@@ -864,7 +864,7 @@ impl ProofHelperEngineImpl {
     }
 
     pub(crate) async fn query_message_data(&self, id: &str, fields: &str) -> Result<Value> {
-        let mut messages = query_collection(
+        let mut messages = fetch_collection(
             Arc::clone(&self.context),
             ParamsOfQueryCollection {
                 collection: "messages".to_string(),
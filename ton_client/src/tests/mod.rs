@@ -703,6 +703,8 @@ impl TestClient {
                     ..Default::default()
                 },
                 send_events: false,
+                dry_run: None,
+                wait_transaction_tree: None,
             },
             Self::default_callback,
         )
@@ -820,6 +822,8 @@ impl TestClient {
                 ParamsOfProcessMessage {
                     message_encode_params: params,
                     send_events: false,
+                    dry_run: None,
+                    wait_transaction_tree: None,
                 },
                 Self::default_callback,
             )
@@ -32,4 +32,4 @@ pub use message_monitor::{
     MessageMonitoringTransaction, MessageMonitoringTransactionCompute, MonitorFetchWaitMode,
     MonitoredMessage, MonitoringQueueInfo,
 };
-pub use sdk_services::{MessageMonitorSdkServices, NetSubscription};
+pub use sdk_services::{MessageMonitorSdkServices, MessageMonitorStorage, NetSubscription};
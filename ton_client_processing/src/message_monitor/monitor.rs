@@ -1,9 +1,12 @@
 use crate::message_monitor::message::{MessageMonitoringParams, MessageMonitoringResult};
-use crate::message_monitor::monitor_queues::{BufferedMessages, MonitorQueues, ADDING_TIMEOUT_MS};
+use crate::message_monitor::monitor_queues::{
+    BufferedMessages, MonitorQueues, MonitorQueuesSnapshot, ADDING_TIMEOUT_MS,
+};
 use crate::message_monitor::queue::BufferedMessage;
-use crate::sdk_services::MessageMonitorSdkServices;
+use crate::sdk_services::{MessageMonitorSdkServices, MessageMonitorStorage};
 use crate::NetSubscription;
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
 
 /// The main message monitor object.
@@ -23,6 +26,13 @@ struct MonitorState<Sdk: MessageMonitorSdkServices + Send + Sync + 'static> {
     notify_resolved: Arc<tokio::sync::watch::Sender<crate::error::Result<()>>>,
     listen_resolved: tokio::sync::watch::Receiver<crate::error::Result<()>>,
     active_subscriptions: Mutex<HashMap<usize, HashSet<String>>>,
+
+    /// Durable storage of the queues snapshots
+    storage: Option<Arc<dyn MessageMonitorStorage>>,
+    notify_restored: tokio::sync::watch::Sender<bool>,
+    listen_restored: tokio::sync::watch::Receiver<bool>,
+    last_snapshot_seq: AtomicU64,
+    saved_snapshot_seq: tokio::sync::Mutex<u64>,
 }
 
 #[derive(Deserialize, Serialize, ApiType, Default)]
@@ -49,10 +59,20 @@ pub enum MonitorFetchWaitMode {
 impl<SdkServices: MessageMonitorSdkServices + Send + Sync> MessageMonitor<SdkServices> {
     pub fn new(sdk: SdkServices) -> Self {
         Self {
-            state: Arc::new(MonitorState::new(sdk)),
+            state: Arc::new(MonitorState::new(sdk, None)),
         }
     }
 
+    /// Creates message monitor which persists its queues into the `storage`.
+    ///
+    /// Queues saved by the previous monitor instance are restored in background.
+    /// `fetch_next_monitor_results` waits until the restoring is finished.
+    pub fn with_storage(sdk: SdkServices, storage: Arc<dyn MessageMonitorStorage>) -> Self {
+        let state = Arc::new(MonitorState::new(sdk, Some(storage)));
+        state.clone().start_restoring();
+        Self { state }
+    }
+
     pub fn monitor_messages(
         &self,
         queue: &str,
@@ -81,17 +101,90 @@ impl<SdkServices: MessageMonitorSdkServices + Send + Sync> MessageMonitor<SdkSer
 }
 
 impl<Sdk: MessageMonitorSdkServices + Send + Sync> MonitorState<Sdk> {
-    fn new(sdk: Sdk) -> Self {
+    fn new(sdk: Sdk, storage: Option<Arc<dyn MessageMonitorStorage>>) -> Self {
         let (sender, receiver) = tokio::sync::watch::channel(Ok(()));
+        let (notify_restored, listen_restored) = tokio::sync::watch::channel(storage.is_none());
         Self {
             sdk,
             queues: RwLock::new(MonitorQueues::new()),
             active_subscriptions: Mutex::new(HashMap::new()),
             notify_resolved: Arc::new(sender),
             listen_resolved: receiver,
+            storage,
+            notify_restored,
+            listen_restored,
+            last_snapshot_seq: AtomicU64::new(0),
+            saved_snapshot_seq: tokio::sync::Mutex::new(0),
+        }
+    }
+
+    fn start_restoring(self: Arc<Self>) {
+        self.clone().sdk.spawn(async move {
+            if let Some(snapshot) = self.load_snapshot().await {
+                let mut queues = self.queues.write().unwrap();
+                let should_start_buffering_timer = !queues.has_buffered();
+                let now_ms = self.sdk.now_ms();
+                queues.restore(now_ms, snapshot);
+                if should_start_buffering_timer && queues.has_buffered() {
+                    queues.last_fetching_time_ms = now_ms;
+                    self.clone().start_buffering_timer();
+                }
+            }
+            self.notify_restored.send(true).ok();
+            self.save_snapshot();
+            self.notify_resolved.send(Ok(())).ok();
+        });
+    }
+
+    async fn load_snapshot(&self) -> Option<MonitorQueuesSnapshot> {
+        let snapshot = match self.storage.as_ref()?.load_queues().await {
+            Ok(snapshot) => snapshot?,
+            Err(err) => {
+                log::warn!("Can not load message monitor queues: {}", err.message);
+                return None;
+            }
+        };
+        match serde_json::from_str(&snapshot) {
+            Ok(snapshot) => Some(snapshot),
+            Err(err) => {
+                log::warn!("Invalid message monitor queues snapshot: {}", err);
+                None
+            }
         }
     }
 
+    /// Saves the current state of the queues into the storage.
+    /// Snapshots are saved in background, outdated snapshots are skipped.
+    fn save_snapshot(self: &Arc<Self>) {
+        let storage = match &self.storage {
+            Some(storage) if *self.listen_restored.borrow() => storage.clone(),
+            _ => return,
+        };
+        let (seq, snapshot) = {
+            let queues = self.queues.read().unwrap();
+            let seq = self.last_snapshot_seq.fetch_add(1, Ordering::SeqCst) + 1;
+            (seq, serde_json::to_string(&queues.snapshot()))
+        };
+        let snapshot = match snapshot {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                log::warn!("Can not serialize message monitor queues: {}", err);
+                return;
+            }
+        };
+        let state = self.clone();
+        self.sdk.spawn(async move {
+            let mut saved_seq = state.saved_snapshot_seq.lock().await;
+            if *saved_seq >= seq {
+                return;
+            }
+            if let Err(err) = storage.save_queues(snapshot).await {
+                log::warn!("Can not save message monitor queues: {}", err.message);
+            }
+            *saved_seq = seq;
+        });
+    }
+
     fn monitor_messages(
         self: &Arc<Self>,
         queue: &str,
@@ -108,25 +201,37 @@ impl<Sdk: MessageMonitorSdkServices + Send + Sync> MonitorState<Sdk> {
             });
         }
 
-        let mut queues = self.queues.write().unwrap();
-        let should_start_buffering_timer = !queues.has_buffered();
-        let now_ms = self.sdk.now_ms();
-        queues.add_buffered(now_ms, queue, buffered);
-        if should_start_buffering_timer {
-            queues.last_fetching_time_ms = now_ms;
-            self.clone().start_buffering_timer();
+        {
+            let mut queues = self.queues.write().unwrap();
+            let should_start_buffering_timer = !queues.has_buffered();
+            let now_ms = self.sdk.now_ms();
+            queues.add_buffered(now_ms, queue, buffered);
+            if should_start_buffering_timer {
+                queues.last_fetching_time_ms = now_ms;
+                self.clone().start_buffering_timer();
+            }
         }
+        self.save_snapshot();
         Ok(())
     }
 
     async fn fetch_next_monitor_results(
-        &self,
+        self: &Arc<Self>,
         queue: &str,
         wait_mode: MonitorFetchWaitMode,
     ) -> crate::error::Result<Vec<MessageMonitoringResult>> {
+        let mut listen_restored = self.listen_restored.clone();
+        while !*listen_restored.borrow() {
+            if listen_restored.changed().await.is_err() {
+                break;
+            }
+        }
         let mut listen_resolved = self.listen_resolved.clone();
         loop {
             if let Some(fetched) = self.fetch_next(queue, wait_mode) {
+                if !fetched.is_empty() {
+                    self.save_snapshot();
+                }
                 return Ok(fetched);
             }
             listen_resolved.changed().await.unwrap();
@@ -140,8 +245,9 @@ impl<Sdk: MessageMonitorSdkServices + Send + Sync> MonitorState<Sdk> {
         Ok(self.queues.read().unwrap().get_info(queue))
     }
 
-    fn cancel_monitor(&self, queue: &str) -> crate::error::Result<()> {
+    fn cancel_monitor(self: &Arc<Self>, queue: &str) -> crate::error::Result<()> {
         self.queues.write().unwrap().remove(queue);
+        self.save_snapshot();
         Ok(())
     }
 
@@ -177,6 +283,7 @@ impl<Sdk: MessageMonitorSdkServices + Send + Sync> MonitorState<Sdk> {
                     Ok(results) => {
                         let empty_subscriptions =
                             self1.resolve_results_and_return_empty_subscriptions(&results);
+                        self1.save_snapshot();
                         for subscription in empty_subscriptions {
                             let _ = self1.sdk.unsubscribe(subscription).await;
                         }
@@ -1,5 +1,5 @@
 use crate::message_monitor::queue::{BufferedMessage, MonitoringQueue};
-use crate::{MessageMonitoringParams, MonitoringQueueInfo};
+use crate::{MessageMonitoringParams, MessageMonitoringResult, MonitoringQueueInfo};
 use std::collections::{HashMap, HashSet};
 
 pub(crate) const ADDING_TIMEOUT_MS: u64 = 1000;
@@ -11,6 +11,18 @@ pub(crate) struct MonitorQueues {
     pub(crate) queues: HashMap<String, MonitoringQueue>,
 }
 
+/// Serializable state of the monitor queues.
+#[derive(Serialize, Deserialize, Default)]
+pub(crate) struct MonitorQueuesSnapshot {
+    queues: HashMap<String, MonitoringQueueSnapshot>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct MonitoringQueueSnapshot {
+    unresolved: Vec<BufferedMessage>,
+    results: Vec<MessageMonitoringResult>,
+}
+
 pub struct BufferedMessages {
     pub messages: Vec<MessageMonitoringParams>,
     pub hashes: HashSet<String>,
@@ -95,11 +107,47 @@ impl MonitorQueues {
                 let hash = queue.buffered[i].hash.clone();
                 if hashes.contains(&hash) {
                     let params = queue.buffered.remove(i);
-                    queue.resolving.insert(hash, params.message.user_data);
+                    queue.resolving.insert(hash, params.message);
                 }
             }
         }
     }
-}
 
-impl MonitorQueues {}
+    pub fn snapshot(&self) -> MonitorQueuesSnapshot {
+        let mut snapshot = MonitorQueuesSnapshot::default();
+        for (name, queue) in &self.queues {
+            let mut unresolved = queue.buffered.clone();
+            unresolved.extend(queue.resolving.iter().map(|(hash, message)| BufferedMessage {
+                hash: hash.clone(),
+                message: message.clone(),
+            }));
+            snapshot.queues.insert(
+                name.clone(),
+                MonitoringQueueSnapshot {
+                    unresolved,
+                    results: queue.results.clone(),
+                },
+            );
+        }
+        snapshot
+    }
+
+    /// Merges restored snapshot into the queues.
+    /// All unresolved messages of the snapshot are buffered to be resolved again.
+    pub fn restore(&mut self, now_ms: u64, snapshot: MonitorQueuesSnapshot) {
+        for (name, restored) in snapshot.queues {
+            let queue = self.ensure(&name);
+            for message in restored.unresolved {
+                let exists = queue.resolving.contains_key(&message.hash)
+                    || queue.buffered.iter().any(|x| x.hash == message.hash);
+                if !exists {
+                    queue.buffered.push(message);
+                }
+            }
+            let mut results = restored.results;
+            results.append(&mut queue.results);
+            queue.results = results;
+        }
+        self.last_adding_time_ms = now_ms;
+    }
+}
@@ -1,10 +1,9 @@
 use crate::message_monitor::MessageMonitoringResult;
 use crate::{MessageMonitoringParams, MonitorFetchWaitMode, MonitoringQueueInfo};
-use serde_json::Value;
 use std::collections::HashMap;
 use std::mem;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct BufferedMessage {
     pub message: MessageMonitoringParams,
     pub hash: String,
@@ -12,16 +11,16 @@ pub(crate) struct BufferedMessage {
 
 pub(crate) struct MonitoringQueue {
     pub buffered: Vec<BufferedMessage>,
-    pub resolving: HashMap<String, Option<Value>>,
+    pub resolving: HashMap<String, MessageMonitoringParams>,
     pub results: Vec<MessageMonitoringResult>,
 }
 
 impl MonitoringQueue {
     pub fn resolve(&mut self, results: &Vec<MessageMonitoringResult>) {
         for result in results {
-            if let Some(user_data) = self.resolving.remove(&result.hash) {
+            if let Some(message) = self.resolving.remove(&result.hash) {
                 let mut result = result.clone();
-                result.user_data = user_data;
+                result.user_data = message.user_data;
                 self.results.push(result);
             }
         }
@@ -1,5 +1,5 @@
 use crate::message_monitor::{CellFromBoc, MessageMonitoringParams, MessageMonitoringResult};
-use crate::{error, Error, MessageMonitorSdkServices, NetSubscription};
+use crate::{error, Error, MessageMonitorSdkServices, MessageMonitorStorage, NetSubscription};
 use base64::Engine;
 use std::collections::{HashMap, HashSet};
 use std::future::Future;
@@ -154,3 +154,26 @@ impl MessageMonitorSdkServices for MockSdkServices {
             .as_millis() as u64
     }
 }
+
+#[derive(Clone, Default)]
+pub struct MockMonitorStorage {
+    snapshot: Arc<RwLock<Option<String>>>,
+}
+
+impl MockMonitorStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl MessageMonitorStorage for MockMonitorStorage {
+    async fn load_queues(&self) -> error::Result<Option<String>> {
+        Ok(self.snapshot.read().unwrap().clone())
+    }
+
+    async fn save_queues(&self, snapshot: String) -> error::Result<()> {
+        *self.snapshot.write().unwrap() = Some(snapshot);
+        Ok(())
+    }
+}
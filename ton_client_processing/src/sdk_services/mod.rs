@@ -4,7 +4,7 @@ mod mock_sdk_services;
 use crate::error;
 use crate::message_monitor::{MessageMonitoringParams, MessageMonitoringResult};
 #[cfg(test)]
-pub use mock_sdk_services::MockSdkServices;
+pub use mock_sdk_services::{MockMonitorStorage, MockSdkServices};
 use std::future::Future;
 use ton_types::Cell;
 
@@ -27,3 +27,17 @@ pub trait MessageMonitorSdkServices {
 
     fn cell_from_boc(&self, boc: &str, name: &str) -> error::Result<Cell>;
 }
+
+/// Durable storage of the message monitor queues.
+///
+/// Message monitor saves the snapshot of its queues after each change and
+/// restores it on creation, so the messages registered before the restart
+/// are monitored further and their results can be fetched.
+#[async_trait]
+pub trait MessageMonitorStorage: Send + Sync {
+    /// Loads the last saved snapshot of the monitor queues.
+    async fn load_queues(&self) -> error::Result<Option<String>>;
+
+    /// Saves the snapshot of the monitor queues.
+    async fn save_queues(&self, snapshot: String) -> error::Result<()>;
+}
@@ -2,7 +2,7 @@ use crate::message_monitor::{
     MessageMonitor, MessageMonitoringParams, MessageMonitoringResult, MessageMonitoringStatus,
     MessageMonitoringTransaction, MonitorFetchWaitMode,
 };
-use crate::sdk_services::MockSdkServices;
+use crate::sdk_services::{MockMonitorStorage, MockSdkServices};
 use crate::MonitoredMessage;
 use std::mem;
 use std::sync::{Arc, RwLock};
@@ -230,6 +230,53 @@ async fn test_buffering() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_restore_queues() {
+    let storage = MockMonitorStorage::new();
+    let mon = MessageMonitor::with_storage(sdk_services(), Arc::new(storage.clone()));
+    let mut with_user_data = msg(2, 2);
+    with_user_data.user_data = Some(json!({ "id": 2 }));
+    mon.monitor_messages("1", vec![msg(1, 1), with_user_data]).unwrap();
+    sleep(Duration::from_millis(1100)).await;
+    drop(mon);
+
+    // Monitor is recreated (e.g. after the application restart) with the same storage
+    let api = sdk_services();
+    let mon = MessageMonitor::with_storage(api.clone(), Arc::new(storage.clone()));
+    let results = mon
+        .fetch_next_monitor_results("1", MonitorFetchWaitMode::NoWait)
+        .await
+        .unwrap();
+    assert_eq!(results, vec![]);
+    let info = mon.get_queue_info("1").unwrap();
+    assert_eq!(info.unresolved, 2);
+
+    api.add_recent_ext_in_messages(vec![
+        msg_res(1, MessageMonitoringStatus::Finalized),
+        msg_res(2, MessageMonitoringStatus::Finalized),
+    ]);
+    let results = mon
+        .fetch_next_monitor_results("1", MonitorFetchWaitMode::All)
+        .await
+        .unwrap();
+    let mut expected = msg_res(2, MessageMonitoringStatus::Finalized);
+    expected.user_data = Some(json!({ "id": 2 }));
+    assert_eq!(
+        sorted(results, |x| &x.hash),
+        vec![msg_res(1, MessageMonitoringStatus::Finalized), expected]
+    );
+
+    // Fetched results are removed from the saved snapshot
+    sleep(Duration::from_millis(100)).await;
+    let mon = MessageMonitor::with_storage(sdk_services(), Arc::new(storage));
+    let results = mon
+        .fetch_next_monitor_results("1", MonitorFetchWaitMode::NoWait)
+        .await
+        .unwrap();
+    assert_eq!(results, vec![]);
+    assert_eq!(mon.get_queue_info("1").unwrap().unresolved, 0);
+}
+
 fn hash(n: usize) -> String {
     UInt256::from_be_bytes(&n.to_be_bytes()).as_hex_string()
 }
@@ -0,0 +1,34 @@
+/*
+* Copyright 2018-2021 TON Labs LTD.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific TON DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::json_helper;
+
+use ton_block::{AccountStatus, MsgAddressInt};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Account {
+    #[serde(with = "json_helper::address")]
+    pub id: MsgAddressInt,
+    #[serde(with = "json_helper::account_status")]
+    pub acc_type: AccountStatus,
+    #[serde(with = "json_helper::uint")]
+    pub balance: u64,
+    #[serde(default)]
+    pub last_paid: Option<u32>,
+    #[serde(default)]
+    pub code_hash: Option<String>,
+    #[serde(default)]
+    pub data_hash: Option<String>,
+    #[serde(default)]
+    pub boc: Option<String>,
+}
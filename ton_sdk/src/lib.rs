@@ -40,6 +40,9 @@ pub use transaction::{Transaction, TransactionFees, TransactionId};
 mod block;
 pub use block::{Block, MsgDescr};
 
+mod account;
+pub use account::Account;
+
 pub mod types;
 pub use types::BlockId;
 